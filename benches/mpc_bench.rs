@@ -0,0 +1,75 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use mpc_exploration::mpc::{
+    RecoveryStrategy, Share, recover_secret_with_strategy, split_secret_with_rng,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Prime of the addition protocol
+const PRIME: u64 = 1_000_000_007;
+
+const PARTICIPANTS_COUNTS: [u8; 4] = [3, 10, 50, 100];
+
+/// Seed of the generator of the secrets and coefficients, so that every run measures the same computations
+const SEED: u64 = 42;
+
+fn bench_split_secret(c: &mut Criterion) {
+    let mut group = c.benchmark_group("split_secret");
+    for participants_count in PARTICIPANTS_COUNTS {
+        let points = (1..=participants_count).collect::<Vec<u8>>();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(participants_count),
+            &points,
+            |b, points| {
+                let mut rng = StdRng::seed_from_u64(SEED);
+                b.iter(|| {
+                    let secret = rng.random::<u64>() % PRIME;
+                    split_secret_with_rng(
+                        black_box(secret),
+                        black_box(points),
+                        None,
+                        PRIME,
+                        &mut rng,
+                    )
+                    .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_recover_secret(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recover_secret");
+    for participants_count in PARTICIPANTS_COUNTS {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let points = (1..=participants_count).collect::<Vec<u8>>();
+        let secret = rng.random::<u64>() % PRIME;
+        let mut shares = split_secret_with_rng(secret, &points, None, PRIME, &mut rng)
+            .unwrap()
+            .into_iter()
+            .map(|(point, value)| Share { point, value })
+            .collect::<Vec<Share>>();
+        shares.sort_by_key(|share| share.point);
+        for (name, strategy) in [
+            ("full_polynomial", RecoveryStrategy::FullPolynomial),
+            ("direct", RecoveryStrategy::Direct),
+            ("montgomery", RecoveryStrategy::Montgomery),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(name, participants_count),
+                &shares,
+                |b, shares| {
+                    b.iter(|| {
+                        recover_secret_with_strategy(black_box(shares), PRIME, strategy).unwrap()
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_split_secret, bench_recover_secret);
+criterion_main!(benches);
@@ -36,7 +36,13 @@ fn main() {
         let url = format!("{}/additions", peer_url);
         let res = client
             .post(&url)
-            .json(&CreateProcessHttpBody { process_id })
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
             .send();
         match res {
             Ok(response) => {
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use mpc_exploration::mpc::{Share, recover_secret};
+
+/// Prime of the addition protocol, used if no prime is given
+const DEFAULT_PRIME: u64 = 1_000_000_007;
+
+// This binary reconstructs a secret from a JSON file of shares, e.g. for the offline debugging of a failed computation.
+// The file contains an array of shares, `[{ "point": 1, "value": 123 }, ...]`.
+// Run via
+// ```
+// cargo run --bin reconstruct -- file=<path> [prime=<prime>]
+// ```
+fn main() {
+    match reconstruct() {
+        Ok(secret) => println!("{secret}"),
+        Err(e) => {
+            eprintln!("Failed to reconstruct the secret: {e:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn reconstruct() -> Result<u64, anyhow::Error> {
+    let argument = |key: &str| {
+        std::env::args().find_map(|arg| arg.strip_prefix(&format!("{key}=")).map(str::to_string))
+    };
+    let path = argument("file")
+        .ok_or_else(|| anyhow::anyhow!("file argument is required, e.g., file=shares.json"))?;
+    let prime = match argument("prime") {
+        Some(prime) => prime
+            .parse::<u64>()
+            .map_err(|e| anyhow::anyhow!("invalid prime `{prime}`: {e}"))?,
+        None => DEFAULT_PRIME,
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("unable to read shares file `{path}`: {e}"))?;
+    let shares = serde_json::from_str::<Vec<Share>>(&content)
+        .map_err(|e| anyhow::anyhow!("invalid shares file `{path}`: {e}"))?;
+    if shares.is_empty() {
+        return Err(anyhow::anyhow!("no share in `{path}`"));
+    }
+    // Interpolation through two shares at the same point fails with an obscure error, they are reported beforehand
+    let mut points = HashSet::new();
+    if let Some(duplicate) = shares.iter().find(|share| !points.insert(share.point)) {
+        return Err(anyhow::anyhow!(
+            "duplicate point {}, each share must be at a distinct point",
+            duplicate.point
+        ));
+    }
+
+    recover_secret(&shares, prime)
+}
@@ -0,0 +1,122 @@
+use std::{
+    fmt::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Completion of an addition process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessCompletion {
+    pub process_id: Uuid,
+    pub final_sum: u64,
+}
+
+/// Broadcasts the completions of addition processes, e.g. to clients awaiting a process.
+///
+/// Completions published while no one is subscribed are dropped.
+/// A subscriber lagging behind by more than the capacity misses the oldest completions, they are counted as lagged.
+pub struct ProcessCompletions {
+    sender: broadcast::Sender<ProcessCompletion>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl ProcessCompletions {
+    /// # Arguments
+    /// * `capacity` - The number of completions kept for lagging subscribers, non zero.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            lagged: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn publish(&self, completion: ProcessCompletion) {
+        // An error only means that there is no subscriber
+        let _ = self.sender.send(completion);
+    }
+
+    /// Subscribes to the completions published from now on.
+    pub fn subscribe(&self) -> CompletionsSubscription {
+        CompletionsSubscription {
+            receiver: self.sender.subscribe(),
+            lagged: self.lagged.clone(),
+        }
+    }
+
+    /// Number of completions missed by lagging subscribers, summed over the subscribers.
+    pub fn lagged_completions(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    /// Renders the completions metrics in the Prometheus text exposition format.
+    pub fn render_metrics(&self, output: &mut String) {
+        let name = "process_completions_lagged_total";
+        let _ = writeln!(
+            output,
+            "# HELP {name} Number of process completions missed by lagging subscribers"
+        );
+        let _ = writeln!(output, "# TYPE {name} counter");
+        let _ = writeln!(output, "{name} {}", self.lagged_completions());
+    }
+}
+
+impl Default for ProcessCompletions {
+    fn default() -> Self {
+        Self::new(128)
+    }
+}
+
+/// Subscription to the completions, missed completions are reported when lagging behind.
+pub struct CompletionsSubscription {
+    receiver: broadcast::Receiver<ProcessCompletion>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl CompletionsSubscription {
+    /// Receives the next completion, a lag is counted and logged before being returned.
+    pub async fn recv(&mut self) -> Result<ProcessCompletion, broadcast::error::RecvError> {
+        let result = self.receiver.recv().await;
+        if let Err(broadcast::error::RecvError::Lagged(missed)) = &result {
+            self.lagged.fetch_add(*missed, Ordering::Relaxed);
+            tracing::warn!(
+                "A completions subscriber lagged behind, {missed} completions were dropped, consider increasing `COMPLETION_EVENT_BUFFER`"
+            );
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_is_reported() {
+        let completions = ProcessCompletions::new(2);
+        let mut subscription = completions.subscribe();
+        for final_sum in 0..5 {
+            completions.publish(ProcessCompletion {
+                process_id: Uuid::new_v4(),
+                final_sum,
+            });
+        }
+
+        assert!(matches!(
+            subscription.recv().await,
+            Err(broadcast::error::RecvError::Lagged(3))
+        ));
+        assert_eq!(completions.lagged_completions(), 3);
+        // The completions kept within the capacity are still received
+        assert_eq!(subscription.recv().await.unwrap().final_sum, 3);
+
+        let mut output = String::new();
+        completions.render_metrics(&mut output);
+        assert!(output.contains("process_completions_lagged_total 3\n"));
+    }
+}
@@ -1,47 +1,182 @@
 use crate::mpc::{self, Share};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 use uuid::Uuid;
 
+pub mod completion;
 pub mod notifier;
 pub mod orchestrator;
+pub mod rejoin;
 pub mod repository;
+pub mod retention;
+pub mod standby;
+pub mod transcript;
 
 const PRIME: u64 = 1_000_000_007;
+/// Inputs are drawn from `u16`
+const INPUT_MAX: u64 = u16::MAX as u64;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AdditionProcess {
     AwaitingPeerShares(AwaitingPeerSharesProcess),
     AwaitingPeerSharesSum(AwaitingPeerSharesSumProcess),
+    Reconstructed(ReconstructedProcess),
     Completed(CompletedProcess),
+    Unrecoverable(UnrecoverableProcess),
+    Tampered(TamperedProcess),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InputShares {
     pub input: u64,
     pub own_share: u64,
     pub shares_to_send: HashMap<u8, u64>,
+    /// Number of shares needed to recover the input if it is shared with a lower degree polynomial for tampering detection, every share is needed otherwise
+    #[serde(default)]
+    pub verification_threshold: Option<usize>,
+    /// Whether the input is a signed integer encoded with [`mpc::encode_signed`], the sum is then decoded as a signed integer
+    #[serde(default)]
+    pub signed: bool,
 }
 
-#[derive(Clone)]
+impl InputShares {
+    /// IDs of the peers participating in the process, fixed at the creation of the process.
+    pub fn peer_ids(&self) -> HashSet<u8> {
+        self.shares_to_send.keys().cloned().collect()
+    }
+
+    /// Whether the sum of the inputs of the participants may wrap around the prime, see [`sum_may_wrap`].
+    pub fn sum_may_wrap(&self) -> bool {
+        sum_may_wrap(self.shares_to_send.len() + 1)
+    }
+}
+
+/// Input of the server in a process.
+///
+/// Signed inputs are encoded in the field, every participant of a process must use signed inputs for the sum to be decoded as a signed integer.
+#[derive(Clone, Copy, Debug)]
+pub enum ProcessInput {
+    Unsigned(u16),
+    Signed(i16),
+}
+
+/// Decodes a signed input, or the final sum of a process whose inputs are signed integers.
+///
+/// The decoded sum is the integer sum of the inputs as long as the sum does not wrap around the prime, see [`sum_may_wrap`].
+pub fn decode_signed(value: u64) -> i64 {
+    mpc::decode_signed(value, PRIME)
+}
+
+/// Reduces a share received from a peer in `[0, prime)`.
+///
+/// Peers are expected to send reduced shares, an unreduced one still represents the same field element and is stored reduced.
+pub fn reduce_share(share: u64) -> u64 {
+    share % PRIME
+}
+
+/// Whether the sum of the inputs of the given number of participants may wrap around the prime.
+///
+/// The final sum is computed modulo the prime, it equals the integer sum of the inputs only if the latter is lower than the prime.
+/// # Arguments
+/// * `participants_count` - The number of participants, the server included.
+pub fn sum_may_wrap(participants_count: usize) -> bool {
+    (participants_count as u128) * (INPUT_MAX as u128) >= PRIME as u128
+}
+
+/// Evaluation points of the shares of the peers.
+///
+/// A peer is evaluated at its ID unless mapped to another point, so that routing IDs and evaluation points can be chosen independently.
+/// The mapping must be the same on every node.
+#[derive(Clone, Debug, Default)]
+pub struct PeerPoints(HashMap<u8, u8>);
+
+impl PeerPoints {
+    /// # Arguments
+    /// * `points` - The evaluation point of each mapped peer ID, points must be distinct and non zero.
+    pub fn new(points: HashMap<u8, u8>) -> Result<Self, anyhow::Error> {
+        if points.values().any(|point| *point == 0) {
+            return Err(anyhow::anyhow!(
+                "point `0` is reserved, shares evaluated at 0 would disclose the secret"
+            ));
+        }
+        if points.values().collect::<HashSet<_>>().len() != points.len() {
+            return Err(anyhow::anyhow!("points must be distinct"));
+        }
+        Ok(Self(points))
+    }
+
+    /// Evaluation point of the shares of a peer, its ID if it is not mapped.
+    pub fn point(&self, peer_id: u8) -> u8 {
+        self.0.get(&peer_id).copied().unwrap_or(peer_id)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AwaitingPeerSharesProcess {
     pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Key of the process in the client application, e.g. a job ID
+    #[serde(default)]
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    #[serde(default)]
+    pub priority: u8,
     pub input_shares: InputShares,
     pub received_shares: HashMap<u8, u64>,
+    /// Explanation of the stall of the process, e.g. a peer persistently unable to produce our share
+    #[serde(default)]
+    pub diagnostic: Option<String>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AwaitingPeerSharesSumProcess {
     pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Key of the process in the client application, e.g. a job ID
+    #[serde(default)]
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    #[serde(default)]
+    pub priority: u8,
     pub input_shares: InputShares,
     pub received_shares: HashMap<u8, u64>,
     pub shares_sum: u64,
     pub received_shares_sums: HashMap<u8, u64>,
 }
 
-#[derive(Clone)]
+/// Process whose final sum is reconstructed but not confirmed yet, it is completed once enough peers report the same final sum.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReconstructedProcess {
+    pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Key of the process in the client application, e.g. a job ID
+    #[serde(default)]
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    #[serde(default)]
+    pub priority: u8,
+    pub reconstructed_at: chrono::DateTime<chrono::Utc>,
+    pub input_shares: InputShares,
+    pub received_shares: HashMap<u8, u64>,
+    pub shares_sum: u64,
+    pub received_shares_sums: HashMap<u8, u64>,
+    /// Final sum reconstructed by the server, awaiting its confirmation by the peers
+    pub candidate_sum: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CompletedProcess {
     pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Key of the process in the client application, e.g. a job ID
+    #[serde(default)]
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    #[serde(default)]
+    pub priority: u8,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
     pub input_shares: InputShares,
     pub received_shares: HashMap<u8, u64>,
     pub shares_sum: u64,
@@ -49,19 +184,121 @@ pub struct CompletedProcess {
     pub final_sum: u64,
 }
 
+/// Process whose state is known to be inconsistent between peers, e.g. because a peer lost its state and re-derived a new input.
+/// Carrying on would silently produce a wrong sum, the process is therefore stopped.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnrecoverableProcess {
+    pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Key of the process in the client application, e.g. a job ID
+    #[serde(default)]
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    #[serde(default)]
+    pub priority: u8,
+    pub input_shares: InputShares,
+    pub reason: String,
+}
+
+/// Process whose shares sums are inconsistent, i.e. recoveries of the final sum from two disjoint subsets of shares sums disagree.
+/// At least one shares sum has been tampered with, the final sum can not be trusted.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TamperedProcess {
+    pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Key of the process in the client application, e.g. a job ID
+    #[serde(default)]
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    #[serde(default)]
+    pub priority: u8,
+    pub input_shares: InputShares,
+    pub received_shares: HashMap<u8, u64>,
+    pub shares_sum: u64,
+    pub received_shares_sums: HashMap<u8, u64>,
+    pub reason: String,
+}
+
+impl CompletedProcess {
+    /// Duration between the creation and the completion of the process.
+    pub fn completion_duration(&self) -> std::time::Duration {
+        (self.completed_at - self.created_at)
+            .to_std()
+            .unwrap_or_default()
+    }
+}
+
 impl AdditionProcess {
     pub fn id(&self) -> Uuid {
         match self {
             AdditionProcess::AwaitingPeerShares(p) => p.id,
             AdditionProcess::AwaitingPeerSharesSum(p) => p.id,
+            AdditionProcess::Reconstructed(p) => p.id,
             AdditionProcess::Completed(p) => p.id,
+            AdditionProcess::Unrecoverable(p) => p.id,
+            AdditionProcess::Tampered(p) => p.id,
         }
     }
     pub fn input_shares(&self) -> &InputShares {
         match self {
             AdditionProcess::AwaitingPeerShares(p) => &p.input_shares,
             AdditionProcess::AwaitingPeerSharesSum(p) => &p.input_shares,
+            AdditionProcess::Reconstructed(p) => &p.input_shares,
             AdditionProcess::Completed(p) => &p.input_shares,
+            AdditionProcess::Unrecoverable(p) => &p.input_shares,
+            AdditionProcess::Tampered(p) => &p.input_shares,
+        }
+    }
+    pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            AdditionProcess::AwaitingPeerShares(p) => p.created_at,
+            AdditionProcess::AwaitingPeerSharesSum(p) => p.created_at,
+            AdditionProcess::Reconstructed(p) => p.created_at,
+            AdditionProcess::Completed(p) => p.created_at,
+            AdditionProcess::Unrecoverable(p) => p.created_at,
+            AdditionProcess::Tampered(p) => p.created_at,
+        }
+    }
+    pub fn external_key(&self) -> Option<&str> {
+        match self {
+            AdditionProcess::AwaitingPeerShares(p) => p.external_key.as_deref(),
+            AdditionProcess::AwaitingPeerSharesSum(p) => p.external_key.as_deref(),
+            AdditionProcess::Reconstructed(p) => p.external_key.as_deref(),
+            AdditionProcess::Completed(p) => p.external_key.as_deref(),
+            AdditionProcess::Unrecoverable(p) => p.external_key.as_deref(),
+            AdditionProcess::Tampered(p) => p.external_key.as_deref(),
+        }
+    }
+    pub fn priority(&self) -> u8 {
+        match self {
+            AdditionProcess::AwaitingPeerShares(p) => p.priority,
+            AdditionProcess::AwaitingPeerSharesSum(p) => p.priority,
+            AdditionProcess::Reconstructed(p) => p.priority,
+            AdditionProcess::Completed(p) => p.priority,
+            AdditionProcess::Unrecoverable(p) => p.priority,
+            AdditionProcess::Tampered(p) => p.priority,
+        }
+    }
+    /// Shares received from peers, `None` if the process is unrecoverable.
+    pub fn received_shares(&self) -> Option<&HashMap<u8, u64>> {
+        match self {
+            AdditionProcess::AwaitingPeerShares(p) => Some(&p.received_shares),
+            AdditionProcess::AwaitingPeerSharesSum(p) => Some(&p.received_shares),
+            AdditionProcess::Reconstructed(p) => Some(&p.received_shares),
+            AdditionProcess::Completed(p) => Some(&p.received_shares),
+            AdditionProcess::Unrecoverable(_) => None,
+            AdditionProcess::Tampered(p) => Some(&p.received_shares),
+        }
+    }
+
+    /// Shares sums received from the peers, `None` before the shares sum round.
+    pub fn received_shares_sums(&self) -> Option<&HashMap<u8, u64>> {
+        match self {
+            AdditionProcess::AwaitingPeerShares(_) | AdditionProcess::Unrecoverable(_) => None,
+            AdditionProcess::AwaitingPeerSharesSum(p) => Some(&p.received_shares_sums),
+            AdditionProcess::Reconstructed(p) => Some(&p.received_shares_sums),
+            AdditionProcess::Completed(p) => Some(&p.received_shares_sums),
+            AdditionProcess::Tampered(p) => Some(&p.received_shares_sums),
         }
     }
 }
@@ -73,6 +310,10 @@ impl AdditionProcess {
 pub struct CreateProcessRequest {
     pub process_id: uuid::Uuid,
     pub input_shares: InputShares,
+    /// Key of the process in the client application, it must be unique
+    pub external_key: Option<String>,
+    /// Priority of the process, higher priority processes are polled and their peer messages delivered first
+    pub priority: u8,
 }
 
 #[derive(Debug, Error)]
@@ -82,21 +323,53 @@ pub enum CreateProcessRequestError {
 }
 
 impl CreateProcessRequest {
+    /// # Arguments
+    /// * `process_id` - The ID of the process,
+    /// * `server_peer_id` - The peer ID of the server,
+    /// * `peer_ids` - The IDs of the participating peers,
+    /// * `verification_threshold` - The number of shares needed to recover the input if tampering detection is enabled,
+    /// * `input` - The input of the server, a random unsigned input is generated if not provided,
+    /// * `points` - The evaluation points of the shares of the peers.
     pub fn new(
         process_id: uuid::Uuid,
         server_peer_id: u8,
         peer_ids: &[u8],
+        verification_threshold: Option<usize>,
+        input: Option<ProcessInput>,
+        points: &PeerPoints,
     ) -> Result<Self, CreateProcessRequestError> {
-        let bootstrap = bootstrap_process(server_peer_id, peer_ids)?;
+        let bootstrap = bootstrap_process(
+            server_peer_id,
+            peer_ids,
+            verification_threshold,
+            input,
+            points,
+        )?;
         Ok(Self {
             process_id,
             input_shares: InputShares {
                 input: bootstrap.input,
                 own_share: bootstrap.own_share,
                 shares_to_send: bootstrap.shares_to_send,
+                verification_threshold,
+                signed: matches!(input, Some(ProcessInput::Signed(_))),
             },
+            external_key: None,
+            priority: 0,
         })
     }
+
+    /// Attaches the key of the process in the client application, the process can then be retrieved by this key.
+    pub fn with_external_key(mut self, external_key: Option<String>) -> Self {
+        self.external_key = external_key;
+        self
+    }
+
+    /// Sets the priority of the process, `0` by default.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
 }
 
 // ########################################################
@@ -114,6 +387,9 @@ pub struct ReceiveSharesRequest {
 
 #[derive(Debug, Error)]
 pub enum ReceiveSharesRequestError {
+    /// The peer is not a participant of the process, e.g. a peer added to the configuration after the creation of the process
+    #[error("peer {peer_id} does not participate in the process")]
+    UnexpectedPeer { peer_id: u8 },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -122,25 +398,33 @@ impl ReceiveSharesRequest {
     pub fn new(
         process: &AwaitingPeerSharesProcess,
         received_shares: HashMap<u8, u64>,
-        peers_count: usize,
     ) -> Result<Self, ReceiveSharesRequestError> {
-        let mut all_received_shares = process.received_shares.clone();
-        for (peer_id, share) in &received_shares {
-            all_received_shares.insert(*peer_id, *share);
+        // The participants are fixed at creation, a share of any other peer would corrupt the shares sum
+        if let Some(peer_id) = received_shares
+            .keys()
+            .find(|peer_id| !process.input_shares.shares_to_send.contains_key(peer_id))
+        {
+            return Err(ReceiveSharesRequestError::UnexpectedPeer { peer_id: *peer_id });
         }
-        if all_received_shares.len() < peers_count {
+        // A received share is never overwritten, the share of a peer already received is ignored
+        let received_shares = received_shares
+            .into_iter()
+            .filter(|(peer_id, _)| !process.received_shares.contains_key(peer_id))
+            .collect::<HashMap<u8, u64>>();
+        let mut all_received_shares = process.received_shares.clone();
+        all_received_shares.extend(&received_shares);
+        if all_received_shares.len() < process.input_shares.shares_to_send.len() {
             return Ok(Self {
                 process_id: process.id,
-                received_shares: all_received_shares,
+                received_shares,
                 computed_shares_sum: None,
             });
         }
-        let computed_shares_sum = all_received_shares
-            .values()
-            .map(|v| Into::<u128>::into(*v))
-            .sum::<u128>()
-            .wrapping_add(process.input_shares.own_share.into())
-            .rem_euclid(PRIME as u128) as u64;
+        let computed_shares_sum = mpc::sum_shares(
+            process.input_shares.own_share,
+            all_received_shares.values().copied(),
+            PRIME,
+        );
         Ok(Self {
             process_id: process.id,
             received_shares,
@@ -153,16 +437,33 @@ impl ReceiveSharesRequest {
 // ################### SHARES SUMS RECEPTION ###############
 // #########################################################
 
+/// Settings of the recovery of the final sum from the shares sums.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RecoverySettings {
+    /// Strategy recovering the final sum, every strategy recovers the same sum
+    pub strategy: mpc::RecoveryStrategy,
+    /// Whether every final sum is checked with [`mpc::check_recovered_secret`] before it is committed
+    pub self_check: bool,
+}
+
 pub struct ReceiveSharesSumsRequest {
     pub process_id: uuid::Uuid,
     /// Newly received shares sums from peers
     pub received_shares_sums: HashMap<u8, u64>,
     /// Computed final sum if all shares sums have been registered
     pub final_sum: Option<u64>,
+    /// Whether the computed final sum awaits its confirmation by the peers before the process is completed
+    pub awaiting_confirmation: bool,
 }
 
 #[derive(Debug, Error)]
 pub enum ReceiveSharesSumsRequestError {
+    #[error("tampering detected: {reason}")]
+    Tampered {
+        /// Every shares sum received from peers
+        received_shares_sums: HashMap<u8, u64>,
+        reason: String,
+    },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -173,34 +474,224 @@ impl ReceiveSharesSumsRequest {
         received_shares_sums: HashMap<u8, u64>,
         own_peer_id: u8,
         peers_count: usize,
+        points: &PeerPoints,
+        recovery: RecoverySettings,
+    ) -> Result<Self, ReceiveSharesSumsRequestError> {
+        Self::new_excluding(
+            process,
+            received_shares_sums,
+            own_peer_id,
+            peers_count,
+            points,
+            &HashSet::new(),
+            recovery,
+        )
+    }
+
+    /// Builds a request reconstructing the final sum without the shares sums of the excluded peers, e.g. peers whose shares sums are known to be corrupt.
+    ///
+    /// The shares sums of the excluded peers are still recorded but neither awaited nor used, the final sum is recovered from the remaining quorum.
+    /// It requires the redundancy of threshold sharing, i.e. a verification threshold, every shares sum is needed otherwise.
+    /// # Arguments
+    /// * `process` - The process awaiting peer shares sums,
+    /// * `received_shares_sums` - The shares sums received from the peers,
+    /// * `own_peer_id` - The peer ID of the server,
+    /// * `peers_count` - The number of peers of the process,
+    /// * `points` - The evaluation points of the shares of the peers,
+    /// * `excluded_peer_ids` - The peers whose shares sums are not used,
+    /// * `recovery` - The settings of the recovery of the final sum.
+    pub fn new_excluding(
+        process: &AwaitingPeerSharesSumProcess,
+        received_shares_sums: HashMap<u8, u64>,
+        own_peer_id: u8,
+        peers_count: usize,
+        points: &PeerPoints,
+        excluded_peer_ids: &HashSet<u8>,
+        recovery: RecoverySettings,
     ) -> Result<Self, ReceiveSharesSumsRequestError> {
         let mut all_received_shares_sums = process.received_shares_sums.clone();
         for (peer_id, share_sum) in &received_shares_sums {
             all_received_shares_sums.insert(*peer_id, *share_sum);
         }
-        if all_received_shares_sums.len() < peers_count {
+        let excluded_count = process
+            .input_shares
+            .shares_to_send
+            .keys()
+            .filter(|peer_id| excluded_peer_ids.contains(peer_id))
+            .count();
+        let used_shares_sums = all_received_shares_sums
+            .iter()
+            .filter(|(peer_id, _)| !excluded_peer_ids.contains(peer_id))
+            .collect::<Vec<_>>();
+        if used_shares_sums.len() < peers_count.saturating_sub(excluded_count) {
             return Ok(Self {
                 process_id: process.id,
                 received_shares_sums: all_received_shares_sums,
                 final_sum: None,
+                awaiting_confirmation: false,
             });
         }
 
         let mut all_sums_coordinates = vec![Share {
-            point: own_peer_id,
+            point: points.point(own_peer_id),
             value: process.shares_sum,
         }];
-        for (peer_id, share_sum) in &all_received_shares_sums {
+        for (peer_id, share_sum) in used_shares_sums {
             all_sums_coordinates.push(Share {
-                point: *peer_id,
+                point: points.point(*peer_id),
                 value: *share_sum,
             });
         }
-        let final_sum = mpc::recover_secret(&all_sums_coordinates, PRIME)?;
+        if excluded_count > 0 {
+            let Some(threshold) = process.input_shares.verification_threshold else {
+                return Err(anyhow::anyhow!(
+                    "shares sums of {excluded_count} peers can not be excluded without a verification threshold, every shares sum is needed"
+                )
+                .into());
+            };
+            if all_sums_coordinates.len() < threshold {
+                return Err(anyhow::anyhow!(
+                    "{} shares sums remain once the excluded peers are dropped, {threshold} are needed",
+                    all_sums_coordinates.len()
+                )
+                .into());
+            }
+        }
+        let final_sum = match process.input_shares.verification_threshold {
+            // Without enough shares sums for two disjoint subsets, the remaining quorum can not be verified
+            Some(threshold) if excluded_count > 0 && all_sums_coordinates.len() < 2 * threshold => {
+                mpc::recover_secret_with_strategy(&all_sums_coordinates, PRIME, recovery.strategy)?
+            }
+            Some(threshold) => {
+                match mpc::recover_secret_verified_with_strategy(
+                    &all_sums_coordinates,
+                    threshold,
+                    PRIME,
+                    recovery.strategy,
+                ) {
+                    Ok(final_sum) => final_sum,
+                    Err(e @ mpc::VerifiedRecoveryError::Disagreement { .. }) => {
+                        return Err(ReceiveSharesSumsRequestError::Tampered {
+                            received_shares_sums: all_received_shares_sums,
+                            reason: e.to_string(),
+                        });
+                    }
+                    Err(e) => return Err(anyhow::anyhow!(e).into()),
+                }
+            }
+            None => {
+                mpc::recover_secret_with_strategy(&all_sums_coordinates, PRIME, recovery.strategy)?
+            }
+        };
+        if recovery.self_check {
+            mpc::check_recovered_secret(
+                &all_sums_coordinates,
+                peers_count + 1 - excluded_count,
+                final_sum,
+                PRIME,
+            )
+            .map_err(|e| e.context(format!("checking final sum of process {}", process.id)))?;
+        }
         Ok(Self {
             process_id: process.id,
             received_shares_sums,
             final_sum: Some(final_sum),
+            awaiting_confirmation: false,
+        })
+    }
+
+    /// Keeps the computed final sum as a candidate awaiting the confirmation of the peers instead of completing the process.
+    pub fn with_confirmation(mut self, awaiting_confirmation: bool) -> Self {
+        self.awaiting_confirmation = awaiting_confirmation;
+        self
+    }
+}
+
+// ###########################################################
+// ################### FORCED COMPLETION #####################
+// ###########################################################
+
+#[derive(Debug, Error)]
+pub enum ForceCompleteRequestError {
+    #[error(
+        "insufficient shares sums to reconstruct the final sum: {available} available, {required} required"
+    )]
+    InsufficientSharesSums { available: usize, required: usize },
+    #[error("tampering detected: {reason}")]
+    Tampered {
+        /// Every shares sum received from peers
+        received_shares_sums: HashMap<u8, u64>,
+        reason: String,
+    },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl ReceiveSharesSumsRequest {
+    /// Builds a request completing the process with the shares sums received so far.
+    ///
+    /// This is a manual override of the regular flow, the reconstruction is only attempted if at least `threshold` shares sums, own one included, are available.
+    /// With a verification threshold and enough shares sums for two disjoint subsets, the reconstruction is verified as in the regular flow.
+    /// # Arguments
+    /// * `process` - The process awaiting peer shares sums,
+    /// * `own_peer_id` - The peer ID of the server,
+    /// * `threshold` - The minimum number of shares sums needed to reconstruct the final sum,
+    /// * `points` - The evaluation points of the shares of the peers,
+    /// * `recovery` - The settings of the recovery of the final sum.
+    pub fn force_complete(
+        process: &AwaitingPeerSharesSumProcess,
+        own_peer_id: u8,
+        threshold: usize,
+        points: &PeerPoints,
+        recovery: RecoverySettings,
+    ) -> Result<Self, ForceCompleteRequestError> {
+        let available = process.received_shares_sums.len() + 1;
+        if available < threshold {
+            return Err(ForceCompleteRequestError::InsufficientSharesSums {
+                available,
+                required: threshold,
+            });
+        }
+
+        let mut all_sums_coordinates = vec![Share {
+            point: points.point(own_peer_id),
+            value: process.shares_sum,
+        }];
+        for (peer_id, share_sum) in &process.received_shares_sums {
+            all_sums_coordinates.push(Share {
+                point: points.point(*peer_id),
+                value: *share_sum,
+            });
+        }
+        let final_sum = match process.input_shares.verification_threshold {
+            Some(verification_threshold)
+                if all_sums_coordinates.len() >= 2 * verification_threshold =>
+            {
+                match mpc::recover_secret_verified_with_strategy(
+                    &all_sums_coordinates,
+                    verification_threshold,
+                    PRIME,
+                    recovery.strategy,
+                ) {
+                    Ok(final_sum) => final_sum,
+                    Err(e @ mpc::VerifiedRecoveryError::Disagreement { .. }) => {
+                        return Err(ForceCompleteRequestError::Tampered {
+                            received_shares_sums: process.received_shares_sums.clone(),
+                            reason: e.to_string(),
+                        });
+                    }
+                    Err(e) => return Err(anyhow::anyhow!(e).into()),
+                }
+            }
+            _ => {
+                mpc::recover_secret_with_strategy(&all_sums_coordinates, PRIME, recovery.strategy)?
+            }
+        };
+        Ok(Self {
+            process_id: process.id,
+            received_shares_sums: HashMap::new(),
+            final_sum: Some(final_sum),
+            awaiting_confirmation: false,
         })
     }
 }
@@ -217,17 +708,46 @@ struct BootstrapProcessResult {
 fn bootstrap_process(
     server_peer_id: u8,
     peer_ids: &[u8],
+    verification_threshold: Option<usize>,
+    input: Option<ProcessInput>,
+    points: &PeerPoints,
 ) -> Result<BootstrapProcessResult, anyhow::Error> {
-    let input = rand::random::<u16>().into();
-    let all_ids = {
-        let mut ids = peer_ids.to_vec();
-        ids.push(server_peer_id);
-        ids
+    let input = match input {
+        Some(ProcessInput::Unsigned(input)) => input.into(),
+        Some(ProcessInput::Signed(input)) => mpc::encode_signed(input.into(), PRIME),
+        None => rand::random::<u16>().into(),
     };
-    let mut input_shares = mpc::split_secret(input, &all_ids, PRIME);
-    let own_share = input_shares.remove(&server_peer_id).ok_or(anyhow::anyhow!(
-        "own share missing for peer id {server_peer_id}"
-    ))?;
+    // A misconfigured peer sharing the ID of the server is not sent any share
+    let peer_ids = peer_ids
+        .iter()
+        .filter(|peer_id| **peer_id != server_peer_id)
+        .copied()
+        .collect::<HashSet<u8>>();
+    let all_points = {
+        let mut all_points = peer_ids
+            .iter()
+            .map(|peer_id| points.point(*peer_id))
+            .collect::<Vec<_>>();
+        all_points.push(points.point(server_peer_id));
+        all_points
+    };
+    if all_points.iter().collect::<HashSet<_>>().len() != all_points.len() {
+        return Err(anyhow::anyhow!(
+            "participants must be evaluated at distinct points, got {all_points:?}"
+        ));
+    }
+    // A verification threshold of `t` shares is reached with a polynomial of degree `t - 1`
+    let degree = verification_threshold.map(|threshold| threshold.saturating_sub(1));
+    let mut shares_by_point = mpc::split_secret(input, &all_points, degree, PRIME)?;
+    let own_share = shares_by_point
+        .remove(&points.point(server_peer_id))
+        .ok_or(anyhow::anyhow!(
+            "own share missing for peer id {server_peer_id}"
+        ))?;
+    let input_shares = peer_ids
+        .iter()
+        .map(|peer_id| (*peer_id, shares_by_point[&points.point(*peer_id)]))
+        .collect();
 
     Ok(BootstrapProcessResult {
         input,
@@ -235,3 +755,400 @@ fn bootstrap_process(
         shares_to_send: input_shares,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn awaiting_shares_sum_process(
+        shares_sums: &HashMap<u8, u64>,
+        own_peer_id: u8,
+        peer_ids: &[u8],
+    ) -> AwaitingPeerSharesSumProcess {
+        AwaitingPeerSharesSumProcess {
+            id: Uuid::new_v4(),
+            created_at: chrono::Utc::now(),
+            external_key: None,
+            priority: 0,
+            input_shares: InputShares {
+                input: 0,
+                own_share: 0,
+                shares_to_send: HashMap::new(),
+                verification_threshold: None,
+                signed: false,
+            },
+            received_shares: HashMap::new(),
+            shares_sum: shares_sums[&own_peer_id],
+            received_shares_sums: peer_ids
+                .iter()
+                .map(|peer_id| (*peer_id, shares_sums[peer_id]))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_sum_may_wrap() {
+        // Up to 256 participants, the sum of `u16` inputs stays below the prime
+        assert!(!sum_may_wrap(3));
+        assert!(!sum_may_wrap(256));
+        assert!(!sum_may_wrap((PRIME / INPUT_MAX) as usize));
+        assert!(sum_may_wrap((PRIME / INPUT_MAX) as usize + 1));
+        assert!(sum_may_wrap(20_000));
+    }
+
+    #[test]
+    fn test_share_of_a_non_participant_is_rejected() {
+        let process = AwaitingPeerSharesProcess {
+            id: Uuid::new_v4(),
+            created_at: chrono::Utc::now(),
+            external_key: None,
+            priority: 0,
+            input_shares: InputShares {
+                input: 0,
+                own_share: 0,
+                shares_to_send: HashMap::from([(2, 0), (3, 0)]),
+                verification_threshold: None,
+                signed: false,
+            },
+            received_shares: HashMap::new(),
+            diagnostic: None,
+        };
+
+        let request = ReceiveSharesRequest::new(&process, HashMap::from([(2, 5)])).unwrap();
+        assert_eq!(request.received_shares, HashMap::from([(2, 5)]));
+        match ReceiveSharesRequest::new(&process, HashMap::from([(2, 5), (4, 7)])) {
+            Err(ReceiveSharesRequestError::UnexpectedPeer { peer_id }) => assert_eq!(peer_id, 4),
+            _ => panic!("expected the share of peer 4 to be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_received_share_is_not_overwritten() {
+        let process = AwaitingPeerSharesProcess {
+            id: Uuid::new_v4(),
+            created_at: chrono::Utc::now(),
+            external_key: None,
+            priority: 0,
+            input_shares: InputShares {
+                input: 0,
+                own_share: 1,
+                shares_to_send: HashMap::from([(2, 0), (3, 0)]),
+                verification_threshold: None,
+                signed: false,
+            },
+            received_shares: HashMap::from([(2, 5)]),
+            diagnostic: None,
+        };
+
+        // A received share is kept, the share of peer 2 does not alter the shares sum
+        let request = ReceiveSharesRequest::new(&process, HashMap::from([(2, 9), (3, 7)])).unwrap();
+        assert_eq!(request.received_shares, HashMap::from([(3, 7)]));
+        assert_eq!(request.computed_shares_sum, Some(13));
+    }
+
+    #[test]
+    fn test_force_complete() {
+        let sum = rand::random::<u64>() % PRIME;
+        let shares_sums = mpc::split_secret(sum, &[1, 2, 3], None, PRIME).unwrap();
+
+        let process = awaiting_shares_sum_process(&shares_sums, 1, &[2]);
+        match ReceiveSharesSumsRequest::force_complete(
+            &process,
+            1,
+            3,
+            &PeerPoints::default(),
+            RecoverySettings::default(),
+        ) {
+            Err(ForceCompleteRequestError::InsufficientSharesSums {
+                available,
+                required,
+            }) => {
+                assert_eq!(available, 2);
+                assert_eq!(required, 3);
+            }
+            _ => panic!("expected insufficient shares sums error"),
+        }
+
+        let process = awaiting_shares_sum_process(&shares_sums, 1, &[2, 3]);
+        let request = ReceiveSharesSumsRequest::force_complete(
+            &process,
+            1,
+            3,
+            &PeerPoints::default(),
+            RecoverySettings::default(),
+        )
+        .unwrap();
+        assert_eq!(request.final_sum, Some(sum));
+    }
+
+    #[test]
+    fn test_force_complete_verifies_the_shares_sums_with_a_threshold() {
+        let sum = rand::random::<u64>() % PRIME;
+        let mut shares_sums = mpc::split_secret(sum, &[1, 2, 3, 4], Some(1), PRIME).unwrap();
+        let mut process = awaiting_shares_sum_process(&shares_sums, 1, &[2, 3, 4]);
+        process.input_shares.verification_threshold = Some(2);
+        let request = ReceiveSharesSumsRequest::force_complete(
+            &process,
+            1,
+            2,
+            &PeerPoints::default(),
+            RecoverySettings::default(),
+        )
+        .unwrap();
+        assert_eq!(request.final_sum, Some(sum));
+
+        *shares_sums.get_mut(&4).unwrap() += 1;
+        let mut process = awaiting_shares_sum_process(&shares_sums, 1, &[2, 3, 4]);
+        process.input_shares.verification_threshold = Some(2);
+        match ReceiveSharesSumsRequest::force_complete(
+            &process,
+            1,
+            2,
+            &PeerPoints::default(),
+            RecoverySettings::default(),
+        ) {
+            Err(ForceCompleteRequestError::Tampered {
+                received_shares_sums,
+                ..
+            }) => assert_eq!(received_shares_sums.len(), 3),
+            _ => panic!("expected the tampered shares sum to be detected"),
+        }
+    }
+
+    #[test]
+    fn test_tampered_shares_sum_is_detected() {
+        let sum = rand::random::<u64>() % PRIME;
+        let mut shares_sums = mpc::split_secret(sum, &[1, 2, 3, 4], Some(1), PRIME).unwrap();
+        let mut process = awaiting_shares_sum_process(&shares_sums, 1, &[]);
+        process.input_shares.verification_threshold = Some(2);
+
+        let request = ReceiveSharesSumsRequest::new(
+            &process,
+            shares_sums
+                .iter()
+                .filter(|(peer_id, _)| **peer_id != 1)
+                .map(|(peer_id, shares_sum)| (*peer_id, *shares_sum))
+                .collect(),
+            1,
+            3,
+            &PeerPoints::default(),
+            RecoverySettings::default(),
+        )
+        .unwrap();
+        assert_eq!(request.final_sum, Some(sum));
+
+        *shares_sums.get_mut(&3).unwrap() += 1;
+        let result = ReceiveSharesSumsRequest::new(
+            &process,
+            shares_sums
+                .iter()
+                .filter(|(peer_id, _)| **peer_id != 1)
+                .map(|(peer_id, shares_sum)| (*peer_id, *shares_sum))
+                .collect(),
+            1,
+            3,
+            &PeerPoints::default(),
+            RecoverySettings::default(),
+        );
+        match result {
+            Err(ReceiveSharesSumsRequestError::Tampered {
+                received_shares_sums,
+                ..
+            }) => assert_eq!(received_shares_sums.len(), 3),
+            _ => panic!("expected tampering to be detected"),
+        }
+    }
+
+    #[test]
+    fn test_reconstruction_excluding_a_corrupt_shares_sum() {
+        let sum = rand::random::<u64>() % PRIME;
+        let mut shares_sums = mpc::split_secret(sum, &[1, 2, 3, 4], Some(1), PRIME).unwrap();
+        *shares_sums.get_mut(&3).unwrap() += 1;
+        let mut process = awaiting_shares_sum_process(&shares_sums, 1, &[]);
+        process.input_shares.shares_to_send = HashMap::from([(2, 0), (3, 0), (4, 0)]);
+        process.input_shares.verification_threshold = Some(2);
+        let received_shares_sums = shares_sums
+            .iter()
+            .filter(|(peer_id, _)| **peer_id != 1)
+            .map(|(peer_id, shares_sum)| (*peer_id, *shares_sum))
+            .collect::<HashMap<u8, u64>>();
+
+        // The remaining shares sums are awaited, the excluded one is not
+        let request = ReceiveSharesSumsRequest::new_excluding(
+            &process,
+            HashMap::from([(2, shares_sums[&2])]),
+            1,
+            3,
+            &PeerPoints::default(),
+            &HashSet::from([3]),
+            RecoverySettings::default(),
+        )
+        .unwrap();
+        assert_eq!(request.final_sum, None);
+
+        let request = ReceiveSharesSumsRequest::new_excluding(
+            &process,
+            received_shares_sums.clone(),
+            1,
+            3,
+            &PeerPoints::default(),
+            &HashSet::from([3]),
+            RecoverySettings::default(),
+        )
+        .unwrap();
+        assert_eq!(request.final_sum, Some(sum));
+
+        // Exclusion relies on the redundancy of threshold sharing
+        process.input_shares.verification_threshold = None;
+        assert!(
+            ReceiveSharesSumsRequest::new_excluding(
+                &process,
+                received_shares_sums,
+                1,
+                3,
+                &PeerPoints::default(),
+                &HashSet::from([3]),
+                RecoverySettings::default(),
+            )
+            .is_err()
+        );
+    }
+
+    /// Log writer appending to a shared buffer
+    struct LogBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_self_check_logs_the_lagrange_terms() {
+        let recovery = RecoverySettings {
+            self_check: true,
+            ..RecoverySettings::default()
+        };
+        let logs = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer({
+                let logs = logs.clone();
+                move || LogBuffer(logs.clone())
+            })
+            .finish();
+
+        // Shares sums of the polynomial `5 + x`, the Lagrange weights at zero of points 1 and 2 are 2 and -1
+        let shares_sums = HashMap::from([(1, 6), (2, 7)]);
+        let process = awaiting_shares_sum_process(&shares_sums, 1, &[]);
+        let request = tracing::subscriber::with_default(subscriber, || {
+            ReceiveSharesSumsRequest::new(
+                &process,
+                HashMap::from([(2, 7)]),
+                1,
+                1,
+                &PeerPoints::default(),
+                recovery,
+            )
+            .unwrap()
+        });
+        assert_eq!(request.final_sum, Some(5));
+
+        let logs = String::from_utf8(logs.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("share at point 1 with value 6 contributes the term 12"));
+        assert!(logs.contains(&format!(
+            "share at point 2 with value 7 contributes the term {}",
+            PRIME - 7
+        )));
+        assert!(logs.contains("secret 5 recovered from 2 shares"));
+
+        // A recovery from an unexpected number of shares fails the check
+        let process = awaiting_shares_sum_process(&HashMap::from([(1, 6), (2, 7), (3, 8)]), 1, &[]);
+        assert!(
+            ReceiveSharesSumsRequest::new(
+                &process,
+                HashMap::from([(2, 7), (3, 8)]),
+                1,
+                1,
+                &PeerPoints::default(),
+                recovery,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_addition_with_mapped_points() {
+        let points = PeerPoints::new(HashMap::from([(1, 7), (2, 3), (3, 200)])).unwrap();
+        let peer_ids = [1, 2, 3];
+        let requests = peer_ids
+            .iter()
+            .map(|peer_id| {
+                let other_ids = peer_ids
+                    .iter()
+                    .filter(|id| *id != peer_id)
+                    .copied()
+                    .collect::<Vec<_>>();
+                let request = CreateProcessRequest::new(
+                    Uuid::new_v4(),
+                    *peer_id,
+                    &other_ids,
+                    None,
+                    None,
+                    &points,
+                )
+                .unwrap();
+                (*peer_id, request)
+            })
+            .collect::<HashMap<u8, _>>();
+        let shares_sums = peer_ids
+            .iter()
+            .map(|peer_id| {
+                let received_shares = peer_ids
+                    .iter()
+                    .filter(|id| *id != peer_id)
+                    .map(|id| requests[id].input_shares.shares_to_send[peer_id]);
+                let shares_sum = mpc::sum_shares(
+                    requests[peer_id].input_shares.own_share,
+                    received_shares,
+                    PRIME,
+                );
+                (*peer_id, shares_sum)
+            })
+            .collect::<HashMap<u8, u64>>();
+
+        let process = awaiting_shares_sum_process(&shares_sums, 1, &[2, 3]);
+        let request = ReceiveSharesSumsRequest::new(
+            &process,
+            HashMap::new(),
+            1,
+            2,
+            &points,
+            RecoverySettings::default(),
+        )
+        .unwrap();
+        let expected_sum = requests
+            .values()
+            .map(|request| request.input_shares.input)
+            .sum::<u64>()
+            % PRIME;
+        assert_eq!(request.final_sum, Some(expected_sum));
+    }
+
+    #[test]
+    fn test_invalid_peer_points() {
+        assert!(PeerPoints::new(HashMap::from([(1, 0)])).is_err());
+        assert!(PeerPoints::new(HashMap::from([(1, 5), (2, 5)])).is_err());
+        // Peer 2 is not mapped and is evaluated at its ID, which is the point of peer 1
+        let points = PeerPoints::new(HashMap::from([(1, 2)])).unwrap();
+        assert!(
+            CreateProcessRequest::new(Uuid::new_v4(), 1, &[2, 3], None, None, &points).is_err()
+        );
+    }
+}
@@ -1,32 +1,59 @@
-/// A notifier trait and its implementation for sending pings through a channel.
+use tokio_util::sync::CancellationToken;
+
+use super::orchestrator::OrchestratorCommand;
+
+/// A notifier trait and its implementation for sending commands through a channel.
 /// This is used to notify other parts of the system at regular intervals or on demand.
+#[async_trait::async_trait]
 pub trait Notifier: Send + Sync {
-    /// Sends a ping notification.
+    /// Sends a ping notification, i.e. a `PollAll` command.
     /// This method attempts to send a ping through the associated channel.
     /// The method does not block; it uses a non-blocking send.
-    /// If the channel is full, the ping is silently skipped.
+    /// If the channel is full, the ping is silently skipped: a sweep is already pending and covers the work of the ping.
     /// If the channel is closed, a warning is logged.
     fn ping(&self);
+
+    /// Sends a command, waiting for room in the channel if it is full.
+    /// If the channel is closed, a warning is logged.
+    /// # Arguments
+    /// * `command` - The command to send.
+    async fn send_command(&self, command: OrchestratorCommand);
 }
 
 pub struct IntervalPing {
-    channel_sender: tokio::sync::mpsc::Sender<()>,
+    channel_sender: tokio::sync::mpsc::Sender<OrchestratorCommand>,
+    shutdown: CancellationToken,
 }
 impl IntervalPing {
-    pub fn new(channel_sender: tokio::sync::mpsc::Sender<()>) -> Self {
-        Self { channel_sender }
+    pub fn new(channel_sender: tokio::sync::mpsc::Sender<OrchestratorCommand>) -> Self {
+        Self {
+            channel_sender,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Sets the token stopping the interval ping loop once cancelled, the loop otherwise runs until the channel is closed.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
     }
 
     /// Runs the interval ping loop, sending pings at the specified interval.
     /// This method should be run in an asynchronous context.
-    /// The loop will continue indefinitely until the channel is closed.
+    /// The loop will continue until the channel is closed or the shutdown token is cancelled.
     /// # Arguments
     /// * `interval` - The duration between each ping.
     pub async fn run_interval_ping(&self, interval: std::time::Duration) {
         let mut interval = tokio::time::interval(interval);
         loop {
-            interval.tick().await;
-            if let Err(e) = self.channel_sender.try_send(()) {
+            tokio::select! {
+                _ = self.shutdown.cancelled() => {
+                    tracing::debug!("Shutdown requested, stopping interval ping");
+                    break;
+                }
+                _ = interval.tick() => {}
+            }
+            if let Err(e) = self.channel_sender.try_send(OrchestratorCommand::PollAll) {
                 match e {
                     tokio::sync::mpsc::error::TrySendError::Full(_) => {
                         // It's fine, the channel is full, we can skip this ping
@@ -41,9 +68,10 @@ impl IntervalPing {
     }
 }
 
+#[async_trait::async_trait]
 impl Notifier for IntervalPing {
     fn ping(&self) {
-        if let Err(e) = self.channel_sender.try_send(()) {
+        if let Err(e) = self.channel_sender.try_send(OrchestratorCommand::PollAll) {
             match e {
                 tokio::sync::mpsc::error::TrySendError::Full(_) => {
                     // It's fine, the channel is full, we can skip this ping
@@ -54,4 +82,57 @@ impl Notifier for IntervalPing {
             }
         }
     }
+
+    async fn send_command(&self, command: OrchestratorCommand) {
+        if self.channel_sender.send(command).await.is_err() {
+            tracing::warn!("Channel closed, cannot send command");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_interval_ping_ticks_at_configured_interval() {
+        let (channel_sender, mut channel_receiver) = tokio::sync::mpsc::channel(64);
+        let interval_ping = IntervalPing::new(channel_sender);
+        let ping_loop = tokio::spawn(async move {
+            interval_ping
+                .run_interval_ping(Duration::from_millis(100))
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(550)).await;
+        ping_loop.abort();
+        let mut pings = 0;
+        while let Ok(command) = channel_receiver.try_recv() {
+            assert!(matches!(command, OrchestratorCommand::PollAll));
+            pings += 1;
+        }
+        // The first tick is immediate, then one tick every 100ms
+        assert!((4..=8).contains(&pings), "{pings} pings sent");
+    }
+
+    #[tokio::test]
+    async fn test_interval_ping_stops_on_shutdown() {
+        let (channel_sender, _channel_receiver) = tokio::sync::mpsc::channel(64);
+        let shutdown = CancellationToken::new();
+        let interval_ping = IntervalPing::new(channel_sender).with_shutdown(shutdown.clone());
+        let ping_loop = tokio::spawn(async move {
+            interval_ping
+                .run_interval_ping(Duration::from_millis(100))
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(1), ping_loop)
+            .await
+            .expect("the interval ping loop should stop on shutdown")
+            .unwrap();
+    }
 }
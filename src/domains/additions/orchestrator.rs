@@ -1,77 +1,310 @@
 use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use anyhow::anyhow;
-use futures::{StreamExt, stream};
+use futures::{StreamExt, future, stream};
+use reqwest::StatusCode;
+use thiserror::Error;
 
 use crate::{
-    Peer,
-    domains::additions::{AwaitingPeerSharesProcess, AwaitingPeerSharesSumProcess},
-    peer_communication::peer_client::{AdditionProcessProgress, PeerClient},
+    domains::additions::{
+        AwaitingPeerSharesProcess, AwaitingPeerSharesSumProcess, ReconstructedProcess,
+    },
+    metrics::Metrics,
+    peer_communication::peer_client::{
+        AdditionProcessProgress, AdditionProcessProgressBatchItem, AdditionProcessProgressQuery,
+        MAX_PROGRESS_BATCH_SIZE, PeerClient, PeerClientError, ProcessFinalSum,
+    },
 };
 
 use super::{
-    AdditionProcess, ReceiveSharesRequest, ReceiveSharesRequestError, ReceiveSharesSumsRequest,
-    ReceiveSharesSumsRequestError, notifier::IntervalPing, repository::AdditionProcessRepository,
+    AdditionProcess, PeerPoints, ReceiveSharesRequest, ReceiveSharesRequestError,
+    ReceiveSharesSumsRequest, ReceiveSharesSumsRequestError, RecoverySettings,
+    completion::{ProcessCompletion, ProcessCompletions},
+    notifier::IntervalPing,
+    reduce_share,
+    repository::{AdditionProcessRepository, ProcessCancellationGuard},
 };
 
 pub fn setup_addition_process_orchestrator(
     repository: Arc<dyn AdditionProcessRepository>,
     peer_client: Arc<dyn PeerClient>,
     own_peer_id: u8,
-    peers: &[Peer],
+    metrics: Arc<Metrics>,
+    completions: Arc<ProcessCompletions>,
+    switch: Arc<OrchestratorSwitch>,
+    max_failures: u8,
 ) -> (AdditionProcessOrchestrator, IntervalPing) {
-    let (channel_sender, channel_receiver) = tokio::sync::mpsc::channel::<()>(1);
+    let (channel_sender, channel_receiver) =
+        tokio::sync::mpsc::channel::<OrchestratorCommand>(ORCHESTRATOR_COMMANDS_BUFFER);
     let orchestrator = AdditionProcessOrchestrator::new(
         repository,
         own_peer_id,
-        peers,
         peer_client,
         channel_receiver,
+        metrics,
+        completions,
+        switch,
+        max_failures,
     );
     let interval_ping = IntervalPing::new(channel_sender);
     (orchestrator, interval_ping)
 }
 
+/// Capacity of the orchestrator commands channel.
+/// Pings are skipped once it is full while the other commands wait for room.
+const ORCHESTRATOR_COMMANDS_BUFFER: usize = 64;
+
+/// Number of consecutive polls a peer responds without our share after which the process is flagged with a diagnostic.
+const MISSING_SHARE_DIAGNOSTIC_THRESHOLD: u8 = 3;
+
+/// Duration after the creation of a process during which peers not knowing it are retried without counting towards the maximum failures.
+const NOT_READY_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Number of consecutive failed reads of the ongoing processes after which the orchestrator reports itself as failing.
+/// Processes no longer advance while the repository can not be read, the node is then reported unhealthy to be restarted.
+const REPOSITORY_FAILURES_THRESHOLD: u32 = 5;
+
+/// Name of the orchestrator in the heartbeats of the background tasks.
+pub const HEARTBEAT_TASK: &str = "orchestrator";
+
+/// Command sent to the orchestrator through its channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrchestratorCommand {
+    /// Polls every ongoing process
+    PollAll,
+    /// Polls a single ongoing process
+    PollOne(uuid::Uuid),
+    /// Notifies the orchestrator that it has been paused
+    Pause,
+    /// Notifies the orchestrator that it has been resumed, every ongoing process is then polled
+    Resume,
+}
+
+/// Switch pausing and resuming the orchestrator, e.g. to inspect the state of the processes during maintenance.
+#[derive(Default)]
+pub struct OrchestratorSwitch {
+    paused: AtomicBool,
+}
+
+impl OrchestratorSwitch {
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+}
+
+/// Peers blocked by operators, e.g. a compromised peer, their requests are rejected and they are not polled.
+/// The committee is kept as is, a blocked peer can be unblocked at runtime.
+#[derive(Default)]
+pub struct BlockedPeers {
+    peer_ids: RwLock<BTreeSet<u8>>,
+}
+
+impl BlockedPeers {
+    pub fn new(peer_ids: &[u8]) -> Self {
+        Self {
+            peer_ids: RwLock::new(peer_ids.iter().cloned().collect()),
+        }
+    }
+
+    /// Blocks a peer, returns whether it was not blocked yet.
+    pub fn block(&self, peer_id: u8) -> bool {
+        self.peer_ids
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(peer_id)
+    }
+
+    /// Unblocks a peer, returns whether it was blocked.
+    pub fn unblock(&self, peer_id: u8) -> bool {
+        self.peer_ids
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&peer_id)
+    }
+
+    pub fn is_blocked(&self, peer_id: u8) -> bool {
+        self.peer_ids
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&peer_id)
+    }
+
+    /// IDs of the blocked peers, in ascending order.
+    pub fn peer_ids(&self) -> Vec<u8> {
+        self.peer_ids
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
 /// Orchestrates the addition processes by interacting with the repository and the peers.
+///
+/// The peers polled for a process are its participants, fixed at the creation of the process.
 pub struct AdditionProcessOrchestrator {
     repository: Arc<dyn AdditionProcessRepository>,
     own_peer_id: u8,
-    peer_ids: HashSet<u8>,
-    channel_receiver: tokio::sync::mpsc::Receiver<()>,
+    channel_receiver: tokio::sync::mpsc::Receiver<OrchestratorCommand>,
     peer_client: Arc<dyn PeerClient>,
     failures_attempts: HashMap<uuid::Uuid, u8>,
+    /// Consecutive failed reads of the ongoing processes
+    repository_failures: u32,
+    /// Consecutive polls each peer of a process responded without our share, by process ID
+    missing_share_responses: Mutex<HashMap<uuid::Uuid, HashMap<u8, u8>>>,
+    /// Number of consecutive failed polls after which a process is skipped
+    max_failures: u8,
+    /// Peers simulated as never responding, they are not polled
+    silent_peer_ids: HashSet<u8>,
+    /// Peers blocked by operators, they are not polled
+    blocked_peers: Arc<BlockedPeers>,
+    /// Evaluation points of the shares of the peers
+    points: PeerPoints,
+    /// Peers whose shares sums are known to be corrupt, they are not used to reconstruct the final sum
+    excluded_shares_sum_peer_ids: HashSet<u8>,
+    /// Number of peers which must report the reconstructed final sum before a process is completed, processes are completed at once if not set
+    confirm_quorum: Option<usize>,
+    /// Settings of the recovery of the final sums
+    recovery: RecoverySettings,
+    /// Duration after the creation of a process during which peers not knowing it are not counted as failures
+    not_ready_grace_period: std::time::Duration,
+    metrics: Arc<Metrics>,
+    completions: Arc<ProcessCompletions>,
+    switch: Arc<OrchestratorSwitch>,
 }
 
 impl AdditionProcessOrchestrator {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         repository: Arc<dyn AdditionProcessRepository>,
         own_peer_id: u8,
-        peers: &[Peer],
         peer_client: Arc<dyn PeerClient>,
-        channel_receiver: tokio::sync::mpsc::Receiver<()>,
+        channel_receiver: tokio::sync::mpsc::Receiver<OrchestratorCommand>,
+        metrics: Arc<Metrics>,
+        completions: Arc<ProcessCompletions>,
+        switch: Arc<OrchestratorSwitch>,
+        max_failures: u8,
     ) -> Self {
-        let peer_ids = peers.iter().map(|peer| peer.id).collect::<HashSet<u8>>();
         Self {
             repository,
             own_peer_id,
-            peer_ids,
             channel_receiver,
             peer_client,
             failures_attempts: HashMap::new(),
+            repository_failures: 0,
+            missing_share_responses: Mutex::new(HashMap::new()),
+            max_failures,
+            metrics,
+            completions,
+            switch,
+            silent_peer_ids: HashSet::new(),
+            blocked_peers: Arc::new(BlockedPeers::default()),
+            points: PeerPoints::default(),
+            excluded_shares_sum_peer_ids: HashSet::new(),
+            confirm_quorum: None,
+            recovery: RecoverySettings::default(),
+            not_ready_grace_period: NOT_READY_GRACE_PERIOD,
         }
     }
 
+    /// Counts the peers not knowing a process as failures once the given duration has elapsed since its creation, instead of after [`NOT_READY_GRACE_PERIOD`].
+    pub fn with_not_ready_grace_period(mut self, grace_period: std::time::Duration) -> Self {
+        self.not_ready_grace_period = grace_period;
+        self
+    }
+
+    /// Recovers the final sums with the given settings instead of the default ones.
+    pub fn with_recovery(mut self, recovery: RecoverySettings) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Evaluates the shares of the peers at the given points instead of their IDs.
+    pub fn with_peer_points(mut self, points: PeerPoints) -> Self {
+        self.points = points;
+        self
+    }
+
+    /// Simulates peers never responding, e.g. to test the stall of processes without stopping a node.
+    /// # Arguments
+    /// * `silent_peer_ids` - The IDs of the peers which are no longer polled.
+    pub fn with_silent_peers(mut self, silent_peer_ids: &[u8]) -> Self {
+        self.silent_peer_ids = silent_peer_ids.iter().cloned().collect();
+        self
+    }
+
+    /// Reconstructs the final sums without the shares sums of the given peers, e.g. peers known to send corrupt shares sums.
+    pub fn with_excluded_shares_sum_peers(mut self, peer_ids: &[u8]) -> Self {
+        self.excluded_shares_sum_peer_ids = peer_ids.iter().cloned().collect();
+        self
+    }
+
+    /// Completes the processes only once the given number of peers report the reconstructed final sum.
+    pub fn with_confirm_quorum(mut self, confirm_quorum: Option<usize>) -> Self {
+        self.confirm_quorum = confirm_quorum;
+        self
+    }
+
+    /// Skips the peers blocked by operators, the blocked peers are read on every poll.
+    pub fn with_blocked_peers(mut self, blocked_peers: Arc<BlockedPeers>) -> Self {
+        self.blocked_peers = blocked_peers;
+        self
+    }
+
     pub async fn run(&mut self) {
-        while self.channel_receiver.recv().await.is_some() {
-            let processes = match self.repository.get_ongoing_processes().await {
+        self.metrics.heartbeats.beat(HEARTBEAT_TASK);
+        while let Some(command) = self.channel_receiver.recv().await {
+            self.metrics.heartbeats.beat(HEARTBEAT_TASK);
+            // Pending commands are coalesced into this sweep, a command received during the sweep triggers another sweep right after it
+            let mut commands = vec![command];
+            while let Ok(command) = self.channel_receiver.try_recv() {
+                commands.push(command);
+            }
+            let mut poll_all = false;
+            let mut targeted_process_ids = HashSet::new();
+            for command in commands {
+                match command {
+                    OrchestratorCommand::PollAll => poll_all = true,
+                    OrchestratorCommand::PollOne(process_id) => {
+                        targeted_process_ids.insert(process_id);
+                    }
+                    OrchestratorCommand::Pause => {
+                        tracing::debug!("orchestrator received a pause command")
+                    }
+                    OrchestratorCommand::Resume => poll_all = true,
+                }
+            }
+            // Commands keep being drained while paused so that polling resumes on the next command
+            if self.switch.is_paused() {
+                tracing::debug!("orchestrator is paused, skipping ongoing addition processes");
+                continue;
+            }
+            if !poll_all && targeted_process_ids.is_empty() {
+                continue;
+            }
+            let mut processes = match self.repository.get_ongoing_processes().await {
                 Ok(processes) => processes
                     .into_iter()
+                    .filter(|p| poll_all || targeted_process_ids.contains(&p.id()))
                     .filter(|p| {
                         if let Some(attempts) = self.failures_attempts.get(&p.id()) {
-                            *attempts < 5
+                            *attempts < self.max_failures
                         } else {
                             true
                         }
@@ -79,9 +312,26 @@ impl AdditionProcessOrchestrator {
                     .collect::<Vec<AdditionProcess>>(),
                 Err(e) => {
                     tracing::error!("Failed to fetch ongoing addition processes: {:?}", e);
+                    self.repository_failures += 1;
+                    if self.repository_failures == REPOSITORY_FAILURES_THRESHOLD {
+                        tracing::error!(
+                            "Ongoing addition processes could not be fetched {} consecutive times, the orchestrator is reported as failing",
+                            self.repository_failures
+                        );
+                        self.metrics.heartbeats.report_failing(HEARTBEAT_TASK);
+                    }
                     continue;
                 }
             };
+            if self.repository_failures >= REPOSITORY_FAILURES_THRESHOLD {
+                tracing::info!(
+                    "Ongoing addition processes fetched again, the orchestrator recovered"
+                );
+                self.metrics.heartbeats.report_recovered(HEARTBEAT_TASK);
+            }
+            self.repository_failures = 0;
+            // Higher priority processes are queried first in the batches of each peer
+            processes.sort_by_key(|p| Reverse(p.priority()));
 
             if processes.is_empty() {
                 tracing::info!("no ongoing addition processes to orchestrate.");
@@ -92,22 +342,24 @@ impl AdditionProcessOrchestrator {
                 );
             }
 
-            let mut failure_ids = vec![];
-            for process in processes {
-                if let Err(e) = self.poll_and_update_process(&process).await {
-                    tracing::error!(
-                        "Failed to poll and update process {}: {:?}",
-                        process.id(),
-                        e
-                    );
-                    failure_ids.push(process.id());
+            let polled_processes = processes.len();
+            let failures = self.poll_and_update_processes(&processes).await;
+            // A successful poll resets the failures of a process, only consecutive failures get it skipped
+            for process in &processes {
+                if !failures.retryable.contains(&process.id())
+                    && !failures.undecodable.contains(&process.id())
+                    && !failures.not_ready.contains(&process.id())
+                {
+                    self.failures_attempts.remove(&process.id());
                 }
             }
+            // Undecodable progresses do not fix themselves on retry, they are not counted towards the maximum failures
+            let failure_ids = failures.retryable;
             if !failure_ids.is_empty() {
                 for failure_id in &failure_ids {
                     let counter = self.failures_attempts.entry(*failure_id).or_insert(0);
                     *counter += 1;
-                    if *counter >= 5 {
+                    if *counter >= self.max_failures {
                         tracing::error!(
                             "Process {} reached maximum failure attempts. It will be skipped in future orchestrations.",
                             failure_id
@@ -115,61 +367,306 @@ impl AdditionProcessOrchestrator {
                     }
                 }
             }
+            self.metrics.orchestrator.record_iteration(
+                polled_processes,
+                failure_ids.len() + failures.undecodable.len(),
+            );
+            self.metrics
+                .orchestrator
+                .record_failures_attempts(&self.failures_attempts);
+        }
+    }
+
+    /// Polls the processes and updates them with the fetched progresses.
+    /// The progresses are fetched with a batch request per peer, grouping the processes missing the progress of the peer.
+    /// # Returns
+    /// * The processes which failed to be polled or updated.
+    async fn poll_and_update_processes(&self, processes: &[AdditionProcess]) -> PollFailures {
+        let mut failures = PollFailures::default();
+        let mut batch_items_per_peer: HashMap<u8, Vec<AdditionProcessProgressBatchItem>> =
+            HashMap::new();
+        let mut polled_processes = vec![];
+        let mut skipped_peer_ids = self.silent_peer_ids.clone();
+        skipped_peer_ids.extend(self.blocked_peers.peer_ids());
+        for process in processes {
+            if let AdditionProcess::Reconstructed(p) = process {
+                if let Err(e) = self.confirm_final_sum(p, &skipped_peer_ids).await {
+                    tracing::error!(
+                        "Failed to confirm the final sum of process {}: {:?}",
+                        p.id,
+                        e
+                    );
+                    failures.retryable.push(p.id);
+                }
+                continue;
+            }
+            match missing_progress_queries(process, &skipped_peer_ids) {
+                Ok(Some(queries)) => {
+                    for (peer_id, item) in queries {
+                        batch_items_per_peer.entry(peer_id).or_default().push(item);
+                    }
+                    polled_processes.push(process);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!("Failed to poll process {}: {:?}", process.id(), e);
+                    failures.retryable.push(process.id());
+                }
+            }
+        }
+
+        // Requests are dropped if their processes are deleted meanwhile
+        let cancellations = polled_processes
+            .iter()
+            .map(|process| {
+                (
+                    process.id(),
+                    self.repository.register_cancellation(process.id()),
+                )
+            })
+            .collect::<HashMap<uuid::Uuid, ProcessCancellationGuard>>();
+        let mut results_per_process = self
+            .fetch_progress_batches(batch_items_per_peer, &cancellations)
+            .await;
+
+        for process in polled_processes {
+            if cancellations
+                .get(&process.id())
+                .is_some_and(|guard| guard.token().is_cancelled())
+            {
+                tracing::info!(
+                    "Process {} deleted while fetching progresses, in-flight requests dropped",
+                    process.id()
+                );
+                continue;
+            }
+            let results = results_per_process
+                .remove(&process.id())
+                .unwrap_or_default();
+            match self.update_process(process, results).await {
+                Ok(()) => {}
+                Err(e @ PollError::Undecodable(_)) => {
+                    tracing::error!("Failed to poll process {}: {}", process.id(), e);
+                    failures.undecodable.push(process.id());
+                }
+                // Past the grace period, the peers most likely lost or evicted the process, e.g. once completed
+                Err(e @ PollError::NotReady(_))
+                    if !self.is_within_not_ready_grace_period(process) =>
+                {
+                    tracing::warn!(
+                        "Process {} still unknown to its peers after {:?}: {}",
+                        process.id(),
+                        self.not_ready_grace_period,
+                        e
+                    );
+                    failures.retryable.push(process.id());
+                }
+                Err(e @ PollError::NotReady(_)) => {
+                    tracing::info!("Process {} not polled yet: {}", process.id(), e);
+                    failures.not_ready.push(process.id());
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to poll and update process {}: {:?}",
+                        process.id(),
+                        e
+                    );
+                    failures.retryable.push(process.id());
+                }
+            }
+        }
+        failures
+    }
+
+    /// Fetches the progresses from the peers, with at most [`MAX_PROGRESS_BATCH_SIZE`] processes per request.
+    /// A request is dropped once all of its processes are deleted.
+    /// # Returns
+    /// * The progress or the error of each peer, by process ID. Processes of failed batch requests are left without result for the peer.
+    async fn fetch_progress_batches(
+        &self,
+        batch_items_per_peer: HashMap<u8, Vec<AdditionProcessProgressBatchItem>>,
+        cancellations: &HashMap<uuid::Uuid, ProcessCancellationGuard>,
+    ) -> HashMap<uuid::Uuid, Vec<PeerProgressResult>> {
+        let mut batches = batch_items_per_peer
+            .into_iter()
+            .flat_map(|(peer_id, items)| {
+                items
+                    .chunks(MAX_PROGRESS_BATCH_SIZE)
+                    .map(|chunk| (peer_id, chunk.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        batches.sort_by_key(|(peer_id, _)| *peer_id);
+
+        let batch_results = stream::iter(batches)
+            .map(|(peer_id, items)| {
+                let process_ids = items
+                    .iter()
+                    .map(|item| item.process_id)
+                    .collect::<Vec<uuid::Uuid>>();
+                let tokens = process_ids
+                    .iter()
+                    .filter_map(|process_id| cancellations.get(process_id))
+                    .map(|guard| guard.token().clone())
+                    .collect::<Vec<_>>();
+                async move {
+                    let result = tokio::select! {
+                        result = self.peer_client.fetch_process_progress_batch(peer_id, items) => Some(result),
+                        _ = future::join_all(tokens.iter().map(|token| token.cancelled())) => None,
+                    };
+                    (peer_id, process_ids, result)
+                }
+            })
+            .buffer_unordered(5)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut results_per_process: HashMap<uuid::Uuid, Vec<PeerProgressResult>> = HashMap::new();
+        for (peer_id, process_ids, result) in batch_results {
+            match result {
+                Some(Ok(mut progresses)) => {
+                    for process_id in process_ids {
+                        let progress = progresses.remove(&process_id).unwrap_or_else(|| {
+                            Err(PeerClientError::Decode {
+                                peer_id,
+                                source: anyhow!("missing progress of process {process_id}"),
+                            })
+                        });
+                        if let Err(e @ PeerClientError::Decode { .. }) = &progress {
+                            self.report_decode_failure(peer_id, e);
+                        }
+                        results_per_process
+                            .entry(process_id)
+                            .or_default()
+                            .push((peer_id, progress));
+                    }
+                }
+                Some(Err(e @ PeerClientError::Decode { .. })) => {
+                    self.report_decode_failure(peer_id, &e);
+                    // Each process of the batch is failed by the undecodable response
+                    for process_id in process_ids {
+                        results_per_process.entry(process_id).or_default().push((
+                            peer_id,
+                            Err(PeerClientError::Decode {
+                                peer_id,
+                                source: anyhow!("{e}"),
+                            }),
+                        ));
+                    }
+                }
+                Some(Err(e)) => {
+                    tracing::error!("Error fetching process progress batch from peer: {}", e)
+                }
+                None => {}
+            }
         }
+        results_per_process
     }
 
-    async fn poll_and_update_process(
+    /// Reports an undecodable response of a peer, it is surfaced on `/health/peers`.
+    fn report_decode_failure(&self, peer_id: u8, error: &PeerClientError) {
+        tracing::error!(
+            "Undecodable response from peer {peer_id}, the peers likely run incompatible versions: {error}"
+        );
+        self.metrics
+            .peers
+            .record_decode_failure(peer_id, error.to_string());
+    }
+
+    fn is_within_not_ready_grace_period(&self, process: &AdditionProcess) -> bool {
+        let age = chrono::Utc::now() - process.created_at();
+        age.to_std()
+            .map_or(true, |age| age < self.not_ready_grace_period)
+    }
+
+    /// Updates a process with the progresses fetched from the peers.
+    async fn update_process(
         &self,
         process: &AdditionProcess,
-    ) -> Result<(), anyhow::Error> {
+        results: Vec<PeerProgressResult>,
+    ) -> Result<(), PollError> {
+        let undecodable_peer_ids = results
+            .iter()
+            .filter(|(_, result)| matches!(result, Err(PeerClientError::Decode { .. })))
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<u8>>();
+        if !results.is_empty() && undecodable_peer_ids.len() == results.len() {
+            return Err(PollError::Undecodable(undecodable_peer_ids));
+        }
+        let not_ready_peer_ids = results
+            .iter()
+            .filter(|(_, result)| {
+                matches!(result, Err(PeerClientError::UnexpectedStatus { status, .. }) if *status == StatusCode::NOT_FOUND)
+            })
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<u8>>();
+        if !results.is_empty() && not_ready_peer_ids.len() == results.len() {
+            return Err(PollError::NotReady(not_ready_peer_ids));
+        }
         match process {
             AdditionProcess::AwaitingPeerShares(p) => {
-                tracing::info!("Polling for peer shares for process {}", p.id);
-                self.poll_for_peer_shares(p).await
+                Ok(self.receive_peer_shares(p, results).await?)
             }
             AdditionProcess::AwaitingPeerSharesSum(p) => {
-                tracing::info!("Polling for peer shares sums for process {}", p.id);
-                self.poll_for_peer_shares_sums(p).await
+                Ok(self.receive_peer_shares_sums(p, results).await?)
             }
-            AdditionProcess::Completed(_p) => {
-                // No action needed for completed processes
+            AdditionProcess::Reconstructed(_)
+            | AdditionProcess::Completed(_)
+            | AdditionProcess::Unrecoverable(_)
+            | AdditionProcess::Tampered(_) => {
+                // Reconstructed processes are confirmed apart, no action needed for completed, unrecoverable or tampered processes
                 Ok(())
             }
         }
     }
 
-    /// Looks for missing shares from peers and tries to fetch them.
-    /// Once shares are fetched, create the associated request and use the repository to update the process state accordingly.
-    async fn poll_for_peer_shares(
+    /// Receives the shares fetched from the peers.
+    /// Creates the associated request and uses the repository to update the process state accordingly.
+    async fn receive_peer_shares(
         &self,
         process: &AwaitingPeerSharesProcess,
+        results: Vec<PeerProgressResult>,
     ) -> Result<(), anyhow::Error> {
-        let missing_peer_ids = self
-            .peer_ids
-            .iter()
-            .filter(|peer_id| !process.received_shares.contains_key(peer_id))
-            .cloned()
-            .collect::<Vec<u8>>();
-        if missing_peer_ids.is_empty() {
-            return Err(anyhow!("unexpected: no missing peer shares to poll for"));
-        }
-        let peer_progresses = self
-            .fetch_process_progress_from_peers(missing_peer_ids, process.id)
-            .await
+        self.account_missing_shares(process, &results).await?;
+        let fetched_progresses = collect_fetched_progresses(results)
             .map_err(|e| e.context("fetching missing process progresses"))?;
-        let received_shares = peer_progresses
+        if !fetched_progresses.conflicting_peer_ids.is_empty() {
+            return self
+                .mark_unrecoverable(
+                    process.id,
+                    format!(
+                        "peers {:?} reported an inconsistent process state",
+                        fetched_progresses.conflicting_peer_ids
+                    ),
+                )
+                .await;
+        }
+        let received_shares = fetched_progresses
+            .progresses
             .into_iter()
             .map(|progress| (progress.peer_id, progress.progress.share))
             .collect::<HashMap<u8, u64>>();
 
-        let receive_shares_request = ReceiveSharesRequest::new(
-            process,
-            received_shares,
-            self.peer_ids.len(),
-        )
-        .map_err(|e| match e {
-            ReceiveSharesRequestError::Unknown(e) => e.context("creating receive shares request"),
-        })?;
+        // The process may have been updated while fetching the progresses, it is read again under lock
+        let _lock = self.repository.lock_process(process.id).await;
+        let process = match self
+            .repository
+            .get_process(process.id)
+            .await
+            .map_err(|e| e.context("retrieving process before receiving shares"))?
+        {
+            AdditionProcess::AwaitingPeerShares(p) => p,
+            _ => return Ok(()),
+        };
+        let receive_shares_request =
+            ReceiveSharesRequest::new(&process, received_shares).map_err(|e| match e {
+                ReceiveSharesRequestError::UnexpectedPeer { .. } => {
+                    anyhow::anyhow!(e).context("creating receive shares request")
+                }
+                ReceiveSharesRequestError::Unknown(e) => {
+                    e.context("creating receive shares request")
+                }
+            })?;
         self.repository
             .receive_shares(receive_shares_request)
             .await
@@ -178,28 +675,140 @@ impl AdditionProcessOrchestrator {
         Ok(())
     }
 
-    /// Looks for missing shares sums from peers and tries to fetch them.
-    /// Once shares sums are fetched, create the associated request and use the repository to update the process state accordingly.
-    async fn poll_for_peer_shares_sums(
+    /// Accounts for the peers which responded without our share, a peer rejects the progress request with `400` if it has no share for us.
+    /// A process is flagged with a diagnostic once a peer responded without our share for [`MISSING_SHARE_DIAGNOSTIC_THRESHOLD`] consecutive polls, it would otherwise stall without explanation.
+    async fn account_missing_shares(
         &self,
-        process: &AwaitingPeerSharesSumProcess,
+        process: &AwaitingPeerSharesProcess,
+        results: &[PeerProgressResult],
     ) -> Result<(), anyhow::Error> {
-        let missing_peer_ids = self
-            .peer_ids
+        let received_peer_ids = results
             .iter()
-            .filter(|peer_id| !process.received_shares_sums.contains_key(peer_id))
-            .cloned()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(peer_id, _)| *peer_id)
             .collect::<Vec<u8>>();
-        if missing_peer_ids.is_empty() {
-            return Err(anyhow!(
-                "unexpected: no missing peer shares sums to poll for"
-            ));
+        let missing_share_peer_ids = results
+            .iter()
+            .filter(|(_, result)| {
+                matches!(result, Err(PeerClientError::UnexpectedStatus { status, .. }) if *status == StatusCode::BAD_REQUEST)
+            })
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<u8>>();
+        if received_peer_ids.len() < results.len() {
+            tracing::warn!(
+                "Process {}: received the progress of {} out of {} polled peers, peers {:?} responded without our share",
+                process.id,
+                received_peer_ids.len(),
+                results.len(),
+                missing_share_peer_ids
+            );
         }
-        let peer_progresses = self
-            .fetch_process_progress_from_peers(missing_peer_ids, process.id)
+
+        let mut persistent_peer_ids = {
+            let mut missing_share_responses = self
+                .missing_share_responses
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let responses = missing_share_responses.entry(process.id).or_default();
+            for peer_id in &received_peer_ids {
+                responses.remove(peer_id);
+            }
+            for peer_id in &missing_share_peer_ids {
+                let count = responses.entry(*peer_id).or_insert(0);
+                *count = count.saturating_add(1);
+            }
+            let persistent_peer_ids = responses
+                .iter()
+                .filter(|(_, count)| **count >= MISSING_SHARE_DIAGNOSTIC_THRESHOLD)
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<u8>>();
+            if responses.is_empty() {
+                missing_share_responses.remove(&process.id);
+            }
+            persistent_peer_ids
+        };
+        if persistent_peer_ids.is_empty() {
+            return Ok(());
+        }
+        persistent_peer_ids.sort_unstable();
+        let diagnostic = format!(
+            "peers {persistent_peer_ids:?} could not produce our share for {MISSING_SHARE_DIAGNOSTIC_THRESHOLD} consecutive polls, they may not know this peer as a participant"
+        );
+        if process.diagnostic.as_ref() == Some(&diagnostic) {
+            return Ok(());
+        }
+
+        let _lock = self.repository.lock_process(process.id).await;
+        if !matches!(
+            self.repository
+                .get_process(process.id)
+                .await
+                .map_err(|e| e.context("retrieving process before recording diagnostic"))?,
+            AdditionProcess::AwaitingPeerShares(_)
+        ) {
+            return Ok(());
+        }
+        tracing::error!("Process {} is stalled: {}", process.id, diagnostic);
+        self.repository
+            .record_diagnostic(process.id, diagnostic)
             .await
+            .map_err(|e| e.context("recording process diagnostic"))?;
+        Ok(())
+    }
+
+    /// Receives the shares sums fetched from the peers.
+    /// Creates the associated request and uses the repository to update the process state accordingly.
+    async fn receive_peer_shares_sums(
+        &self,
+        process: &AwaitingPeerSharesSumProcess,
+        results: Vec<PeerProgressResult>,
+    ) -> Result<(), anyhow::Error> {
+        let fetched_progresses = collect_fetched_progresses(results)
             .map_err(|e| e.context("fetching missing process progresses for shares sums"))?;
-        let received_shares_sums = peer_progresses
+        if !fetched_progresses.conflicting_peer_ids.is_empty() {
+            return self
+                .mark_unrecoverable(
+                    process.id,
+                    format!(
+                        "peers {:?} reported an inconsistent process state",
+                        fetched_progresses.conflicting_peer_ids
+                    ),
+                )
+                .await;
+        }
+        // A peer whose share changed since we received it has lost and re-derived its process state
+        if let Some(changed_peer) = fetched_progresses.progresses.iter().find(|p| {
+            process
+                .received_shares
+                .get(&p.peer_id)
+                .is_some_and(|share| *share != reduce_share(p.progress.share))
+        }) {
+            return self
+                .mark_unrecoverable(
+                    process.id,
+                    format!(
+                        "share of peer {} changed since it was received",
+                        changed_peer.peer_id
+                    ),
+                )
+                .await;
+        }
+        let completed_peer_ids = fetched_progresses
+            .progresses
+            .iter()
+            .filter(|p| p.progress.completed)
+            .map(|p| p.peer_id)
+            .collect::<Vec<u8>>();
+        if !completed_peer_ids.is_empty() {
+            // Completed peers only answer until their retention elapses, the process must be finished before
+            tracing::debug!(
+                "Peers {:?} already completed process {}",
+                completed_peer_ids,
+                process.id
+            );
+        }
+        let received_shares_sums = fetched_progresses
+            .progresses
             .into_iter()
             .filter_map(|progress_from_peer| {
                 if let Some(shares_sum) = progress_from_peer.progress.shares_sum {
@@ -210,17 +819,42 @@ impl AdditionProcessOrchestrator {
             })
             .collect::<HashMap<u8, u64>>();
 
-        let receive_shares_sums_request = ReceiveSharesSumsRequest::new(
-            process,
+        // The process may have been updated while fetching the progresses, it is read again under lock
+        let _lock = self.repository.lock_process(process.id).await;
+        let process = match self
+            .repository
+            .get_process(process.id)
+            .await
+            .map_err(|e| e.context("retrieving process before receiving shares sums"))?
+        {
+            AdditionProcess::AwaitingPeerSharesSum(p) => p,
+            _ => return Ok(()),
+        };
+        let receive_shares_sums_request = match ReceiveSharesSumsRequest::new_excluding(
+            &process,
             received_shares_sums,
             self.own_peer_id,
-            self.peer_ids.len(),
-        )
-        .map_err(|e| match e {
-            ReceiveSharesSumsRequestError::Unknown(e) => {
-                e.context("creating receive shares sums request")
+            process.input_shares.shares_to_send.len(),
+            &self.points,
+            &self.excluded_shares_sum_peer_ids,
+            self.recovery,
+        ) {
+            Ok(request) => request.with_confirmation(self.confirm_quorum.is_some()),
+            Err(ReceiveSharesSumsRequestError::Tampered {
+                received_shares_sums,
+                reason,
+            }) => {
+                tracing::error!("Process {} has been tampered with: {}", process.id, reason);
+                self.repository
+                    .mark_tampered(process.id, received_shares_sums, reason)
+                    .await
+                    .map_err(|e| e.context("marking process as tampered"))?;
+                return Ok(());
             }
-        })?;
+            Err(ReceiveSharesSumsRequestError::Unknown(e)) => {
+                return Err(e.context("creating receive shares sums request"));
+            }
+        };
         let updated_process = self
             .repository
             .receive_shares_sums(receive_shares_sums_request)
@@ -228,46 +862,981 @@ impl AdditionProcessOrchestrator {
             .map_err(|e| e.context("updating process with received shares sums"))?;
 
         if let AdditionProcess::Completed(completed_process) = updated_process {
+            let completion_duration = completed_process.completion_duration();
+            self.metrics
+                .process_completion_duration
+                .observe(completion_duration);
             tracing::info!(
-                "Process {} completed with final sum: {}",
+                "Process {} completed with final sum: {} in {:?}",
                 process.id,
-                completed_process.final_sum
+                completed_process.final_sum,
+                completion_duration
             );
+            self.completions.publish(ProcessCompletion {
+                process_id: process.id,
+                final_sum: completed_process.final_sum,
+            });
         }
 
         Ok(())
     }
 
-    async fn fetch_process_progress_from_peers(
+    /// Completes a reconstructed process once enough peers report its candidate sum as their final sum.
+    ///
+    /// A peer reporting another final sum dissents, the process then stays reconstructed until enough other peers agree.
+    /// # Arguments
+    /// * `process` - The reconstructed process,
+    /// * `skipped_peer_ids` - The peers which are not queried, simulated as never responding or blocked.
+    async fn confirm_final_sum(
         &self,
-        peer_ids: Vec<u8>,
-        process_id: uuid::Uuid,
-    ) -> Result<Vec<AdditionProcessProgressFromPeer>, anyhow::Error> {
-        let bodies = stream::iter(peer_ids)
-            .map(|peer_id| async move {
-                self.peer_client
-                    .fetch_process_progress(peer_id, process_id)
-                    .await
-                    .map(|progress| AdditionProcessProgressFromPeer { peer_id, progress })
-            })
-            .buffer_unordered(5);
-        let results: Vec<Result<AdditionProcessProgressFromPeer, anyhow::Error>> =
-            bodies.collect().await;
-        let mut progresses = Vec::new();
-        for result in results {
+        process: &ReconstructedProcess,
+        skipped_peer_ids: &HashSet<u8>,
+    ) -> Result<(), anyhow::Error> {
+        let mut peer_ids = process
+            .input_shares
+            .peer_ids()
+            .into_iter()
+            .filter(|peer_id| !skipped_peer_ids.contains(peer_id))
+            .collect::<Vec<u8>>();
+        peer_ids.sort_unstable();
+        let reported_sums = future::join_all(peer_ids.into_iter().map(|peer_id| async move {
+            let result = self.peer_client.fetch_final_sum(peer_id, process.id).await;
+            (peer_id, result)
+        }))
+        .await;
+
+        let mut confirming_peer_ids = vec![];
+        let mut dissenting_peer_ids = vec![];
+        for (peer_id, result) in reported_sums {
             match result {
-                Ok(progress) => progresses.push(progress),
-                Err(e) => tracing::error!("Error fetching process progress from peer: {}", e),
+                Ok(reported) if reported.final_sum == Some(process.candidate_sum) => {
+                    confirming_peer_ids.push(peer_id)
+                }
+                Ok(ProcessFinalSum { final_sum: Some(_) }) => dissenting_peer_ids.push(peer_id),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(
+                    "Failed to fetch the final sum of process {} from peer {}: {}",
+                    process.id,
+                    peer_id,
+                    e
+                ),
             }
         }
-        if progresses.is_empty() {
-            return Err(anyhow!("Failed to fetch progress from any peer"));
+        if !dissenting_peer_ids.is_empty() {
+            tracing::error!(
+                "Final sum of process {} is disputed by peers {:?}",
+                process.id,
+                dissenting_peer_ids
+            );
+        }
+        // The quorum can not exceed the peers of the process, e.g. a process created with fewer peers
+        let quorum = self
+            .confirm_quorum
+            .unwrap_or(0)
+            .min(process.input_shares.shares_to_send.len());
+        if confirming_peer_ids.len() < quorum {
+            tracing::info!(
+                "Final sum of process {} confirmed by {} peers out of the {} required",
+                process.id,
+                confirming_peer_ids.len(),
+                quorum
+            );
+            return Ok(());
+        }
+
+        let _lock = self.repository.lock_process(process.id).await;
+        if !matches!(
+            self.repository
+                .get_process(process.id)
+                .await
+                .map_err(|e| e.context("retrieving process before confirming its final sum"))?,
+            AdditionProcess::Reconstructed(_)
+        ) {
+            return Ok(());
+        }
+        let confirmed_process = self
+            .repository
+            .confirm_final_sum(process.id)
+            .await
+            .map_err(|e| e.context("confirming final sum"))?;
+        if let AdditionProcess::Completed(completed_process) = confirmed_process {
+            let completion_duration = completed_process.completion_duration();
+            self.metrics
+                .process_completion_duration
+                .observe(completion_duration);
+            tracing::info!(
+                "Process {} completed with final sum: {} confirmed by peers {:?} in {:?}",
+                process.id,
+                completed_process.final_sum,
+                confirming_peer_ids,
+                completion_duration
+            );
+            self.completions.publish(ProcessCompletion {
+                process_id: process.id,
+                final_sum: completed_process.final_sum,
+            });
+        }
+        Ok(())
+    }
+
+    async fn mark_unrecoverable(
+        &self,
+        process_id: uuid::Uuid,
+        reason: String,
+    ) -> Result<(), anyhow::Error> {
+        tracing::error!("Process {} is unrecoverable: {}", process_id, reason);
+        self.repository
+            .mark_unrecoverable(process_id, reason)
+            .await
+            .map_err(|e| e.context("marking process as unrecoverable"))?;
+        Ok(())
+    }
+}
+
+/// Builds the progress queries of a process for the peers whose progress is missing.
+/// # Arguments
+/// * `process` - The process to poll,
+/// * `skipped_peer_ids` - The peers which are not queried, simulated as never responding or blocked.
+/// # Returns
+/// * The query for each missing peer, `None` if the process is not ongoing or only misses the progress of skipped peers.
+fn missing_progress_queries(
+    process: &AdditionProcess,
+    skipped_peer_ids: &HashSet<u8>,
+) -> Result<Option<Vec<(u8, AdditionProcessProgressBatchItem)>>, anyhow::Error> {
+    // The shares sum is sent along so that the peers record the server in the shares sum round
+    let (shares_sum, received, input_shares) = match process {
+        AdditionProcess::AwaitingPeerShares(p) => {
+            tracing::info!("Polling for peer shares for process {}", p.id);
+            (None, &p.received_shares, &p.input_shares)
+        }
+        AdditionProcess::AwaitingPeerSharesSum(p) => {
+            tracing::info!("Polling for peer shares sums for process {}", p.id);
+            (Some(p.shares_sum), &p.received_shares_sums, &p.input_shares)
+        }
+        AdditionProcess::Reconstructed(_)
+        | AdditionProcess::Completed(_)
+        | AdditionProcess::Unrecoverable(_)
+        | AdditionProcess::Tampered(_) => return Ok(None),
+    };
+    let mut missing_peer_ids = input_shares
+        .peer_ids()
+        .into_iter()
+        .filter(|peer_id| !received.contains_key(peer_id))
+        .collect::<Vec<u8>>();
+    missing_peer_ids.sort_unstable();
+    if missing_peer_ids.is_empty() {
+        return Err(anyhow!(
+            "unexpected: no missing peer progress to poll for process {}",
+            process.id()
+        ));
+    }
+    missing_peer_ids.retain(|peer_id| !skipped_peer_ids.contains(peer_id));
+    if missing_peer_ids.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        missing_peer_ids
+            .into_iter()
+            .map(|peer_id| {
+                (
+                    peer_id,
+                    AdditionProcessProgressBatchItem {
+                        process_id: process.id(),
+                        query: AdditionProcessProgressQuery {
+                            shares_sum,
+                            sent_share: input_shares.shares_to_send.get(&peer_id).cloned(),
+                        },
+                    },
+                )
+            })
+            .collect(),
+    ))
+}
+
+/// Sorts the progresses fetched from the peers of a process, conflicting peers are reported apart and failures are logged.
+fn collect_fetched_progresses(
+    results: Vec<PeerProgressResult>,
+) -> Result<FetchedProgresses, anyhow::Error> {
+    let mut fetched_progresses = FetchedProgresses {
+        progresses: Vec::new(),
+        conflicting_peer_ids: Vec::new(),
+    };
+    for (peer_id, result) in results {
+        match result {
+            Ok(progress) => fetched_progresses
+                .progresses
+                .push(AdditionProcessProgressFromPeer { peer_id, progress }),
+            Err(PeerClientError::UnexpectedStatus { status, .. })
+                if status == StatusCode::CONFLICT =>
+            {
+                fetched_progresses.conflicting_peer_ids.push(peer_id)
+            }
+            // Undecodable progresses are reported when fetched
+            Err(PeerClientError::Decode { .. }) => {}
+            Err(e) => tracing::error!("Error fetching process progress from peer: {}", e),
         }
-        Ok(progresses)
     }
+    if fetched_progresses.progresses.is_empty()
+        && fetched_progresses.conflicting_peer_ids.is_empty()
+    {
+        return Err(anyhow!("Failed to fetch progress from any peer"));
+    }
+    Ok(fetched_progresses)
 }
 
+/// Failure of the poll of a process.
+#[derive(Debug, Error)]
+enum PollError {
+    /// Every fetched progress was undecodable, retrying does not help
+    #[error(
+        "progresses of peers {0:?} could not be decoded, the peers likely run incompatible versions"
+    )]
+    Undecodable(Vec<u8>),
+    /// Every polled peer does not know the process yet, e.g. it is still being created on the peers
+    #[error("peers {0:?} do not know the process yet")]
+    NotReady(Vec<u8>),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// Processes whose poll failed during an orchestrator iteration.
+#[derive(Default)]
+struct PollFailures {
+    /// Processes whose poll may succeed on retry, they count towards the maximum failures
+    retryable: Vec<uuid::Uuid>,
+    /// Processes whose fetched progresses could not be decoded
+    undecodable: Vec<uuid::Uuid>,
+    /// Processes unknown to every polled peer within the grace period after their creation, they are retried without counting towards the maximum failures
+    not_ready: Vec<uuid::Uuid>,
+}
+
+/// Progress of a process fetched from a peer, or the error of the fetch
+type PeerProgressResult = (u8, Result<AdditionProcessProgress, PeerClientError>);
+
 struct AdditionProcessProgressFromPeer {
     peer_id: u8,
     progress: AdditionProcessProgress,
 }
+
+struct FetchedProgresses {
+    progresses: Vec<AdditionProcessProgressFromPeer>,
+    /// Peers which reported the process state as inconsistent
+    conflicting_peer_ids: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        domains::additions::{
+            CreateProcessRequest,
+            notifier::Notifier,
+            repository::{
+                InMemoryAdditionProcessRepository, ProcessLockGuard, ProcessesStatistics,
+                RepositoryError,
+            },
+        },
+        peer_communication::{
+            dry_run_peer_client::{DryRunPeerClient, DryRunRequest},
+            scripted_peer_client::{PeerMethod, ScriptedPeerClient, ScriptedResponse},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_processes_created_in_a_burst_are_polled_without_waiting_an_interval() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client = Arc::new(DryRunPeerClient::new());
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        // No interval ping, the processes are only polled on the pings of their creation
+        tokio::spawn(async move { orchestrator.run().await });
+
+        let mut process_ids = HashSet::new();
+        for _ in 0..5 {
+            let process = repository
+                .create_process(
+                    CreateProcessRequest::new(
+                        Uuid::new_v4(),
+                        1,
+                        &[2, 3],
+                        None,
+                        None,
+                        &PeerPoints::default(),
+                    )
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+            process_ids.insert(process.id());
+            notifier.ping();
+        }
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let polled_process_ids = peer_client
+            .requests()
+            .into_iter()
+            .filter_map(|request| match request {
+                DryRunRequest::FetchProcessProgress { process_id, .. } => Some(process_id),
+                _ => None,
+            })
+            .collect::<HashSet<Uuid>>();
+        assert_eq!(polled_process_ids, process_ids);
+    }
+
+    #[tokio::test]
+    async fn test_blocked_peers_are_not_polled() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client = Arc::new(DryRunPeerClient::new());
+        let blocked_peers = Arc::new(BlockedPeers::new(&[3]));
+        let (orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        let mut orchestrator = orchestrator.with_blocked_peers(blocked_peers.clone());
+        tokio::spawn(async move { orchestrator.run().await });
+
+        let polled_peer_ids = || {
+            peer_client
+                .requests()
+                .into_iter()
+                .filter_map(|request| match request {
+                    DryRunRequest::FetchProcessProgress { peer_id, .. } => Some(peer_id),
+                    _ => None,
+                })
+                .collect::<HashSet<u8>>()
+        };
+        repository
+            .create_process(
+                CreateProcessRequest::new(
+                    Uuid::new_v4(),
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        notifier.ping();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(polled_peer_ids(), HashSet::from([2]));
+
+        // Unblocked peers are polled on the next iteration
+        blocked_peers.unblock(3);
+        notifier.ping();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(polled_peer_ids(), HashSet::from([2, 3]));
+    }
+
+    #[tokio::test]
+    async fn test_poll_one_command_only_polls_the_targeted_process() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client = Arc::new(DryRunPeerClient::new());
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+
+        let mut process_ids = vec![];
+        for _ in 0..3 {
+            let process = repository
+                .create_process(
+                    CreateProcessRequest::new(
+                        Uuid::new_v4(),
+                        1,
+                        &[2, 3],
+                        None,
+                        None,
+                        &PeerPoints::default(),
+                    )
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+            process_ids.push(process.id());
+        }
+        notifier
+            .send_command(OrchestratorCommand::PollOne(process_ids[0]))
+            .await;
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let polled_process_ids = peer_client
+            .requests()
+            .into_iter()
+            .filter_map(|request| match request {
+                DryRunRequest::FetchProcessProgress { process_id, .. } => Some(process_id),
+                _ => None,
+            })
+            .collect::<HashSet<Uuid>>();
+        assert_eq!(polled_process_ids, HashSet::from([process_ids[0]]));
+    }
+
+    #[tokio::test]
+    async fn test_higher_priority_processes_are_polled_first() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client = Arc::new(DryRunPeerClient::new());
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+
+        let mut process_ids = vec![];
+        for priority in [0, 0, 9, 0] {
+            let process = repository
+                .create_process(
+                    CreateProcessRequest::new(
+                        Uuid::new_v4(),
+                        1,
+                        &[2, 3],
+                        None,
+                        None,
+                        &PeerPoints::default(),
+                    )
+                    .unwrap()
+                    .with_priority(priority),
+                )
+                .await
+                .unwrap();
+            process_ids.push(process.id());
+        }
+        notifier.ping();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let first_polled_process_id =
+            peer_client
+                .requests()
+                .into_iter()
+                .find_map(|request| match request {
+                    DryRunRequest::FetchProcessProgress {
+                        peer_id: 2,
+                        process_id,
+                        ..
+                    } => Some(process_id),
+                    _ => None,
+                });
+        assert_eq!(first_polled_process_id, Some(process_ids[2]));
+    }
+
+    #[tokio::test]
+    async fn test_deleting_a_process_drops_its_in_flight_requests() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client =
+            Arc::new(ScriptedPeerClient::new().with_delay(Duration::from_millis(500)));
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+        let process_id = repository
+            .create_process(
+                CreateProcessRequest::new(
+                    Uuid::new_v4(),
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+            .id();
+        notifier.ping();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(
+            !peer_client
+                .calls(PeerMethod::FetchProcessProgress)
+                .is_empty()
+        );
+        repository.delete_process(process_id).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(700)).await;
+        assert!(
+            peer_client
+                .answered_calls(PeerMethod::FetchProcessProgress)
+                .is_empty()
+        );
+        assert!(matches!(
+            repository.get_process(process_id).await,
+            Err(RepositoryError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_process_is_skipped_after_max_failures() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client = Arc::new(ScriptedPeerClient::new().respond_to_all(
+            PeerMethod::FetchProcessProgress,
+            ScriptedResponse::Unreachable,
+        ));
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            2,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+        repository
+            .create_process(
+                CreateProcessRequest::new(
+                    Uuid::new_v4(),
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Each sweep fetches the progress of the two peers until the process is skipped
+        for expected_fetches in [2, 4, 4, 4] {
+            notifier.ping();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert_eq!(
+                peer_client.calls(PeerMethod::FetchProcessProgress).len(),
+                expected_fetches
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_unknown_to_peers_is_not_skipped() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        // Both peers do not know the process for the first three sweeps, they then respond with their share
+        let not_found = vec![ScriptedResponse::Status(StatusCode::NOT_FOUND); 3];
+        let peer_client = Arc::new(
+            ScriptedPeerClient::new()
+                .queue(PeerMethod::FetchProcessProgress, 2, not_found.clone())
+                .queue(PeerMethod::FetchProcessProgress, 3, not_found)
+                .respond_to_all(
+                    PeerMethod::FetchProcessProgress,
+                    ScriptedResponse::share(42),
+                ),
+        );
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            2,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+        let process_id = repository
+            .create_process(
+                CreateProcessRequest::new(
+                    Uuid::new_v4(),
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+            .id();
+
+        for expected_fetches in [2, 4, 6, 8] {
+            notifier.ping();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert_eq!(
+                peer_client.calls(PeerMethod::FetchProcessProgress).len(),
+                expected_fetches
+            );
+        }
+        assert!(matches!(
+            repository.get_process(process_id).await.unwrap(),
+            AdditionProcess::AwaitingPeerSharesSum(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_process_unknown_to_peers_is_skipped_after_the_grace_period() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        // Both peers never know the process
+        let peer_client = Arc::new(ScriptedPeerClient::new().respond_to_all(
+            PeerMethod::FetchProcessProgress,
+            ScriptedResponse::Status(StatusCode::NOT_FOUND),
+        ));
+        let (orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            2,
+        );
+        let mut orchestrator = orchestrator.with_not_ready_grace_period(Duration::ZERO);
+        tokio::spawn(async move { orchestrator.run().await });
+        let process_id = repository
+            .create_process(
+                CreateProcessRequest::new(
+                    Uuid::new_v4(),
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+            .id();
+
+        for expected_fetches in [2, 4, 4, 4] {
+            notifier.ping();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert_eq!(
+                peer_client.calls(PeerMethod::FetchProcessProgress).len(),
+                expected_fetches
+            );
+        }
+        assert!(matches!(
+            repository.get_process(process_id).await.unwrap(),
+            AdditionProcess::AwaitingPeerShares(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_process_is_flagged_when_a_peer_persistently_misses_our_share() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            // Peer 3 has no share for us, it rejects every progress request with `400`
+            Arc::new(
+                ScriptedPeerClient::new()
+                    .respond_to_all(
+                        PeerMethod::FetchProcessProgress,
+                        ScriptedResponse::share(42),
+                    )
+                    .respond(
+                        PeerMethod::FetchProcessProgress,
+                        3,
+                        ScriptedResponse::Status(StatusCode::BAD_REQUEST),
+                    ),
+            ),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+        let process_id = Uuid::new_v4();
+        repository
+            .create_process(
+                CreateProcessRequest::new(
+                    process_id,
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let awaiting_process = async || match repository.get_process(process_id).await.unwrap() {
+            AdditionProcess::AwaitingPeerShares(p) => p,
+            _ => panic!("process should still await peer shares"),
+        };
+        for _ in 1..MISSING_SHARE_DIAGNOSTIC_THRESHOLD {
+            notifier.ping();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        // The share of peer 2 is received, the process only misses the share of peer 3
+        let process = awaiting_process().await;
+        assert_eq!(process.received_shares, HashMap::from([(2, 42)]));
+        assert_eq!(process.diagnostic, None);
+
+        notifier.ping();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let diagnostic = awaiting_process()
+            .await
+            .diagnostic
+            .expect("the process should be flagged with a diagnostic");
+        assert!(diagnostic.contains("peers [3] could not produce our share"));
+    }
+
+    /// Runs an orchestrator requiring the confirmation of 2 peers over a process reconstructed with the candidate sum 42.
+    /// # Returns
+    /// * The process once the orchestrator had the time to confirm it.
+    async fn confirm_reconstructed_process(final_sums: HashMap<u8, u64>) -> AdditionProcess {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        // Progresses are never fetched, the peers only report their final sums
+        let peer_client = final_sums.into_iter().fold(
+            ScriptedPeerClient::new().respond_to_all(
+                PeerMethod::FetchProcessProgress,
+                ScriptedResponse::Status(StatusCode::INTERNAL_SERVER_ERROR),
+            ),
+            |peer_client, (peer_id, final_sum)| {
+                peer_client.respond(
+                    PeerMethod::FetchFinalSum,
+                    peer_id,
+                    ScriptedResponse::FinalSum(Some(final_sum)),
+                )
+            },
+        );
+        let (orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            Arc::new(peer_client),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        let mut orchestrator = orchestrator.with_confirm_quorum(Some(2));
+        tokio::spawn(async move { orchestrator.run().await });
+        let request = CreateProcessRequest::new(
+            Uuid::new_v4(),
+            1,
+            &[2, 3],
+            None,
+            None,
+            &PeerPoints::default(),
+        )
+        .unwrap();
+        let process_id = request.process_id;
+        let input_shares = request.input_shares;
+        repository
+            .import_all(vec![AdditionProcess::Reconstructed(ReconstructedProcess {
+                id: process_id,
+                created_at: chrono::Utc::now(),
+                external_key: None,
+                priority: 0,
+                reconstructed_at: chrono::Utc::now(),
+                input_shares,
+                received_shares: HashMap::new(),
+                shares_sum: 0,
+                received_shares_sums: HashMap::new(),
+                candidate_sum: 42,
+            })])
+            .await
+            .unwrap();
+
+        notifier.ping();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        repository.get_process(process_id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconstructed_process_is_completed_once_confirmed() {
+        match confirm_reconstructed_process(HashMap::from([(2, 42), (3, 42)])).await {
+            AdditionProcess::Completed(p) => assert_eq!(p.final_sum, 42),
+            _ => panic!("process should be completed once its final sum is confirmed"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dissenting_peer_prevents_the_completion() {
+        match confirm_reconstructed_process(HashMap::from([(2, 42), (3, 43)])).await {
+            AdditionProcess::Reconstructed(p) => assert_eq!(p.candidate_sum, 42),
+            _ => panic!("process should stay reconstructed while a peer dissents"),
+        }
+    }
+
+    /// Repository failing to list the ongoing processes while unavailable, every other operation is delegated to an in memory repository
+    #[derive(Default)]
+    struct UnavailableRepository {
+        inner: InMemoryAdditionProcessRepository,
+        unavailable: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl AdditionProcessRepository for UnavailableRepository {
+        async fn lock_process(&self, process_id: Uuid) -> ProcessLockGuard {
+            self.inner.lock_process(process_id).await
+        }
+
+        fn register_cancellation(&self, process_id: Uuid) -> ProcessCancellationGuard {
+            self.inner.register_cancellation(process_id)
+        }
+
+        async fn get_process(&self, process_id: Uuid) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.get_process(process_id).await
+        }
+
+        async fn get_ongoing_processes(&self) -> Result<Vec<AdditionProcess>, RepositoryError> {
+            if self.unavailable.load(Ordering::SeqCst) {
+                return Err(RepositoryError::Internal(anyhow!("repository unavailable")));
+            }
+            self.inner.get_ongoing_processes().await
+        }
+
+        async fn get_process_by_external_key(
+            &self,
+            external_key: &str,
+        ) -> Result<Option<AdditionProcess>, RepositoryError> {
+            self.inner.get_process_by_external_key(external_key).await
+        }
+
+        async fn create_process(
+            &self,
+            request: CreateProcessRequest,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.create_process(request).await
+        }
+
+        async fn receive_shares(
+            &self,
+            request: ReceiveSharesRequest,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.receive_shares(request).await
+        }
+
+        async fn receive_shares_sums(
+            &self,
+            request: ReceiveSharesSumsRequest,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.receive_shares_sums(request).await
+        }
+
+        async fn confirm_final_sum(
+            &self,
+            process_id: Uuid,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.confirm_final_sum(process_id).await
+        }
+
+        async fn mark_unrecoverable(
+            &self,
+            process_id: Uuid,
+            reason: String,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.mark_unrecoverable(process_id, reason).await
+        }
+
+        async fn record_diagnostic(
+            &self,
+            process_id: Uuid,
+            diagnostic: String,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner.record_diagnostic(process_id, diagnostic).await
+        }
+
+        async fn mark_tampered(
+            &self,
+            process_id: Uuid,
+            received_shares_sums: HashMap<u8, u64>,
+            reason: String,
+        ) -> Result<AdditionProcess, RepositoryError> {
+            self.inner
+                .mark_tampered(process_id, received_shares_sums, reason)
+                .await
+        }
+
+        async fn delete_process(&self, process_id: Uuid) -> Result<(), RepositoryError> {
+            self.inner.delete_process(process_id).await
+        }
+
+        async fn list_process_ids(&self) -> Result<Vec<Uuid>, RepositoryError> {
+            self.inner.list_process_ids().await
+        }
+
+        async fn export_all(&self) -> Result<Vec<AdditionProcess>, RepositoryError> {
+            self.inner.export_all().await
+        }
+
+        async fn import_all(
+            &self,
+            processes: Vec<AdditionProcess>,
+        ) -> Result<usize, RepositoryError> {
+            self.inner.import_all(processes).await
+        }
+
+        async fn evict_completed_processes(
+            &self,
+            completed_before: chrono::DateTime<chrono::Utc>,
+        ) -> Result<usize, RepositoryError> {
+            self.inner.evict_completed_processes(completed_before).await
+        }
+
+        async fn statistics(&self) -> Result<ProcessesStatistics, RepositoryError> {
+            self.inner.statistics().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persistent_repository_failures_are_reported_as_failing() {
+        let repository = Arc::new(UnavailableRepository::default());
+        repository.unavailable.store(true, Ordering::SeqCst);
+        let metrics = Arc::new(Metrics::new());
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            Arc::new(DryRunPeerClient::new()),
+            1,
+            metrics.clone(),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+
+        for _ in 1..REPOSITORY_FAILURES_THRESHOLD {
+            notifier.ping();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(metrics.heartbeats.failing_tasks().is_empty());
+
+        notifier.ping();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(metrics.heartbeats.failing_tasks(), vec![HEARTBEAT_TASK]);
+
+        // A successful read recovers the orchestrator
+        repository.unavailable.store(false, Ordering::SeqCst);
+        notifier.ping();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(metrics.heartbeats.failing_tasks().is_empty());
+    }
+}
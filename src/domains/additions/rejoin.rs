@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::peer_communication::peer_client::PeerClient;
+
+use super::{
+    CreateProcessRequest, CreateProcessRequestError, PeerPoints,
+    repository::{AdditionProcessRepository, RepositoryError},
+};
+
+/// Creates the ongoing processes of the peers which are missing on the node, e.g. processes created while the node was down.
+///
+/// A missing process is created as a client would have, with a random input and the peers as participants, the node then takes part in it.
+pub struct ProcessesRejoiner {
+    repository: Arc<dyn AdditionProcessRepository>,
+    peer_client: Arc<dyn PeerClient>,
+    server_peer_id: u8,
+    peer_ids: Vec<u8>,
+    verification_threshold: Option<usize>,
+    points: PeerPoints,
+}
+
+impl ProcessesRejoiner {
+    pub fn new(
+        repository: Arc<dyn AdditionProcessRepository>,
+        peer_client: Arc<dyn PeerClient>,
+        server_peer_id: u8,
+        peer_ids: &[u8],
+        verification_threshold: Option<usize>,
+        points: PeerPoints,
+    ) -> Self {
+        Self {
+            repository,
+            peer_client,
+            server_peer_id,
+            peer_ids: peer_ids.to_vec(),
+            // Tampering detection needs two disjoint subsets of shares, as on creation
+            verification_threshold: verification_threshold
+                .filter(|threshold| 2 * threshold <= peer_ids.len() + 1),
+            points,
+        }
+    }
+
+    /// Lists the ongoing processes of every peer and creates the missing ones.
+    /// A peer which can not be reached is skipped, its processes are only rejoined if another peer lists them.
+    /// # Returns
+    /// * The IDs of the created processes.
+    pub async fn run(&self) -> Vec<Uuid> {
+        let mut created_process_ids = Vec::new();
+        for peer_id in &self.peer_ids {
+            let process_ids = match self.peer_client.fetch_ongoing_process_ids(*peer_id).await {
+                Ok(process_ids) => process_ids,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to list the ongoing processes of peer {peer_id}, they are not rejoined: {e}"
+                    );
+                    continue;
+                }
+            };
+            for process_id in process_ids {
+                match self.create_missing_process(process_id).await {
+                    Ok(true) => created_process_ids.push(process_id),
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to rejoin process {process_id}: {:?}", e)
+                    }
+                }
+            }
+        }
+        if !created_process_ids.is_empty() {
+            tracing::info!(
+                "{} ongoing processes of the peers rejoined",
+                created_process_ids.len()
+            );
+        }
+        created_process_ids
+    }
+
+    /// Creates a process unless it already exists.
+    /// # Returns
+    /// * Whether the process has been created.
+    async fn create_missing_process(&self, process_id: Uuid) -> Result<bool, anyhow::Error> {
+        let request = CreateProcessRequest::new(
+            process_id,
+            self.server_peer_id,
+            &self.peer_ids,
+            self.verification_threshold,
+            None,
+            &self.points,
+        )
+        .map_err(|e| match e {
+            CreateProcessRequestError::Unknown(err) => err,
+        })?;
+        match self.repository.create_process(request).await {
+            Ok(_) => Ok(true),
+            Err(RepositoryError::AlreadyExists(_)) => Ok(false),
+            Err(e) => Err(e.context("creating rejoined process").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::additions::repository::InMemoryAdditionProcessRepository,
+        peer_communication::scripted_peer_client::{
+            PeerMethod, ScriptedPeerClient, ScriptedResponse,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_missing_processes_are_created() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let known_process_id = Uuid::new_v4();
+        let missing_process_id = Uuid::new_v4();
+        repository
+            .create_process(
+                CreateProcessRequest::new(
+                    known_process_id,
+                    1,
+                    &[2, 3],
+                    None,
+                    None,
+                    &PeerPoints::default(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let known_input = repository
+            .get_process(known_process_id)
+            .await
+            .unwrap()
+            .input_shares()
+            .input;
+
+        let rejoiner = ProcessesRejoiner::new(
+            repository.clone(),
+            // Peer 2 lists the processes while peer 3 is unreachable
+            Arc::new(
+                ScriptedPeerClient::new()
+                    .respond(
+                        PeerMethod::FetchOngoingProcessIds,
+                        2,
+                        ScriptedResponse::ProcessIds(vec![known_process_id, missing_process_id]),
+                    )
+                    .respond(
+                        PeerMethod::FetchOngoingProcessIds,
+                        3,
+                        ScriptedResponse::Unreachable,
+                    ),
+            ),
+            1,
+            &[2, 3],
+            None,
+            PeerPoints::default(),
+        );
+        assert_eq!(rejoiner.run().await, vec![missing_process_id]);
+
+        let rejoined_process = repository.get_process(missing_process_id).await.unwrap();
+        let mut participants = rejoined_process
+            .input_shares()
+            .shares_to_send
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        participants.sort();
+        assert_eq!(participants, vec![2, 3]);
+        // The existing process is left untouched
+        assert_eq!(
+            repository
+                .get_process(known_process_id)
+                .await
+                .unwrap()
+                .input_shares()
+                .input,
+            known_input
+        );
+        // Rejoining again does not create anything
+        assert!(rejoiner.run().await.is_empty());
+    }
+}
@@ -1,24 +1,81 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
 use crate::domains::additions::{
     AwaitingPeerSharesProcess, AwaitingPeerSharesSumProcess, CompletedProcess,
+    ReconstructedProcess, TamperedProcess, UnrecoverableProcess,
 };
 
 use super::{
     AdditionProcess, CreateProcessRequest, ReceiveSharesRequest, ReceiveSharesSumsRequest,
+    reduce_share,
 };
+use thiserror::Error;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+#[derive(Debug, Error)]
+pub enum RepositoryError {
+    #[error("Process {0} not found")]
+    NotFound(Uuid),
+    #[error("Invalid process state: {0}")]
+    InvalidState(String),
+    #[error("Process {0} already exists")]
+    AlreadyExists(Uuid),
+    #[error("Every share of process {0} was already received")]
+    AllSharesReceived(Uuid),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl RepositoryError {
+    /// Adds context to an internal error, the other errors are kept as is.
+    pub fn context<C>(self, context: C) -> Self
+    where
+        C: std::fmt::Display + Send + Sync + 'static,
+    {
+        match self {
+            RepositoryError::Internal(e) => RepositoryError::Internal(e.context(context)),
+            e => e,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait AdditionProcessRepository: Send + Sync {
+    /// Acquires the lock of an addition process.
+    /// The lock must be held across a read, compute and write sequence on a process so that concurrent updates of the same process are serialized.
+    /// Updates of different processes proceed in parallel.
+    /// # Arguments
+    /// * `process_id` - The UUID of the addition process to lock.
+    async fn lock_process(&self, process_id: Uuid) -> ProcessLockGuard;
+
+    /// Registers a cancellation of an addition process, cancelled once the process is deleted.
+    /// It must be held across the work on the process which becomes pointless once it is deleted, e.g. requests to peers.
+    /// # Arguments
+    /// * `process_id` - The UUID of the addition process.
+    fn register_cancellation(&self, process_id: Uuid) -> ProcessCancellationGuard;
+
     /// Retrieves an addition process by its ID.
     /// # Arguments
     /// * `process_id` - The UUID of the addition process to retrieve.
-    async fn get_process(&self, process_id: Uuid) -> Result<AdditionProcess, anyhow::Error>;
+    async fn get_process(&self, process_id: Uuid) -> Result<AdditionProcess, RepositoryError>;
 
     /// Retrieves all ongoing addition processes.
-    async fn get_ongoing_processes(&self) -> Result<Vec<AdditionProcess>, anyhow::Error>;
+    async fn get_ongoing_processes(&self) -> Result<Vec<AdditionProcess>, RepositoryError>;
+
+    /// Retrieves an addition process by the key of the process in the client application.
+    /// # Arguments
+    /// * `external_key` - The key given at the creation of the process.
+    /// # Returns
+    /// * The process, `None` if no process has been created with this key.
+    async fn get_process_by_external_key(
+        &self,
+        external_key: &str,
+    ) -> Result<Option<AdditionProcess>, RepositoryError>;
 
     /// Creates a new addition process.
     /// # Arguments
@@ -26,40 +83,229 @@ pub trait AdditionProcessRepository: Send + Sync {
     async fn create_process(
         &self,
         request: CreateProcessRequest,
-    ) -> Result<AdditionProcess, anyhow::Error>;
+    ) -> Result<AdditionProcess, RepositoryError>;
 
     /// Receives shares for an existing addition process.
     /// If a shares sum is provided, the process is updated to the next state.
+    /// A received share is never overwritten, shares received once every share of the process is received are rejected.
     /// # Arguments
     /// * `request` - The request containing the shares to be received.
     async fn receive_shares(
         &self,
         request: ReceiveSharesRequest,
-    ) -> Result<AdditionProcess, anyhow::Error>;
+    ) -> Result<AdditionProcess, RepositoryError>;
 
     /// Receives shares sums for an existing addition process.
-    /// If the final sum is provided, the process is marked as completed.
+    /// If the final sum is provided, the process is marked as completed, or as reconstructed if the final sum awaits its confirmation.
     /// # Arguments
     /// * `request` - The request containing the shares sums to be received.
     async fn receive_shares_sums(
         &self,
         request: ReceiveSharesSumsRequest,
-    ) -> Result<AdditionProcess, anyhow::Error>;
+    ) -> Result<AdditionProcess, RepositoryError>;
+
+    /// Completes a reconstructed addition process, its candidate sum has been confirmed by the peers.
+    /// # Arguments
+    /// * `process_id` - The UUID of the reconstructed addition process.
+    async fn confirm_final_sum(&self, process_id: Uuid)
+    -> Result<AdditionProcess, RepositoryError>;
+
+    /// Marks an ongoing addition process as unrecoverable, it is then no longer orchestrated.
+    /// # Arguments
+    /// * `process_id` - The UUID of the addition process,
+    /// * `reason` - The reason why the process can not be recovered.
+    async fn mark_unrecoverable(
+        &self,
+        process_id: Uuid,
+        reason: String,
+    ) -> Result<AdditionProcess, RepositoryError>;
+
+    /// Records a diagnostic explaining why an addition process awaiting peer shares is stalled.
+    /// # Arguments
+    /// * `process_id` - The UUID of the addition process,
+    /// * `diagnostic` - The explanation of the stall, it replaces the previous one.
+    async fn record_diagnostic(
+        &self,
+        process_id: Uuid,
+        diagnostic: String,
+    ) -> Result<AdditionProcess, RepositoryError>;
+
+    /// Marks an addition process awaiting shares sums as tampered, it is then no longer orchestrated.
+    /// # Arguments
+    /// * `process_id` - The UUID of the addition process,
+    /// * `received_shares_sums` - Every shares sum received from peers,
+    /// * `reason` - The detected inconsistency.
+    async fn mark_tampered(
+        &self,
+        process_id: Uuid,
+        received_shares_sums: HashMap<u8, u64>,
+        reason: String,
+    ) -> Result<AdditionProcess, RepositoryError>;
 
-    /// Deletes an addition process by its ID.
+    /// Deletes an addition process by its ID, its registered cancellations are cancelled.
     /// # Arguments
     /// * `process_id` - The UUID of the addition process to delete.
-    async fn delete_process(&self, process_id: Uuid) -> Result<(), anyhow::Error>;
+    async fn delete_process(&self, process_id: Uuid) -> Result<(), RepositoryError>;
+
+    /// Lists the IDs of all addition processes, whatever their state.
+    /// Processes can then be read one by one without holding the whole set in memory.
+    async fn list_process_ids(&self) -> Result<Vec<Uuid>, RepositoryError>;
+
+    /// Exports all addition processes, whatever their state.
+    async fn export_all(&self) -> Result<Vec<AdditionProcess>, RepositoryError>;
+
+    /// Imports addition processes, e.g. exported from another node.
+    /// No process is imported if one of them already exists.
+    /// # Arguments
+    /// * `processes` - The addition processes to import.
+    /// # Returns
+    /// * The number of imported processes.
+    async fn import_all(&self, processes: Vec<AdditionProcess>) -> Result<usize, RepositoryError>;
+
+    /// Evicts the processes completed before the given time, other processes are kept.
+    /// # Arguments
+    /// * `completed_before` - The completion time before which completed processes are evicted.
+    /// # Returns
+    /// * The number of evicted processes.
+    async fn evict_completed_processes(
+        &self,
+        completed_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, RepositoryError>;
+
+    /// Counts the processes by state and averages the completion duration of the completed ones, without cloning the processes.
+    async fn statistics(&self) -> Result<ProcessesStatistics, RepositoryError>;
+}
+
+/// Aggregates over all addition processes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProcessesStatistics {
+    pub awaiting_peer_shares: usize,
+    pub awaiting_peer_shares_sum: usize,
+    pub reconstructed: usize,
+    pub completed: usize,
+    pub unrecoverable: usize,
+    pub tampered: usize,
+    /// Average duration between the creation and the completion of the completed processes, `None` without completed process
+    pub average_completion_duration: Option<std::time::Duration>,
+}
+
+/// Keyed locks, one per process.
+#[derive(Clone, Default)]
+pub struct ProcessLocks {
+    locks: Arc<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl ProcessLocks {
+    pub async fn lock(&self, process_id: Uuid) -> ProcessLockGuard {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            locks.entry(process_id).or_default().clone()
+        };
+        let guard = lock.lock_owned().await;
+        ProcessLockGuard {
+            locks: self.clone(),
+            process_id,
+            guard: Some(guard),
+        }
+    }
+}
+
+/// Guard of the lock of a process, the lock is released on drop.
+pub struct ProcessLockGuard {
+    locks: ProcessLocks,
+    process_id: Uuid,
+    guard: Option<tokio::sync::OwnedMutexGuard<()>>,
+}
+
+impl Drop for ProcessLockGuard {
+    fn drop(&mut self) {
+        self.guard.take();
+        // The lock is removed once no one holds or waits for it anymore
+        let mut locks = self.locks.locks.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(lock) = locks.get(&self.process_id)
+            && Arc::strong_count(lock) == 1
+        {
+            locks.remove(&self.process_id);
+        }
+    }
+}
+
+/// Keyed cancellation tokens, one per process with registered cancellations.
+#[derive(Clone, Default)]
+pub struct ProcessCancellations {
+    /// Token of each process and the number of guards holding it
+    tokens: Arc<Mutex<HashMap<Uuid, (CancellationToken, usize)>>>,
+}
+
+impl ProcessCancellations {
+    pub fn register(&self, process_id: Uuid) -> ProcessCancellationGuard {
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        let (token, holders) = tokens.entry(process_id).or_default();
+        *holders += 1;
+        ProcessCancellationGuard {
+            cancellations: self.clone(),
+            process_id,
+            token: token.clone(),
+        }
+    }
+
+    /// Cancels the registered cancellations of a process.
+    pub fn cancel(&self, process_id: Uuid) {
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((token, _)) = tokens.remove(&process_id) {
+            token.cancel();
+        }
+    }
+}
+
+/// Guard of a registered cancellation of a process, the registration is released on drop.
+pub struct ProcessCancellationGuard {
+    cancellations: ProcessCancellations,
+    process_id: Uuid,
+    token: CancellationToken,
+}
+
+impl ProcessCancellationGuard {
+    /// Token cancelled once the process is deleted.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for ProcessCancellationGuard {
+    fn drop(&mut self) {
+        let mut tokens = self
+            .cancellations
+            .tokens
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        // The entry is already removed if the process has been cancelled
+        if let Some((token, holders)) = tokens.get_mut(&self.process_id)
+            && !token.is_cancelled()
+        {
+            *holders -= 1;
+            if *holders == 0 {
+                tokens.remove(&self.process_id);
+            }
+        }
+    }
 }
 
 pub struct InMemoryAdditionProcessRepository {
     processes: RwLock<HashMap<Uuid, AdditionProcess>>,
+    /// Process IDs by external key, always locked after `processes`
+    external_keys: RwLock<HashMap<String, Uuid>>,
+    locks: ProcessLocks,
+    cancellations: ProcessCancellations,
 }
 
 impl InMemoryAdditionProcessRepository {
     pub fn new() -> Self {
         Self {
             processes: RwLock::new(HashMap::new()),
+            external_keys: RwLock::new(HashMap::new()),
+            locks: ProcessLocks::default(),
+            cancellations: ProcessCancellations::default(),
         }
     }
 }
@@ -72,37 +318,75 @@ impl Default for InMemoryAdditionProcessRepository {
 
 #[async_trait::async_trait]
 impl AdditionProcessRepository for InMemoryAdditionProcessRepository {
-    async fn get_process(&self, process_id: Uuid) -> Result<AdditionProcess, anyhow::Error> {
+    async fn lock_process(&self, process_id: Uuid) -> ProcessLockGuard {
+        self.locks.lock(process_id).await
+    }
+
+    fn register_cancellation(&self, process_id: Uuid) -> ProcessCancellationGuard {
+        self.cancellations.register(process_id)
+    }
+
+    async fn get_process(&self, process_id: Uuid) -> Result<AdditionProcess, RepositoryError> {
         let processes = self.processes.read().await;
         processes
             .get(&process_id)
             .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Process not found"))
+            .ok_or(RepositoryError::NotFound(process_id))
     }
 
-    async fn get_ongoing_processes(&self) -> Result<Vec<AdditionProcess>, anyhow::Error> {
+    async fn get_ongoing_processes(&self) -> Result<Vec<AdditionProcess>, RepositoryError> {
         let processes = self.processes.read().await;
         let mut ongoing_processes = Vec::new();
         for process in processes.values() {
-            if !matches!(process, AdditionProcess::Completed(_)) {
+            if !matches!(
+                process,
+                AdditionProcess::Completed(_)
+                    | AdditionProcess::Unrecoverable(_)
+                    | AdditionProcess::Tampered(_)
+            ) {
                 ongoing_processes.push(process.clone());
             }
         }
         Ok(ongoing_processes)
     }
 
+    async fn get_process_by_external_key(
+        &self,
+        external_key: &str,
+    ) -> Result<Option<AdditionProcess>, RepositoryError> {
+        let processes = self.processes.read().await;
+        let external_keys = self.external_keys.read().await;
+        Ok(external_keys
+            .get(external_key)
+            .and_then(|process_id| processes.get(process_id))
+            .cloned())
+    }
+
     async fn create_process(
         &self,
         request: CreateProcessRequest,
-    ) -> Result<AdditionProcess, anyhow::Error> {
+    ) -> Result<AdditionProcess, RepositoryError> {
         let mut processes = self.processes.write().await;
         if processes.contains_key(&request.process_id) {
-            return Err(anyhow::anyhow!("Process with this ID already exists"));
+            return Err(RepositoryError::AlreadyExists(request.process_id));
+        }
+        let mut external_keys = self.external_keys.write().await;
+        if let Some(external_key) = &request.external_key {
+            if let Some(process_id) = external_keys.get(external_key) {
+                return Err(RepositoryError::InvalidState(format!(
+                    "external key {external_key} is already used by process {process_id}"
+                )));
+            }
+            external_keys.insert(external_key.clone(), request.process_id);
         }
         let process = AdditionProcess::AwaitingPeerShares(AwaitingPeerSharesProcess {
             id: request.process_id,
+            created_at: chrono::Utc::now(),
+            external_key: request.external_key.clone(),
+            priority: request.priority,
             input_shares: request.input_shares.clone(),
             received_shares: HashMap::new(),
+            diagnostic: None,
         });
         processes.insert(request.process_id, process.clone());
         Ok(process)
@@ -111,28 +395,48 @@ impl AdditionProcessRepository for InMemoryAdditionProcessRepository {
     async fn receive_shares(
         &self,
         request: ReceiveSharesRequest,
-    ) -> Result<AdditionProcess, anyhow::Error> {
+    ) -> Result<AdditionProcess, RepositoryError> {
         let mut processes = self.processes.write().await;
         let process = processes
             .get_mut(&request.process_id)
-            .ok_or_else(|| anyhow::anyhow!("Process not found"))?;
+            .ok_or(RepositoryError::NotFound(request.process_id))?;
 
         let internal_process = match process {
             AdditionProcess::AwaitingPeerShares(p) => p,
+            AdditionProcess::AwaitingPeerSharesSum(_)
+            | AdditionProcess::Reconstructed(_)
+            | AdditionProcess::Completed(_) => {
+                return Err(RepositoryError::AllSharesReceived(request.process_id));
+            }
             _ => {
-                return Err(anyhow::anyhow!(
-                    "Process is not in a state to receive shares"
+                return Err(RepositoryError::InvalidState(
+                    "Process is not in a state to receive shares".to_string(),
                 ));
             }
         };
 
+        let expected_shares_count = internal_process.input_shares.shares_to_send.len();
+        let new_shares_count = request
+            .received_shares
+            .keys()
+            .filter(|peer_id| !internal_process.received_shares.contains_key(peer_id))
+            .count();
+        if internal_process.received_shares.len() + new_shares_count > expected_shares_count {
+            return Err(RepositoryError::AllSharesReceived(request.process_id));
+        }
         for (peer_id, share) in &request.received_shares {
-            internal_process.received_shares.insert(*peer_id, *share);
+            internal_process
+                .received_shares
+                .entry(*peer_id)
+                .or_insert_with(|| reduce_share(*share));
         }
 
         if let Some(shares_sum) = request.computed_shares_sum {
             let internal_process = AwaitingPeerSharesSumProcess {
                 id: internal_process.id,
+                created_at: internal_process.created_at,
+                external_key: internal_process.external_key.clone(),
+                priority: internal_process.priority,
                 input_shares: internal_process.input_shares.clone(),
                 received_shares: internal_process.received_shares.clone(),
                 shares_sum,
@@ -147,17 +451,17 @@ impl AdditionProcessRepository for InMemoryAdditionProcessRepository {
     async fn receive_shares_sums(
         &self,
         request: ReceiveSharesSumsRequest,
-    ) -> Result<AdditionProcess, anyhow::Error> {
+    ) -> Result<AdditionProcess, RepositoryError> {
         let mut processes = self.processes.write().await;
         let process = processes
             .get_mut(&request.process_id)
-            .ok_or_else(|| anyhow::anyhow!("Process not found"))?;
+            .ok_or(RepositoryError::NotFound(request.process_id))?;
 
         let internal_process = match process {
             AdditionProcess::AwaitingPeerSharesSum(p) => p,
             _ => {
-                return Err(anyhow::anyhow!(
-                    "Process is not in a state to receive shares sums"
+                return Err(RepositoryError::InvalidState(
+                    "Process is not in a state to receive shares sums".to_string(),
                 ));
             }
         };
@@ -165,12 +469,32 @@ impl AdditionProcessRepository for InMemoryAdditionProcessRepository {
         for (peer_id, share_sum) in &request.received_shares_sums {
             internal_process
                 .received_shares_sums
-                .insert(*peer_id, *share_sum);
+                .insert(*peer_id, reduce_share(*share_sum));
         }
 
-        if let Some(final_sum) = request.final_sum {
+        if let Some(candidate_sum) = request.final_sum
+            && request.awaiting_confirmation
+        {
+            let reconstructed_process = ReconstructedProcess {
+                id: internal_process.id,
+                created_at: internal_process.created_at,
+                external_key: internal_process.external_key.clone(),
+                priority: internal_process.priority,
+                reconstructed_at: chrono::Utc::now(),
+                input_shares: internal_process.input_shares.clone(),
+                received_shares: internal_process.received_shares.clone(),
+                shares_sum: internal_process.shares_sum,
+                received_shares_sums: internal_process.received_shares_sums.clone(),
+                candidate_sum,
+            };
+            *process = AdditionProcess::Reconstructed(reconstructed_process);
+        } else if let Some(final_sum) = request.final_sum {
             let completed_process = CompletedProcess {
                 id: internal_process.id,
+                created_at: internal_process.created_at,
+                external_key: internal_process.external_key.clone(),
+                priority: internal_process.priority,
+                completed_at: chrono::Utc::now(),
                 input_shares: internal_process.input_shares.clone(),
                 received_shares: internal_process.received_shares.clone(),
                 shares_sum: internal_process.shares_sum,
@@ -183,9 +507,474 @@ impl AdditionProcessRepository for InMemoryAdditionProcessRepository {
         Ok(process.clone())
     }
 
-    async fn delete_process(&self, process_id: Uuid) -> Result<(), anyhow::Error> {
+    async fn confirm_final_sum(
+        &self,
+        process_id: Uuid,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let mut processes = self.processes.write().await;
+        let process = processes
+            .get_mut(&process_id)
+            .ok_or(RepositoryError::NotFound(process_id))?;
+        let AdditionProcess::Reconstructed(reconstructed_process) = process else {
+            return Err(RepositoryError::InvalidState(
+                "Only a reconstructed process can have its final sum confirmed".to_string(),
+            ));
+        };
+        *process = AdditionProcess::Completed(CompletedProcess {
+            id: reconstructed_process.id,
+            created_at: reconstructed_process.created_at,
+            external_key: reconstructed_process.external_key.clone(),
+            priority: reconstructed_process.priority,
+            completed_at: chrono::Utc::now(),
+            input_shares: reconstructed_process.input_shares.clone(),
+            received_shares: reconstructed_process.received_shares.clone(),
+            shares_sum: reconstructed_process.shares_sum,
+            received_shares_sums: reconstructed_process.received_shares_sums.clone(),
+            final_sum: reconstructed_process.candidate_sum,
+        });
+        Ok(process.clone())
+    }
+
+    async fn mark_unrecoverable(
+        &self,
+        process_id: Uuid,
+        reason: String,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let mut processes = self.processes.write().await;
+        let process = processes
+            .get_mut(&process_id)
+            .ok_or(RepositoryError::NotFound(process_id))?;
+        match process {
+            AdditionProcess::Completed(_) => {
+                return Err(RepositoryError::InvalidState(
+                    "Completed process can not be marked as unrecoverable".to_string(),
+                ));
+            }
+            AdditionProcess::Unrecoverable(_) | AdditionProcess::Tampered(_) => {}
+            _ => {
+                *process = AdditionProcess::Unrecoverable(UnrecoverableProcess {
+                    id: process.id(),
+                    created_at: process.created_at(),
+                    external_key: process.external_key().map(str::to_string),
+                    priority: process.priority(),
+                    input_shares: process.input_shares().clone(),
+                    reason,
+                });
+            }
+        }
+        Ok(process.clone())
+    }
+
+    async fn record_diagnostic(
+        &self,
+        process_id: Uuid,
+        diagnostic: String,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let mut processes = self.processes.write().await;
+        let process = processes
+            .get_mut(&process_id)
+            .ok_or(RepositoryError::NotFound(process_id))?;
+        let AdditionProcess::AwaitingPeerShares(awaiting_process) = process else {
+            return Err(RepositoryError::InvalidState(
+                "Only a process awaiting shares can be flagged with a diagnostic".to_string(),
+            ));
+        };
+        awaiting_process.diagnostic = Some(diagnostic);
+        Ok(process.clone())
+    }
+
+    async fn mark_tampered(
+        &self,
+        process_id: Uuid,
+        received_shares_sums: HashMap<u8, u64>,
+        reason: String,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let mut processes = self.processes.write().await;
+        let process = processes
+            .get_mut(&process_id)
+            .ok_or(RepositoryError::NotFound(process_id))?;
+        let AdditionProcess::AwaitingPeerSharesSum(awaiting_process) = process else {
+            return Err(RepositoryError::InvalidState(
+                "Only a process awaiting shares sums can be marked as tampered".to_string(),
+            ));
+        };
+        *process = AdditionProcess::Tampered(TamperedProcess {
+            id: awaiting_process.id,
+            created_at: awaiting_process.created_at,
+            external_key: awaiting_process.external_key.clone(),
+            priority: awaiting_process.priority,
+            input_shares: awaiting_process.input_shares.clone(),
+            received_shares: awaiting_process.received_shares.clone(),
+            shares_sum: awaiting_process.shares_sum,
+            received_shares_sums: received_shares_sums
+                .into_iter()
+                .map(|(peer_id, share_sum)| (peer_id, reduce_share(share_sum)))
+                .collect(),
+            reason,
+        });
+        Ok(process.clone())
+    }
+
+    async fn delete_process(&self, process_id: Uuid) -> Result<(), RepositoryError> {
         let mut processes = self.processes.write().await;
-        processes.remove(&process_id);
+        let process = processes
+            .remove(&process_id)
+            .ok_or(RepositoryError::NotFound(process_id))?;
+        if let Some(external_key) = process.external_key() {
+            self.external_keys.write().await.remove(external_key);
+        }
+        self.cancellations.cancel(process_id);
         Ok(())
     }
+
+    async fn list_process_ids(&self) -> Result<Vec<Uuid>, RepositoryError> {
+        let processes = self.processes.read().await;
+        Ok(processes.keys().cloned().collect())
+    }
+
+    async fn export_all(&self) -> Result<Vec<AdditionProcess>, RepositoryError> {
+        let processes = self.processes.read().await;
+        Ok(processes.values().cloned().collect())
+    }
+
+    async fn import_all(
+        &self,
+        imported_processes: Vec<AdditionProcess>,
+    ) -> Result<usize, RepositoryError> {
+        let mut processes = self.processes.write().await;
+        if let Some(existing) = imported_processes
+            .iter()
+            .find(|p| processes.contains_key(&p.id()))
+        {
+            return Err(RepositoryError::AlreadyExists(existing.id()));
+        }
+        let mut external_keys = self.external_keys.write().await;
+        let mut imported_keys = HashSet::new();
+        for external_key in imported_processes.iter().filter_map(|p| p.external_key()) {
+            if external_keys.contains_key(external_key) || !imported_keys.insert(external_key) {
+                return Err(RepositoryError::InvalidState(format!(
+                    "external key {external_key} is already used"
+                )));
+            }
+        }
+        let count = imported_processes.len();
+        for process in imported_processes {
+            if let Some(external_key) = process.external_key() {
+                external_keys.insert(external_key.to_string(), process.id());
+            }
+            processes.insert(process.id(), process);
+        }
+        Ok(count)
+    }
+
+    async fn evict_completed_processes(
+        &self,
+        completed_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, RepositoryError> {
+        let mut processes = self.processes.write().await;
+        let mut external_keys = self.external_keys.write().await;
+        let count = processes.len();
+        processes.retain(|_, process| {
+            let evicted = matches!(process, AdditionProcess::Completed(p) if p.completed_at < completed_before);
+            if evicted && let Some(external_key) = process.external_key() {
+                external_keys.remove(external_key);
+            }
+            !evicted
+        });
+        Ok(count - processes.len())
+    }
+
+    async fn statistics(&self) -> Result<ProcessesStatistics, RepositoryError> {
+        let processes = self.processes.read().await;
+        let mut statistics = ProcessesStatistics::default();
+        let mut total_completion_duration = std::time::Duration::ZERO;
+        for process in processes.values() {
+            match process {
+                AdditionProcess::AwaitingPeerShares(_) => statistics.awaiting_peer_shares += 1,
+                AdditionProcess::AwaitingPeerSharesSum(_) => {
+                    statistics.awaiting_peer_shares_sum += 1
+                }
+                AdditionProcess::Reconstructed(_) => statistics.reconstructed += 1,
+                AdditionProcess::Completed(p) => {
+                    statistics.completed += 1;
+                    total_completion_duration += p.completion_duration();
+                }
+                AdditionProcess::Unrecoverable(_) => statistics.unrecoverable += 1,
+                AdditionProcess::Tampered(_) => statistics.tampered += 1,
+            }
+        }
+        if statistics.completed > 0 {
+            statistics.average_completion_duration =
+                Some(total_completion_duration / statistics.completed as u32);
+        }
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::additions::{InputShares, PRIME, ReceiveSharesRequest as Request};
+
+    fn create_process_request() -> CreateProcessRequest {
+        CreateProcessRequest {
+            process_id: Uuid::new_v4(),
+            input_shares: InputShares {
+                input: 12,
+                own_share: 34,
+                shares_to_send: HashMap::from([(2, 56), (3, 78)]),
+                verification_threshold: None,
+                signed: false,
+            },
+            external_key: None,
+            priority: 0,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_receptions_do_not_lose_shares() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_ids = (2..=21).collect::<Vec<u8>>();
+        let mut request = create_process_request();
+        request.input_shares.shares_to_send =
+            peer_ids.iter().map(|peer_id| (*peer_id, 0)).collect();
+        let process_id = repository.create_process(request).await.unwrap().id();
+
+        let tasks = peer_ids
+            .iter()
+            .map(|peer_id| {
+                let repository = repository.clone();
+                let peer_id = *peer_id;
+                tokio::spawn(async move {
+                    let _lock = repository.lock_process(process_id).await;
+                    let current = match repository.get_process(process_id).await.unwrap() {
+                        AdditionProcess::AwaitingPeerShares(p) => p,
+                        _ => panic!("expected process awaiting peer shares"),
+                    };
+                    tokio::task::yield_now().await;
+                    let request = Request::new(&current, HashMap::from([(peer_id, 1)])).unwrap();
+                    repository.receive_shares(request).await.unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        match repository.get_process(process_id).await.unwrap() {
+            AdditionProcess::AwaitingPeerSharesSum(p) => {
+                assert_eq!(p.received_shares.len(), peer_ids.len());
+                // Own share is 34, every peer sent 1
+                assert_eq!(p.shares_sum, 34 + peer_ids.len() as u64);
+            }
+            _ => panic!("expected process awaiting peer shares sums"),
+        }
+        assert!(repository.locks.locks.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_extra_share_after_the_share_round_is_rejected() {
+        let repository = InMemoryAdditionProcessRepository::new();
+        let process_id = repository
+            .create_process(create_process_request())
+            .await
+            .unwrap()
+            .id();
+        let AdditionProcess::AwaitingPeerShares(process) =
+            repository.get_process(process_id).await.unwrap()
+        else {
+            panic!("expected process awaiting peer shares");
+        };
+        repository
+            .receive_shares(Request::new(&process, HashMap::from([(2, 5), (3, 6)])).unwrap())
+            .await
+            .unwrap();
+
+        let extra_share = ReceiveSharesRequest {
+            process_id,
+            received_shares: HashMap::from([(2, 100)]),
+            computed_shares_sum: Some(1),
+        };
+        assert!(matches!(
+            repository.receive_shares(extra_share).await,
+            Err(RepositoryError::AllSharesReceived(id)) if id == process_id
+        ));
+        match repository.get_process(process_id).await.unwrap() {
+            AdditionProcess::AwaitingPeerSharesSum(p) => {
+                assert_eq!(p.received_shares, HashMap::from([(2, 5), (3, 6)]));
+                assert_eq!(p.shares_sum, 34 + 5 + 6);
+            }
+            _ => panic!("expected process awaiting peer shares sums"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_received_shares_are_stored_reduced() {
+        let repository = InMemoryAdditionProcessRepository::new();
+        let process_id = repository
+            .create_process(create_process_request())
+            .await
+            .unwrap()
+            .id();
+        repository
+            .receive_shares(ReceiveSharesRequest {
+                process_id,
+                received_shares: HashMap::from([(2, PRIME + 5), (3, 6)]),
+                computed_shares_sum: Some(45),
+            })
+            .await
+            .unwrap();
+        repository
+            .receive_shares_sums(ReceiveSharesSumsRequest {
+                process_id,
+                received_shares_sums: HashMap::from([(2, 2 * PRIME + 7)]),
+                final_sum: None,
+                awaiting_confirmation: false,
+            })
+            .await
+            .unwrap();
+
+        match repository.get_process(process_id).await.unwrap() {
+            AdditionProcess::AwaitingPeerSharesSum(p) => {
+                assert_eq!(p.received_shares, HashMap::from([(2, 5), (3, 6)]));
+                assert_eq!(p.received_shares_sums, HashMap::from([(2, 7)]));
+            }
+            _ => panic!("expected process awaiting peer shares sums"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import() {
+        let source = InMemoryAdditionProcessRepository::new();
+        let ongoing = source
+            .create_process(create_process_request())
+            .await
+            .unwrap();
+        let advanced = source
+            .create_process(create_process_request())
+            .await
+            .unwrap();
+        source
+            .receive_shares(ReceiveSharesRequest {
+                process_id: advanced.id(),
+                received_shares: HashMap::from([(2, 1), (3, 2)]),
+                computed_shares_sum: Some(37),
+            })
+            .await
+            .unwrap();
+
+        let exported = serde_json::to_string(&source.export_all().await.unwrap()).unwrap();
+
+        let destination = InMemoryAdditionProcessRepository::new();
+        let imported = destination
+            .import_all(serde_json::from_str(&exported).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(imported, 2);
+
+        let mut ongoing_ids = destination
+            .get_ongoing_processes()
+            .await
+            .unwrap()
+            .iter()
+            .map(|p| p.id())
+            .collect::<Vec<_>>();
+        ongoing_ids.sort();
+        let mut expected_ids = vec![ongoing.id(), advanced.id()];
+        expected_ids.sort();
+        assert_eq!(ongoing_ids, expected_ids);
+        match destination.get_process(advanced.id()).await.unwrap() {
+            AdditionProcess::AwaitingPeerSharesSum(p) => {
+                assert_eq!(p.shares_sum, 37);
+                assert_eq!(p.received_shares, HashMap::from([(2, 1), (3, 2)]));
+                assert_eq!(
+                    p.input_shares.shares_to_send,
+                    HashMap::from([(2, 56), (3, 78)])
+                );
+            }
+            _ => panic!("expected process awaiting peer shares sums"),
+        }
+
+        // Importing twice is rejected
+        assert!(matches!(
+            destination
+                .import_all(serde_json::from_str(&exported).unwrap())
+                .await,
+            Err(RepositoryError::AlreadyExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_repository_errors() {
+        let repository = InMemoryAdditionProcessRepository::new();
+        let unknown_process_id = Uuid::new_v4();
+        assert!(matches!(
+            repository.get_process(unknown_process_id).await,
+            Err(RepositoryError::NotFound(id)) if id == unknown_process_id
+        ));
+        assert!(matches!(
+            repository.delete_process(unknown_process_id).await,
+            Err(RepositoryError::NotFound(_))
+        ));
+
+        let process_id = repository
+            .create_process(create_process_request())
+            .await
+            .unwrap()
+            .id();
+        let duplicated_request = CreateProcessRequest {
+            process_id,
+            ..create_process_request()
+        };
+        assert!(matches!(
+            repository.create_process(duplicated_request).await,
+            Err(RepositoryError::AlreadyExists(id)) if id == process_id
+        ));
+        assert!(matches!(
+            repository
+                .mark_tampered(process_id, HashMap::new(), "tampered".to_string())
+                .await,
+            Err(RepositoryError::InvalidState(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_external_key_index() {
+        let repository = InMemoryAdditionProcessRepository::new();
+        let process_id = repository
+            .create_process(create_process_request().with_external_key(Some("job-1".to_string())))
+            .await
+            .unwrap()
+            .id();
+        let process = repository
+            .get_process_by_external_key("job-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(process.id(), process_id);
+        assert_eq!(process.external_key(), Some("job-1"));
+
+        // A key identifies a single process
+        assert!(matches!(
+            repository
+                .create_process(
+                    create_process_request().with_external_key(Some("job-1".to_string()))
+                )
+                .await,
+            Err(RepositoryError::InvalidState(_))
+        ));
+
+        repository.delete_process(process_id).await.unwrap();
+        assert!(
+            repository
+                .get_process_by_external_key("job-1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        repository
+            .create_process(create_process_request().with_external_key(Some("job-1".to_string())))
+            .await
+            .unwrap();
+    }
 }
@@ -0,0 +1,118 @@
+use std::{sync::Arc, time::Duration};
+
+use super::repository::AdditionProcessRepository;
+
+/// Periodically evicts the completed processes older than the retention.
+/// Ongoing and unrecoverable processes are kept.
+pub struct CompletedProcessesSweeper {
+    repository: Arc<dyn AdditionProcessRepository>,
+    retention: Duration,
+}
+
+impl CompletedProcessesSweeper {
+    pub fn new(repository: Arc<dyn AdditionProcessRepository>, retention: Duration) -> Self {
+        Self {
+            repository,
+            retention,
+        }
+    }
+
+    /// Runs the sweeper loop, evicting expired completed processes at the specified interval.
+    /// # Arguments
+    /// * `interval` - The duration between each sweep.
+    pub async fn run(&self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sweep().await {
+                tracing::error!("Failed to evict completed addition processes: {:?}", e);
+            }
+        }
+    }
+
+    async fn sweep(&self) -> Result<(), anyhow::Error> {
+        let retention = chrono::TimeDelta::from_std(self.retention)?;
+        let evicted = self
+            .repository
+            .evict_completed_processes(chrono::Utc::now() - retention)
+            .await?;
+        if evicted > 0 {
+            tracing::info!("{evicted} completed addition processes evicted");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domains::additions::{
+        AdditionProcess, CreateProcessRequest, InputShares, ReceiveSharesRequest,
+        ReceiveSharesSumsRequest, repository::InMemoryAdditionProcessRepository,
+    };
+
+    async fn create_process(repository: &InMemoryAdditionProcessRepository) -> Uuid {
+        repository
+            .create_process(CreateProcessRequest {
+                process_id: Uuid::new_v4(),
+                input_shares: InputShares {
+                    input: 12,
+                    own_share: 34,
+                    shares_to_send: HashMap::from([(2, 56)]),
+                    verification_threshold: None,
+                    signed: false,
+                },
+                external_key: None,
+                priority: 0,
+            })
+            .await
+            .unwrap()
+            .id()
+    }
+
+    #[tokio::test]
+    async fn test_completed_processes_are_evicted_after_retention() {
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let ongoing_id = create_process(&repository).await;
+        let completed_id = create_process(&repository).await;
+        repository
+            .receive_shares(ReceiveSharesRequest {
+                process_id: completed_id,
+                received_shares: HashMap::from([(2, 1)]),
+                computed_shares_sum: Some(35),
+            })
+            .await
+            .unwrap();
+        repository
+            .receive_shares_sums(ReceiveSharesSumsRequest {
+                process_id: completed_id,
+                received_shares_sums: HashMap::from([(2, 70)]),
+                final_sum: Some(42),
+                awaiting_confirmation: false,
+            })
+            .await
+            .unwrap();
+
+        let sweeper =
+            CompletedProcessesSweeper::new(repository.clone(), Duration::from_millis(100));
+        tokio::spawn(async move { sweeper.run(Duration::from_millis(20)).await });
+
+        // Kept until the retention elapses
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(matches!(
+            repository.get_process(completed_id).await.unwrap(),
+            AdditionProcess::Completed(_)
+        ));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(repository.get_process(completed_id).await.is_err());
+        assert!(matches!(
+            repository.get_process(ongoing_id).await.unwrap(),
+            AdditionProcess::AwaitingPeerShares(_)
+        ));
+    }
+}
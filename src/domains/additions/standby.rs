@@ -0,0 +1,158 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+
+use super::{
+    AdditionProcess,
+    repository::{AdditionProcessRepository, RepositoryError},
+};
+
+/// Role of the node, a standby mirrors the processes of a primary until it is promoted.
+///
+/// The processes of a standby are read-only: its orchestrator is paused and processes can not be created nor deleted.
+#[derive(Default)]
+pub struct StandbyRole {
+    standby: AtomicBool,
+}
+
+impl StandbyRole {
+    pub fn new(standby: bool) -> Self {
+        Self {
+            standby: AtomicBool::new(standby),
+        }
+    }
+
+    pub fn is_standby(&self) -> bool {
+        self.standby.load(Ordering::Relaxed)
+    }
+
+    /// Promotes the node to active, returns whether it was a standby.
+    pub fn promote(&self) -> bool {
+        self.standby.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[derive(Deserialize)]
+struct PrimaryExport {
+    processes: Vec<AdditionProcess>,
+}
+
+/// Mirrors the processes of a primary while the node is a standby.
+///
+/// The processes are pulled from the export endpoint of the primary, processes missing on the primary are deleted.
+pub struct StandbyMirror {
+    repository: Arc<dyn AdditionProcessRepository>,
+    client: reqwest::Client,
+    /// URL of the export endpoint of the primary
+    export_url: String,
+    admin_token: String,
+    role: Arc<StandbyRole>,
+}
+
+impl StandbyMirror {
+    /// # Arguments
+    /// * `repository` - The repository holding the mirrored processes,
+    /// * `primary_url` - The base URL of the primary,
+    /// * `admin_token` - The admin token of the primary, needed by its export endpoint,
+    /// * `role` - The role of the node, the mirror stops once it is promoted.
+    pub fn new(
+        repository: Arc<dyn AdditionProcessRepository>,
+        primary_url: &str,
+        admin_token: String,
+        role: Arc<StandbyRole>,
+    ) -> Self {
+        Self {
+            repository,
+            client: reqwest::Client::new(),
+            export_url: format!("{}/admin/export", primary_url.trim_end_matches('/')),
+            admin_token,
+            role,
+        }
+    }
+
+    /// Runs the mirror loop until the node is promoted.
+    /// # Arguments
+    /// * `interval` - The duration between each synchronization with the primary.
+    pub async fn run(&self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if !self.role.is_standby() {
+                tracing::info!("Node promoted, processes are no longer mirrored from the primary");
+                return;
+            }
+            if let Err(e) = self.sync().await {
+                tracing::error!("Failed to mirror the processes of the primary: {:?}", e);
+            }
+        }
+    }
+
+    async fn sync(&self) -> Result<(), anyhow::Error> {
+        let response = self
+            .client
+            .get(&self.export_url)
+            .header("X-ADMIN-TOKEN", &self.admin_token)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "primary responded {} to the export of its processes",
+                response.status()
+            ));
+        }
+        let export = response.json::<PrimaryExport>().await?;
+        let updated = self.mirror(export.processes).await?;
+        if updated > 0 {
+            tracing::debug!("{updated} addition processes mirrored from the primary");
+        }
+        Ok(())
+    }
+
+    /// Aligns the repository with the processes of the primary.
+    /// # Returns
+    /// * The number of created, updated and deleted processes.
+    async fn mirror(&self, processes: Vec<AdditionProcess>) -> Result<usize, anyhow::Error> {
+        let primary_ids = processes.iter().map(|p| p.id()).collect::<HashSet<_>>();
+        let mut updated = 0;
+        for process in processes {
+            // A sync in flight must not overwrite the processes of a promoted node
+            if !self.role.is_standby() {
+                return Ok(updated);
+            }
+            let _lock = self.repository.lock_process(process.id()).await;
+            match self.repository.get_process(process.id()).await {
+                Ok(local) if serde_json::to_value(&local)? == serde_json::to_value(&process)? => {
+                    continue;
+                }
+                Ok(_) => self.repository.delete_process(process.id()).await?,
+                Err(RepositoryError::NotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+            self.repository.import_all(vec![process]).await?;
+            updated += 1;
+        }
+        for process_id in self.repository.list_process_ids().await? {
+            if !self.role.is_standby() {
+                return Ok(updated);
+            }
+            if primary_ids.contains(&process_id) {
+                continue;
+            }
+            let _lock = self.repository.lock_process(process_id).await;
+            match self.repository.delete_process(process_id).await {
+                Ok(()) => updated += 1,
+                Err(RepositoryError::NotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(updated)
+    }
+}
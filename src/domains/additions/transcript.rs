@@ -0,0 +1,379 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    AdditionProcess, CreateProcessRequest, ReceiveSharesRequest, ReceiveSharesSumsRequest,
+    repository::{
+        AdditionProcessRepository, ProcessCancellationGuard, ProcessLockGuard, ProcessesStatistics,
+        RepositoryError,
+    },
+};
+
+/// Step of the protocol executed by the server for a process.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TranscriptEvent {
+    /// Process created with the input of the server and the share it keeps
+    Created {
+        #[serde(with = "crate::value_encoding::value")]
+        input: u64,
+        #[serde(with = "crate::value_encoding::value")]
+        own_share: u64,
+    },
+    /// Share generated for a peer, delivered by pushes and polls
+    OutgoingShare {
+        peer_id: u8,
+        #[serde(with = "crate::value_encoding::value")]
+        value: u64,
+    },
+    ShareReceived {
+        peer_id: u8,
+        #[serde(with = "crate::value_encoding::value")]
+        value: u64,
+    },
+    /// Shares sum computed once every share is received, it is then exchanged with the peers
+    SharesSumComputed {
+        #[serde(with = "crate::value_encoding::value")]
+        shares_sum: u64,
+    },
+    SharesSumReceived {
+        peer_id: u8,
+        #[serde(with = "crate::value_encoding::value")]
+        value: u64,
+    },
+    /// Reconstruction of the final sum, from the own shares sum and the shares sums of the peers.
+    /// The final sum is a candidate until it is confirmed if a confirmation by the peers is required.
+    Reconstructed {
+        #[serde(with = "crate::value_encoding::value")]
+        own_shares_sum: u64,
+        /// Pairs of peer ID and shares sum, ordered by peer ID
+        received_shares_sums: Vec<(u8, u64)>,
+        #[serde(with = "crate::value_encoding::value")]
+        final_sum: u64,
+    },
+    /// Final sum confirmed by the peers, the process is completed
+    Confirmed {
+        #[serde(with = "crate::value_encoding::value")]
+        final_sum: u64,
+    },
+    Unrecoverable {
+        reason: String,
+    },
+    Tampered {
+        reason: String,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub event: TranscriptEvent,
+}
+
+/// Transcripts of the processes, the ordered protocol steps executed by the server.
+#[derive(Default)]
+pub struct ProcessTranscripts {
+    transcripts: Mutex<HashMap<Uuid, Vec<TranscriptEntry>>>,
+}
+
+impl ProcessTranscripts {
+    /// Transcript of a process, `None` if nothing has been recorded for it.
+    pub fn transcript(&self, process_id: Uuid) -> Option<Vec<TranscriptEntry>> {
+        self.lock_transcripts().get(&process_id).cloned()
+    }
+
+    fn record(&self, process_id: Uuid, events: impl IntoIterator<Item = TranscriptEvent>) {
+        let recorded_at = chrono::Utc::now();
+        self.lock_transcripts()
+            .entry(process_id)
+            .or_default()
+            .extend(
+                events
+                    .into_iter()
+                    .map(|event| TranscriptEntry { recorded_at, event }),
+            );
+    }
+
+    fn lock_transcripts(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Vec<TranscriptEntry>>> {
+        self.transcripts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Repository recording the transcript of the processes as they are updated, the updates are delegated to the inner repository.
+///
+/// Every protocol step goes through the repository, whether it comes from the orchestrator polls or from the pushes of the peers.
+pub struct TranscriptRecordingRepository {
+    inner: Arc<dyn AdditionProcessRepository>,
+    transcripts: Arc<ProcessTranscripts>,
+}
+
+impl TranscriptRecordingRepository {
+    pub fn new(
+        inner: Arc<dyn AdditionProcessRepository>,
+        transcripts: Arc<ProcessTranscripts>,
+    ) -> Self {
+        Self { inner, transcripts }
+    }
+}
+
+/// Wraps the repository so that the transcripts of the processes are recorded, if enabled.
+/// # Returns
+/// * The repository to use and the recorded transcripts, `None` if disabled.
+pub fn record_transcripts(
+    repository: Arc<dyn AdditionProcessRepository>,
+    enabled: bool,
+) -> (
+    Arc<dyn AdditionProcessRepository>,
+    Option<Arc<ProcessTranscripts>>,
+) {
+    if !enabled {
+        return (repository, None);
+    }
+    let transcripts = Arc::new(ProcessTranscripts::default());
+    (
+        Arc::new(TranscriptRecordingRepository::new(
+            repository,
+            transcripts.clone(),
+        )),
+        Some(transcripts),
+    )
+}
+
+/// Entries of `after` missing from `before`, ordered by peer ID.
+fn new_entries(
+    before: Option<&HashMap<u8, u64>>,
+    after: Option<&HashMap<u8, u64>>,
+) -> BTreeMap<u8, u64> {
+    after
+        .into_iter()
+        .flatten()
+        .filter(|(peer_id, _)| before.is_none_or(|before| !before.contains_key(peer_id)))
+        .map(|(peer_id, value)| (*peer_id, *value))
+        .collect()
+}
+
+/// Shares sums received from the peers, `None` before the shares sums round or if the process is unrecoverable.
+fn shares_sums_of(process: &AdditionProcess) -> Option<&HashMap<u8, u64>> {
+    match process {
+        AdditionProcess::AwaitingPeerSharesSum(p) => Some(&p.received_shares_sums),
+        AdditionProcess::Reconstructed(p) => Some(&p.received_shares_sums),
+        AdditionProcess::Completed(p) => Some(&p.received_shares_sums),
+        AdditionProcess::Tampered(p) => Some(&p.received_shares_sums),
+        AdditionProcess::AwaitingPeerShares(_) | AdditionProcess::Unrecoverable(_) => None,
+    }
+}
+
+#[async_trait::async_trait]
+impl AdditionProcessRepository for TranscriptRecordingRepository {
+    async fn lock_process(&self, process_id: Uuid) -> ProcessLockGuard {
+        self.inner.lock_process(process_id).await
+    }
+
+    fn register_cancellation(&self, process_id: Uuid) -> ProcessCancellationGuard {
+        self.inner.register_cancellation(process_id)
+    }
+
+    async fn get_process(&self, process_id: Uuid) -> Result<AdditionProcess, RepositoryError> {
+        self.inner.get_process(process_id).await
+    }
+
+    async fn get_ongoing_processes(&self) -> Result<Vec<AdditionProcess>, RepositoryError> {
+        self.inner.get_ongoing_processes().await
+    }
+
+    async fn get_process_by_external_key(
+        &self,
+        external_key: &str,
+    ) -> Result<Option<AdditionProcess>, RepositoryError> {
+        self.inner.get_process_by_external_key(external_key).await
+    }
+
+    async fn create_process(
+        &self,
+        request: CreateProcessRequest,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let process = self.inner.create_process(request).await?;
+        let input_shares = process.input_shares();
+        let outgoing_shares = input_shares
+            .shares_to_send
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(peer_id, value)| TranscriptEvent::OutgoingShare {
+                peer_id: *peer_id,
+                value: *value,
+            });
+        self.transcripts.record(
+            process.id(),
+            std::iter::once(TranscriptEvent::Created {
+                input: input_shares.input,
+                own_share: input_shares.own_share,
+            })
+            .chain(outgoing_shares),
+        );
+        Ok(process)
+    }
+
+    async fn receive_shares(
+        &self,
+        request: ReceiveSharesRequest,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let before = self.inner.get_process(request.process_id).await?;
+        let process = self.inner.receive_shares(request).await?;
+        let mut events = new_entries(before.received_shares(), process.received_shares())
+            .into_iter()
+            .map(|(peer_id, value)| TranscriptEvent::ShareReceived { peer_id, value })
+            .collect::<Vec<_>>();
+        if let (AdditionProcess::AwaitingPeerShares(_), AdditionProcess::AwaitingPeerSharesSum(p)) =
+            (&before, &process)
+        {
+            events.push(TranscriptEvent::SharesSumComputed {
+                shares_sum: p.shares_sum,
+            });
+        }
+        self.transcripts.record(process.id(), events);
+        Ok(process)
+    }
+
+    async fn receive_shares_sums(
+        &self,
+        request: ReceiveSharesSumsRequest,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let before = self.inner.get_process(request.process_id).await?;
+        let process = self.inner.receive_shares_sums(request).await?;
+        let mut events = new_entries(shares_sums_of(&before), shares_sums_of(&process))
+            .into_iter()
+            .map(|(peer_id, value)| TranscriptEvent::SharesSumReceived { peer_id, value })
+            .collect::<Vec<_>>();
+        let reconstruction = match (&before, &process) {
+            (AdditionProcess::AwaitingPeerSharesSum(_), AdditionProcess::Completed(p)) => {
+                Some((p.shares_sum, &p.received_shares_sums, p.final_sum))
+            }
+            (AdditionProcess::AwaitingPeerSharesSum(_), AdditionProcess::Reconstructed(p)) => {
+                Some((p.shares_sum, &p.received_shares_sums, p.candidate_sum))
+            }
+            _ => None,
+        };
+        if let Some((own_shares_sum, received_shares_sums, final_sum)) = reconstruction {
+            events.push(TranscriptEvent::Reconstructed {
+                own_shares_sum,
+                received_shares_sums: new_entries(None, Some(received_shares_sums))
+                    .into_iter()
+                    .collect(),
+                final_sum,
+            });
+        }
+        self.transcripts.record(process.id(), events);
+        Ok(process)
+    }
+
+    async fn confirm_final_sum(
+        &self,
+        process_id: Uuid,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let process = self.inner.confirm_final_sum(process_id).await?;
+        if let AdditionProcess::Completed(p) = &process {
+            self.transcripts.record(
+                process_id,
+                [TranscriptEvent::Confirmed {
+                    final_sum: p.final_sum,
+                }],
+            );
+        }
+        Ok(process)
+    }
+
+    async fn mark_unrecoverable(
+        &self,
+        process_id: Uuid,
+        reason: String,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let process = self
+            .inner
+            .mark_unrecoverable(process_id, reason.clone())
+            .await?;
+        self.transcripts
+            .record(process_id, [TranscriptEvent::Unrecoverable { reason }]);
+        Ok(process)
+    }
+
+    async fn record_diagnostic(
+        &self,
+        process_id: Uuid,
+        diagnostic: String,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        self.inner.record_diagnostic(process_id, diagnostic).await
+    }
+
+    async fn mark_tampered(
+        &self,
+        process_id: Uuid,
+        received_shares_sums: HashMap<u8, u64>,
+        reason: String,
+    ) -> Result<AdditionProcess, RepositoryError> {
+        let before = self.inner.get_process(process_id).await?;
+        let process = self
+            .inner
+            .mark_tampered(process_id, received_shares_sums, reason.clone())
+            .await?;
+        let events = new_entries(shares_sums_of(&before), shares_sums_of(&process))
+            .into_iter()
+            .map(|(peer_id, value)| TranscriptEvent::SharesSumReceived { peer_id, value })
+            .chain([TranscriptEvent::Tampered { reason }]);
+        self.transcripts.record(process_id, events);
+        Ok(process)
+    }
+
+    async fn delete_process(&self, process_id: Uuid) -> Result<(), RepositoryError> {
+        self.inner.delete_process(process_id).await?;
+        self.transcripts.lock_transcripts().remove(&process_id);
+        Ok(())
+    }
+
+    async fn list_process_ids(&self) -> Result<Vec<Uuid>, RepositoryError> {
+        self.inner.list_process_ids().await
+    }
+
+    async fn export_all(&self) -> Result<Vec<AdditionProcess>, RepositoryError> {
+        self.inner.export_all().await
+    }
+
+    async fn import_all(&self, processes: Vec<AdditionProcess>) -> Result<usize, RepositoryError> {
+        self.inner.import_all(processes).await
+    }
+
+    async fn evict_completed_processes(
+        &self,
+        completed_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, RepositoryError> {
+        let evicted = self
+            .inner
+            .evict_completed_processes(completed_before)
+            .await?;
+        if evicted > 0 {
+            let process_ids = self
+                .transcripts
+                .lock_transcripts()
+                .keys()
+                .copied()
+                .collect::<Vec<_>>();
+            for process_id in process_ids {
+                if let Err(RepositoryError::NotFound(_)) = self.inner.get_process(process_id).await
+                {
+                    self.transcripts.lock_transcripts().remove(&process_id);
+                }
+            }
+        }
+        Ok(evicted)
+    }
+
+    async fn statistics(&self) -> Result<ProcessesStatistics, RepositoryError> {
+        self.inner.statistics().await
+    }
+}
@@ -0,0 +1,189 @@
+use std::{future::IntoFuture, time::Duration};
+
+use axum::Router;
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BindRetryConfig {
+    /// Number of retries after the first failed attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each subsequent retry.
+    pub base_delay: Duration,
+}
+
+/// Binds a TCP listener to the address, retrying with an exponential backoff and jitter on failure.
+///
+/// A transient failure, e.g. the port still being held by a terminating previous instance, is then not fatal.
+/// # Arguments
+/// * `addr` - The address to bind the listener to,
+/// * `retry_config` - The number of retries and the base delay between them.
+pub async fn bind_listener_with_retries<A>(
+    addr: A,
+    retry_config: BindRetryConfig,
+) -> Result<TcpListener, std::io::Error>
+where
+    A: ToSocketAddrs + Clone,
+{
+    let mut retries = 0;
+    loop {
+        match TcpListener::bind(addr.clone()).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if retries < retry_config.max_retries => {
+                let delay = backoff_delay(retry_config.base_delay, retries);
+                tracing::warn!(
+                    "Failed to bind the TCP listener: {e}, retrying in {delay:?} ({}/{})",
+                    retries + 1,
+                    retry_config.max_retries
+                );
+                tokio::time::sleep(delay).await;
+                retries += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Serves the router until the shutdown signal completes, in-flight requests are then drained for at most the grace period.
+///
+/// Once the grace period elapses, it returns without waiting for the remaining connections, which are dropped when the process exits.
+/// # Arguments
+/// * `listener` - The listener to accept connections from,
+/// * `router` - The router serving the requests,
+/// * `shutdown_signal` - The future completing when the server must shut down,
+/// * `grace` - The maximum duration in-flight requests are drained for.
+pub async fn serve_with_shutdown_grace<F>(
+    listener: TcpListener,
+    router: Router,
+    shutdown_signal: F,
+    grace: Duration,
+) -> Result<(), std::io::Error>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let (signaled_sender, signaled_receiver) = tokio::sync::oneshot::channel::<()>();
+    let serve = axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            shutdown_signal.await;
+            let _ = signaled_sender.send(());
+        })
+        .into_future();
+    let grace_elapsed = async move {
+        match signaled_receiver.await {
+            Ok(()) => tokio::time::sleep(grace).await,
+            // The server stopped without a shutdown signal, its result is returned
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    tokio::select! {
+        result = serve => result,
+        _ = grace_elapsed => {
+            tracing::warn!("Shutdown grace period of {grace:?} elapsed, remaining connections are dropped");
+            Ok(())
+        }
+    }
+}
+
+/// Exponential backoff delay, with a random jitter of up to half of the delay.
+fn backoff_delay(base_delay: Duration, retry: u32) -> Duration {
+    let delay = base_delay.saturating_mul(2_u32.saturating_pow(retry.min(16)));
+    let jitter = delay.mul_f64(rand::random::<f64>() / 2.0);
+    delay + jitter
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        let base_delay = Duration::from_millis(100);
+        for retry in 0..5 {
+            let delay = backoff_delay(base_delay, retry);
+            let expected_delay = base_delay * 2_u32.pow(retry);
+            assert!(delay >= expected_delay);
+            assert!(delay <= expected_delay.mul_f64(1.5));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bind_retries_until_port_is_freed() {
+        let occupying_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = occupying_listener.local_addr().unwrap();
+
+        let bind_task = tokio::spawn(bind_listener_with_retries(
+            addr,
+            BindRetryConfig {
+                max_retries: 10,
+                base_delay: Duration::from_millis(50),
+            },
+        ));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!bind_task.is_finished());
+
+        drop(occupying_listener);
+        let listener = bind_task.await.unwrap().unwrap();
+        assert_eq!(listener.local_addr().unwrap(), addr);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drops_slow_request_after_grace_period() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let request_started = Arc::new(tokio::sync::Notify::new());
+        let router = Router::new().route(
+            "/slow",
+            axum::routing::get({
+                let request_started = request_started.clone();
+                || async move {
+                    request_started.notify_one();
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "done"
+                }
+            }),
+        );
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel::<()>();
+        let grace = Duration::from_millis(300);
+        let server_task = tokio::spawn(serve_with_shutdown_grace(
+            listener,
+            router,
+            async move {
+                let _ = shutdown_receiver.await;
+            },
+            grace,
+        ));
+
+        let slow_request = tokio::spawn(reqwest::get(format!("http://{addr}/slow")));
+        request_started.notified().await;
+        let shutdown_start = std::time::Instant::now();
+        shutdown_sender.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), server_task)
+            .await
+            .expect("server should exit within the grace period")
+            .unwrap()
+            .unwrap();
+        let shutdown_duration = shutdown_start.elapsed();
+        assert!(shutdown_duration >= grace);
+        assert!(shutdown_duration < grace + Duration::from_secs(1));
+        // The slow request is still in flight, the server did not wait for it
+        assert!(!slow_request.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_bind_fails_after_max_retries() {
+        let occupying_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = occupying_listener.local_addr().unwrap();
+
+        let result = bind_listener_with_retries(
+            addr,
+            BindRetryConfig {
+                max_retries: 2,
+                base_delay: Duration::from_millis(10),
+            },
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
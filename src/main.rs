@@ -9,19 +9,29 @@ use dotenvy::dotenv;
 use mpc_exploration::{
     Config,
     domains::additions::{
-        orchestrator::setup_addition_process_orchestrator,
+        completion::ProcessCompletions,
+        notifier::Notifier,
+        orchestrator::{BlockedPeers, OrchestratorSwitch, setup_addition_process_orchestrator},
+        rejoin::ProcessesRejoiner,
         repository::InMemoryAdditionProcessRepository,
+        retention::CompletedProcessesSweeper,
+        standby::{StandbyMirror, StandbyRole},
+        transcript,
     },
-    peer_communication::setup_peer_communication,
+    listener::{bind_listener_with_retries, serve_with_shutdown_grace},
+    metrics::Metrics,
+    peer_communication::{peer_client::CORRELATION_ID_HEADER, setup_peer_communication},
     routes::app_router,
+    simulation::SimulatedNetwork,
 };
 use tokio::signal;
+use tokio_util::sync::CancellationToken;
 use tower_http::{
     request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
     timeout::TimeoutLayer,
     trace::TraceLayer,
 };
-use tracing::{Span, error, info, info_span, level_filters::LevelFilter};
+use tracing::{Span, error, field::Empty, info, info_span, level_filters::LevelFilter};
 use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
 const REQUEST_ID_HEADER: &str = "x-request-id";
@@ -43,6 +53,12 @@ async fn main() -> Result<(), anyhow::Error> {
         }
     };
 
+    // `--print-config` prints the resolved configuration and exits without starting the server
+    if std::env::args().skip(1).any(|arg| arg == "--print-config") {
+        println!("{config}");
+        return Ok(());
+    }
+
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::fmt::layer()
@@ -50,21 +66,41 @@ async fn main() -> Result<(), anyhow::Error> {
         )
         .init();
 
+    if let Some(nodes_count) = config.simulate_peers {
+        return run_simulation(&config, nodes_count).await;
+    }
+
     let x_request_id = HeaderName::from_static(REQUEST_ID_HEADER);
 
-    let addition_process_repository = Arc::new(InMemoryAdditionProcessRepository::new());
+    let (addition_process_repository, transcripts) = transcript::record_transcripts(
+        Arc::new(InMemoryAdditionProcessRepository::new()),
+        config.record_transcripts,
+    );
+    let metrics = Arc::new(Metrics::new());
+    let completions = Arc::new(ProcessCompletions::new(config.completion_event_buffer));
+    let orchestrator_switch = Arc::new(OrchestratorSwitch::default());
+    // A standby only mirrors the processes of its primary, its orchestrator resumes on promotion
+    let standby = Arc::new(StandbyRole::new(config.standby_primary_url.is_some()));
+    if standby.is_standby() {
+        orchestrator_switch.pause();
+        metrics.orchestrator.record_paused(true);
+    }
+    let blocked_peers = Arc::new(BlockedPeers::new(&config.blocked_peer_ids));
 
-    let (
-        peer_client,
-        peer_messages_sender,
-        mut peer_messages_relayer,
-        peer_messages_relayer_pinger,
-    ) = setup_peer_communication(config.server_peer_id, &config.peers);
+    // Cancelled on shutdown, the interval pingers then stop instead of racing with the teardown of their channels
+    let shutdown = CancellationToken::new();
+
+    let (peer_client, peer_messages_sender, peer_messages_relayer, peer_messages_relayer_pinger) =
+        setup_peer_communication(&config)?;
+    let peer_messages_relayer_pinger = peer_messages_relayer_pinger.with_shutdown(shutdown.clone());
+    let mut peer_messages_relayer =
+        peer_messages_relayer.with_heartbeats(metrics.heartbeats.clone());
     tokio::spawn(async move {
         peer_messages_relayer.run().await;
     });
-    tokio::spawn(async move {
-        if let Err(e) = peer_messages_relayer_pinger.run().await {
+    let relayer_interval = config.relayer_interval;
+    let relayer_pinger_task = tokio::spawn(async move {
+        if let Err(e) = peer_messages_relayer_pinger.run(relayer_interval).await {
             error!(
                 "Peer messages relayer interval pinger encountered an error: {}",
                 e
@@ -72,31 +108,90 @@ async fn main() -> Result<(), anyhow::Error> {
         }
     });
 
-    let (mut addition_process_orchestrator, addition_process_notifier) =
+    let (addition_process_orchestrator, addition_process_notifier) =
         setup_addition_process_orchestrator(
             addition_process_repository.clone(),
-            peer_client,
+            peer_client.clone(),
             config.server_peer_id,
-            &config.peers,
+            metrics.clone(),
+            completions.clone(),
+            orchestrator_switch.clone(),
+            config.orchestrator_max_failures,
         );
+    let mut addition_process_orchestrator = addition_process_orchestrator
+        .with_silent_peers(&config.silent_peer_ids)
+        .with_blocked_peers(blocked_peers.clone())
+        .with_peer_points(config.peer_points.clone())
+        .with_excluded_shares_sum_peers(&config.excluded_shares_sum_peer_ids)
+        .with_confirm_quorum(config.confirm_quorum)
+        .with_recovery(config.recovery_settings());
     tokio::spawn(async move {
         addition_process_orchestrator.run().await;
     });
-    let addition_process_notifier = Arc::new(addition_process_notifier);
-    tokio::spawn({
+    let addition_process_notifier =
+        Arc::new(addition_process_notifier.with_shutdown(shutdown.clone()));
+    if let (Some(primary_url), Some(admin_token)) =
+        (&config.standby_primary_url, &config.admin_token)
+    {
+        let mirror = StandbyMirror::new(
+            addition_process_repository.clone(),
+            primary_url,
+            admin_token.clone(),
+            standby.clone(),
+        );
+        let standby_sync_interval = config.standby_sync_interval;
+        tokio::spawn(async move {
+            mirror.run(standby_sync_interval).await;
+        });
+    }
+    if let Some(retention) = config.completed_retention {
+        let sweeper =
+            CompletedProcessesSweeper::new(addition_process_repository.clone(), retention);
+        tokio::spawn(async move {
+            sweeper
+                .run(retention.clamp(Duration::from_secs(1), Duration::from_secs(60)))
+                .await;
+        });
+    }
+    let orchestrator_pinger_task = tokio::spawn({
         let addition_process_notifier = addition_process_notifier.clone();
+        let orchestrator_interval = config.orchestrator_interval;
         async move {
             addition_process_notifier
-                .run_interval_ping(tokio::time::Duration::from_secs(1))
+                .run_interval_ping(orchestrator_interval)
                 .await;
         }
     });
 
+    if config.rejoin_on_startup {
+        let rejoiner = ProcessesRejoiner::new(
+            addition_process_repository.clone(),
+            peer_client.clone(),
+            config.server_peer_id,
+            &config.peers.iter().map(|p| p.id).collect::<Vec<_>>(),
+            config.verification_threshold,
+            config.peer_points.clone(),
+        );
+        let addition_process_notifier = addition_process_notifier.clone();
+        tokio::spawn(async move {
+            if !rejoiner.run().await.is_empty() {
+                addition_process_notifier.ping();
+            }
+        });
+    }
+
     let app = app_router(
         &config,
         addition_process_repository,
         Arc::new(peer_messages_sender),
+        peer_client,
         addition_process_notifier,
+        metrics,
+        completions,
+        orchestrator_switch,
+        blocked_peers,
+        transcripts,
+        standby,
     )
     .layer((
         // Set `x-request-id` header for every request
@@ -110,13 +205,18 @@ async fn main() -> Result<(), anyhow::Error> {
                     .map(MatchedPath::as_str);
 
                 let request_id = request.headers().get(REQUEST_ID_HEADER);
+                // Set by peers, it stitches the logs of a process across nodes
+                let correlation_id = request.headers().get(CORRELATION_ID_HEADER);
+                // Recorded by the `Peer` extractor once the peer is authorized
 
                 match request_id {
                     Some(v) => info_span!(
                         "http_request",
                         method = ?request.method(),
                         matched_path,
-                        request_id = ?v
+                        request_id = ?v,
+                        correlation_id = ?correlation_id,
+                        peer_id = Empty,
                     ),
                     None => {
                         error!("Failed to extract `request_id` header");
@@ -124,6 +224,8 @@ async fn main() -> Result<(), anyhow::Error> {
                             "http_request",
                             method = ?request.method(),
                             matched_path,
+                            correlation_id = ?correlation_id,
+                            peer_id = Empty,
                         )
                     }
                 }
@@ -144,29 +246,65 @@ async fn main() -> Result<(), anyhow::Error> {
     ));
 
     let addr = format!("0.0.0.0:{}", config.port);
-    let listener = tokio::net::TcpListener::bind(&addr).await.map_err(|err| {
-        let err = format!("Error while binding the TCP listener to address {addr}: {err}");
-
-        error!(err);
-        anyhow::anyhow!(err)
-    })?;
-
-    info!("Successfully bind the TCP listener to address {addr}\n");
-
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+    let listener = bind_listener_with_retries(addr.as_str(), config.bind_retry)
         .await
         .map_err(|err| {
-            let err = format!("Error while serving the routes: {err}");
+            let err = format!("Error while binding the TCP listener to address {addr}: {err}");
+
             error!(err);
             anyhow::anyhow!(err)
         })?;
 
+    info!("Successfully bind the TCP listener to address {addr}\n");
+
+    let serve_result = serve_with_shutdown_grace(
+        listener,
+        app,
+        {
+            let shutdown = shutdown.clone();
+            async move {
+                shutdown_signal().await;
+                shutdown.cancel();
+            }
+        },
+        config.shutdown_grace,
+    )
+    .await;
+    // The server may also stop on an error, the pingers are stopped in both cases
+    shutdown.cancel();
+    let _ = tokio::join!(relayer_pinger_task, orchestrator_pinger_task);
+    serve_result.map_err(|err| {
+        let err = format!("Error while serving the routes: {err}");
+        error!(err);
+        anyhow::anyhow!(err)
+    })?;
+
     info!("App has been gracefully shutdown");
 
     Ok(())
 }
 
+/// Runs an addition between nodes simulated in process and logs its result.
+async fn run_simulation(config: &Config, nodes_count: u8) -> Result<(), anyhow::Error> {
+    info!("Simulating {nodes_count} peers communicating in memory");
+    let network = SimulatedNetwork::start(config, nodes_count);
+
+    let process_id = uuid::Uuid::new_v4();
+    network.create_process(process_id).await?;
+    let completed_processes = network
+        .wait_for_completion(process_id, Duration::from_secs(30))
+        .await?;
+    for (peer_id, process) in network.peer_ids().iter().zip(&completed_processes) {
+        info!(
+            "Node {peer_id}: input {}, sum {:?}",
+            process.input, process.sum
+        );
+    }
+    info!("Simulated addition {process_id} completed");
+
+    Ok(())
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -0,0 +1,453 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Metrics of the node, rendered in the Prometheus text exposition format.
+pub struct Metrics {
+    /// Duration between the creation and the completion of addition processes.
+    pub process_completion_duration: Histogram,
+    /// Activity of the addition process orchestrator loop.
+    pub orchestrator: OrchestratorMetrics,
+    /// Responses of the peers which could not be decoded.
+    pub peers: PeerMetrics,
+    /// Heartbeats of the background tasks.
+    pub heartbeats: Arc<Heartbeats>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            process_completion_duration: Histogram::new(vec![
+                0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+            ]),
+            orchestrator: OrchestratorMetrics::default(),
+            peers: PeerMetrics::default(),
+            heartbeats: Arc::new(Heartbeats::default()),
+        }
+    }
+
+    /// Renders all the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        self.process_completion_duration.render(
+            "addition_process_completion_duration_seconds",
+            "Duration between the creation and the completion of addition processes",
+            &mut output,
+        );
+        self.orchestrator.render(&mut output);
+        self.peers.render(&mut output);
+        output
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Histogram of durations, observations are expressed in seconds.
+pub struct Histogram {
+    /// Upper bounds of the buckets, in seconds, in ascending order
+    bounds: Vec<f64>,
+    /// Number of observations per bucket, the last one is the `+Inf` bucket
+    bucket_counts: Vec<AtomicU64>,
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: Vec<f64>) -> Self {
+        let bucket_counts = (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect();
+        Self {
+            bounds,
+            bucket_counts,
+            sum_micros: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    /// Records an observation.
+    /// # Arguments
+    /// * `value` - The observed duration.
+    pub fn observe(&self, value: Duration) {
+        let seconds = value.as_secs_f64();
+        let bucket_index = self
+            .bounds
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.bucket_counts[bucket_index].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(value.as_micros() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of recorded observations.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, name: &str, help: &str, output: &mut String) {
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} histogram");
+        let mut cumulative_count = 0;
+        for (bound, bucket_count) in self.bounds.iter().zip(&self.bucket_counts) {
+            cumulative_count += bucket_count.load(Ordering::Relaxed);
+            let _ = writeln!(output, "{name}_bucket{{le=\"{bound}\"}} {cumulative_count}");
+        }
+        cumulative_count += self.bucket_counts[self.bounds.len()].load(Ordering::Relaxed);
+        let _ = writeln!(output, "{name}_bucket{{le=\"+Inf\"}} {cumulative_count}");
+        let sum_seconds = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(output, "{name}_sum {sum_seconds}");
+        let _ = writeln!(output, "{name}_count {}", self.count());
+    }
+}
+
+/// Activity of the orchestrator loop, a stale last run indicates a dead or blocked loop.
+#[derive(Default)]
+pub struct OrchestratorMetrics {
+    /// Unix timestamp in milliseconds of the last completed iteration, `0` if none completed
+    last_run_timestamp_millis: AtomicU64,
+    last_polled_processes: AtomicU64,
+    poll_successes: AtomicU64,
+    poll_failures: AtomicU64,
+    paused: AtomicBool,
+    /// Consecutive failed polls of the processes as of the last completed iteration
+    failures_attempts: Mutex<BTreeMap<Uuid, u8>>,
+}
+
+/// Consecutive failed polls of a process, it is skipped once they reach the maximum failures.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProcessFailureAttempts {
+    pub process_id: Uuid,
+    pub attempts: u8,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrchestratorMetricsSnapshot {
+    /// Time of the last completed iteration of the orchestrator loop.
+    pub last_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Number of processes polled during the last completed iteration.
+    pub last_polled_processes: u64,
+    /// Cumulative number of successful process polls.
+    pub poll_successes: u64,
+    /// Cumulative number of failed process polls.
+    pub poll_failures: u64,
+    /// Whether the orchestrator is paused by an operator.
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl OrchestratorMetrics {
+    /// Records a completed iteration of the orchestrator loop.
+    /// # Arguments
+    /// * `polled_processes` - The number of processes polled during the iteration,
+    /// * `failures` - The number of polls which failed.
+    pub fn record_iteration(&self, polled_processes: usize, failures: usize) {
+        let successes = polled_processes.saturating_sub(failures);
+        self.last_polled_processes
+            .store(polled_processes as u64, Ordering::Relaxed);
+        self.poll_successes
+            .fetch_add(successes as u64, Ordering::Relaxed);
+        self.poll_failures
+            .fetch_add(failures as u64, Ordering::Relaxed);
+        self.last_run_timestamp_millis.store(
+            chrono::Utc::now().timestamp_millis().max(0) as u64,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Records the consecutive failed polls of the processes, replacing the previous record.
+    pub fn record_failures_attempts(&self, failures_attempts: &HashMap<Uuid, u8>) {
+        *self
+            .failures_attempts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = failures_attempts
+            .iter()
+            .map(|(process_id, attempts)| (*process_id, *attempts))
+            .collect();
+    }
+
+    /// Consecutive failed polls of the processes, ordered by process ID.
+    pub fn failures_attempts(&self) -> Vec<ProcessFailureAttempts> {
+        self.failures_attempts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(process_id, attempts)| ProcessFailureAttempts {
+                process_id: *process_id,
+                attempts: *attempts,
+            })
+            .collect()
+    }
+
+    /// Records whether the orchestrator is paused.
+    pub fn record_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> OrchestratorMetricsSnapshot {
+        let last_run_timestamp_millis = self.last_run_timestamp_millis.load(Ordering::Relaxed);
+        OrchestratorMetricsSnapshot {
+            last_run_at: (last_run_timestamp_millis > 0)
+                .then(|| chrono::DateTime::from_timestamp_millis(last_run_timestamp_millis as i64))
+                .flatten(),
+            last_polled_processes: self.last_polled_processes.load(Ordering::Relaxed),
+            poll_successes: self.poll_successes.load(Ordering::Relaxed),
+            poll_failures: self.poll_failures.load(Ordering::Relaxed),
+            paused: self.paused.load(Ordering::Relaxed),
+        }
+    }
+
+    fn render(&self, output: &mut String) {
+        let snapshot = self.snapshot();
+        let last_run_timestamp_seconds =
+            self.last_run_timestamp_millis.load(Ordering::Relaxed) as f64 / 1_000.0;
+        for (name, help, kind, value) in [
+            (
+                "orchestrator_last_run_timestamp_seconds",
+                "Unix timestamp of the last completed orchestrator iteration",
+                "gauge",
+                last_run_timestamp_seconds,
+            ),
+            (
+                "orchestrator_last_polled_processes",
+                "Number of processes polled during the last completed orchestrator iteration",
+                "gauge",
+                snapshot.last_polled_processes as f64,
+            ),
+            (
+                "orchestrator_poll_successes_total",
+                "Number of successful process polls",
+                "counter",
+                snapshot.poll_successes as f64,
+            ),
+            (
+                "orchestrator_poll_failures_total",
+                "Number of failed process polls",
+                "counter",
+                snapshot.poll_failures as f64,
+            ),
+            (
+                "orchestrator_paused",
+                "Whether the orchestrator is paused, 1 if paused and 0 otherwise",
+                "gauge",
+                if snapshot.paused { 1.0 } else { 0.0 },
+            ),
+        ] {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} {kind}");
+            let _ = writeln!(output, "{name} {value}");
+        }
+    }
+}
+
+/// Heartbeats of the background tasks, e.g. the orchestrator and the outbox relayer.
+///
+/// A task beats on each iteration of its loop. A task whose heartbeat is stale has most likely died, e.g. after a panic, while the server keeps serving requests.
+/// A task beating while failing persistently, e.g. unable to read its repository, reports itself as failing.
+#[derive(Default)]
+pub struct Heartbeats {
+    last_beats: Mutex<BTreeMap<&'static str, Instant>>,
+    failing_tasks: Mutex<BTreeSet<&'static str>>,
+}
+
+impl Heartbeats {
+    /// Records a heartbeat of a task, the task is monitored from its first heartbeat.
+    pub fn beat(&self, task: &'static str) {
+        self.last_beats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(task, Instant::now());
+    }
+
+    /// Tasks whose last heartbeat is older than the timeout, sorted by name.
+    pub fn stale_tasks(&self, timeout: Duration) -> Vec<&'static str> {
+        self.last_beats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, last_beat)| last_beat.elapsed() > timeout)
+            .map(|(task, _)| *task)
+            .collect()
+    }
+
+    /// Reports a task as failing until it recovers.
+    pub fn report_failing(&self, task: &'static str) {
+        self.failing_tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(task);
+    }
+
+    /// Reports a task as no longer failing.
+    pub fn report_recovered(&self, task: &'static str) {
+        self.failing_tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(task);
+    }
+
+    /// Tasks reported as failing, sorted by name.
+    pub fn failing_tasks(&self) -> Vec<&'static str> {
+        self.failing_tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .copied()
+            .collect()
+    }
+}
+
+/// Responses of the peers which could not be decoded.
+///
+/// An undecodable response does not fix itself on retry, it most likely indicates peers running incompatible versions.
+#[derive(Default)]
+pub struct PeerMetrics {
+    decode_failures: Mutex<BTreeMap<u8, PeerDecodeFailures>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeerDecodeFailures {
+    /// Cumulative number of undecodable responses of the peer.
+    pub count: u64,
+    /// Time of the last undecodable response of the peer.
+    pub last_at: chrono::DateTime<chrono::Utc>,
+    /// Error of the last undecodable response of the peer.
+    pub last_error: String,
+}
+
+impl PeerMetrics {
+    /// Records an undecodable response of a peer.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer,
+    /// * `error` - The decoding error.
+    pub fn record_decode_failure(&self, peer_id: u8, error: String) {
+        let mut decode_failures = self
+            .decode_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let failures = decode_failures
+            .entry(peer_id)
+            .or_insert_with(|| PeerDecodeFailures {
+                count: 0,
+                last_at: chrono::Utc::now(),
+                last_error: String::new(),
+            });
+        failures.count += 1;
+        failures.last_at = chrono::Utc::now();
+        failures.last_error = error;
+    }
+
+    /// Undecodable responses by peer ID, peers without any are omitted.
+    pub fn decode_failures(&self) -> BTreeMap<u8, PeerDecodeFailures> {
+        self.decode_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn render(&self, output: &mut String) {
+        let name = "peer_decode_failures_total";
+        let _ = writeln!(
+            output,
+            "# HELP {name} Number of responses of the peer which could not be decoded"
+        );
+        let _ = writeln!(output, "# TYPE {name} counter");
+        for (peer_id, failures) in self.decode_failures() {
+            let _ = writeln!(output, "{name}{{peer_id=\"{peer_id}\"}} {}", failures.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeats_become_stale_without_beats() {
+        let heartbeats = Heartbeats::default();
+        heartbeats.beat("orchestrator");
+        heartbeats.beat("outbox_relayer");
+        assert!(heartbeats.stale_tasks(Duration::from_millis(50)).is_empty());
+
+        std::thread::sleep(Duration::from_millis(100));
+        heartbeats.beat("outbox_relayer");
+        assert_eq!(
+            heartbeats.stale_tasks(Duration::from_millis(50)),
+            vec!["orchestrator"]
+        );
+    }
+
+    #[test]
+    fn test_histogram_render() {
+        let histogram = Histogram::new(vec![1.0, 5.0]);
+        histogram.observe(Duration::from_millis(500));
+        histogram.observe(Duration::from_secs(2));
+        histogram.observe(Duration::from_secs(10));
+        let mut output = String::new();
+        histogram.render("test_duration_seconds", "Test durations", &mut output);
+        assert_eq!(
+            output,
+            "# HELP test_duration_seconds Test durations\n\
+             # TYPE test_duration_seconds histogram\n\
+             test_duration_seconds_bucket{le=\"1\"} 1\n\
+             test_duration_seconds_bucket{le=\"5\"} 2\n\
+             test_duration_seconds_bucket{le=\"+Inf\"} 3\n\
+             test_duration_seconds_sum 12.5\n\
+             test_duration_seconds_count 3\n"
+        );
+    }
+
+    #[test]
+    fn test_orchestrator_metrics() {
+        let metrics = OrchestratorMetrics::default();
+        assert!(metrics.snapshot().last_run_at.is_none());
+
+        metrics.record_iteration(3, 1);
+        metrics.record_iteration(2, 0);
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.last_polled_processes, 2);
+        assert_eq!(snapshot.poll_successes, 4);
+        assert_eq!(snapshot.poll_failures, 1);
+        let elapsed = chrono::Utc::now() - snapshot.last_run_at.unwrap();
+        assert!(elapsed < chrono::TimeDelta::seconds(5));
+
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(output.contains("orchestrator_poll_successes_total 4\n"));
+        assert!(output.contains("orchestrator_poll_failures_total 1\n"));
+        assert!(output.contains("orchestrator_paused 0\n"));
+
+        metrics.record_paused(true);
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(output.contains("orchestrator_paused 1\n"));
+    }
+
+    #[test]
+    fn test_peer_metrics() {
+        let metrics = PeerMetrics::default();
+        metrics.record_decode_failure(2, "first".to_string());
+        metrics.record_decode_failure(2, "second".to_string());
+
+        let decode_failures = metrics.decode_failures();
+        assert_eq!(decode_failures.len(), 1);
+        assert_eq!(decode_failures[&2].count, 2);
+        assert_eq!(decode_failures[&2].last_error, "second");
+
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(output.contains("peer_decode_failures_total{peer_id=\"2\"} 2\n"));
+    }
+}
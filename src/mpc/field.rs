@@ -0,0 +1,320 @@
+//! Statically typed arithmetic over the prime field `Z/PZ`.
+//!
+//! The prime is part of the type, values and shares of different fields can not be mixed at call sites.
+//! The prime is checked at compile time, instantiating a field with a non prime modulus fails to build.
+//!
+//! ```
+//! use mpc_exploration::mpc::field::{FieldElement, recover, split};
+//!
+//! type F = FieldElement<1_000_000_007>;
+//!
+//! let secret = F::new(42);
+//! let shares = split(secret, &[1, 2, 3]);
+//! assert_eq!(recover(&shares).unwrap(), secret);
+//!
+//! // Shares can be added locally, the sum of the secrets is then reconstructed
+//! let other_shares = split(F::new(58), &[1, 2, 3]);
+//! let summed_shares = shares
+//!     .iter()
+//!     .zip(&other_shares)
+//!     .map(|(a, b)| a.add(b))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(recover(&summed_shares).unwrap(), F::new(100));
+//! ```
+//!
+//! ```compile_fail
+//! use mpc_exploration::mpc::field::FieldElement;
+//!
+//! // 1_000_000_008 is not prime
+//! let value = FieldElement::<1_000_000_008>::new(1);
+//! ```
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use anyhow::anyhow;
+
+use super::{
+    montgomery::Montgomery,
+    polynomial::{self, Polynomial},
+};
+
+/// Element of the prime field `Z/PZ`, the value is always reduced in `[0, P)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldElement<const P: u64>(u64);
+
+impl<const P: u64> FieldElement<P> {
+    /// Evaluated once per field, fails the build if `P` is not prime.
+    const PRIME_CHECK: () = assert!(is_prime(P), "field modulus must be prime");
+
+    pub const PRIME: u64 = P;
+
+    /// Parameters of the Montgomery form of the field, evaluated once per field. `None` for the only even prime, 2.
+    pub const MONTGOMERY: Option<Montgomery> = Montgomery::new(P);
+
+    pub fn new(value: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::PRIME_CHECK;
+        Self(value % P)
+    }
+
+    pub fn zero() -> Self {
+        Self::new(0)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Multiplicative inverse, fails for zero.
+    pub fn inverse(&self) -> Result<Self, anyhow::Error> {
+        polynomial::modulo_inv(self.0, P).map(Self)
+    }
+}
+
+impl<const P: u64> Add for FieldElement<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + rhs.0 as u128) % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Sub for FieldElement<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl<const P: u64> Neg for FieldElement<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self.0 == 0 { self } else { Self(P - self.0) }
+    }
+}
+
+impl<const P: u64> Mul for FieldElement<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 * rhs.0 as u128) % P as u128) as u64)
+    }
+}
+
+/// Polynomial with coefficients in the prime field `Z/PZ`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldPolynomial<const P: u64> {
+    inner: Polynomial,
+}
+
+impl<const P: u64> FieldPolynomial<P> {
+    /// Coefficients in ascending order, i.e. [1, 2, 3] -> 1 + 2x + 3x^2
+    pub fn new(coefficients: &[FieldElement<P>]) -> Self {
+        Self {
+            inner: Polynomial::new(coefficients.iter().map(|c| c.value()).collect()),
+        }
+    }
+
+    pub fn evaluate(&self, point: FieldElement<P>) -> FieldElement<P> {
+        match &FieldElement::<P>::MONTGOMERY {
+            Some(field) => FieldElement::new(self.inner.evaluate_montgomery(point.value(), field)),
+            None => FieldElement::new(self.inner.evaluate(point.value(), P)),
+        }
+    }
+
+    /// Lagrange interpolation of the polynomial passing through the given coordinates.
+    pub fn interpolate(
+        points: &[FieldElement<P>],
+        values: &[FieldElement<P>],
+    ) -> Result<Self, anyhow::Error> {
+        let points = points.iter().map(|p| p.value()).collect::<Vec<u64>>();
+        let values = values.iter().map(|v| v.value()).collect::<Vec<u64>>();
+        let inner = match &FieldElement::<P>::MONTGOMERY {
+            Some(field) => Polynomial::interpolate_montgomery(&points, &values, field)?,
+            None => Polynomial::interpolate(&points, &values, P)?,
+        };
+        Ok(Self { inner })
+    }
+}
+
+/// Share of a secret of the prime field `Z/PZ`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldShare<const P: u64> {
+    pub point: u8,
+    pub value: FieldElement<P>,
+}
+
+impl<const P: u64> FieldShare<P> {
+    /// Adds two shares evaluated at the same point, the result is a share of the sum of the secrets.
+    pub fn add(&self, other: &Self) -> Result<Self, anyhow::Error> {
+        if self.point != other.point {
+            return Err(anyhow!(
+                "unable to add shares of different points: {} and {}",
+                self.point,
+                other.point
+            ));
+        }
+        Ok(Self {
+            point: self.point,
+            value: self.value + other.value,
+        })
+    }
+}
+
+/// Splits a secret into one share per point, every share is needed to recover the secret.
+pub fn split<const P: u64>(secret: FieldElement<P>, points: &[u8]) -> Vec<FieldShare<P>> {
+    let shares = super::split_secret_with_degree(
+        secret.value(),
+        points,
+        points.len().saturating_sub(1),
+        P,
+        &mut rand::rng(),
+    );
+    points
+        .iter()
+        .map(|point| FieldShare {
+            point: *point,
+            value: FieldElement::new(shares[point]),
+        })
+        .collect()
+}
+
+/// Recovers a secret from its shares.
+pub fn recover<const P: u64>(shares: &[FieldShare<P>]) -> Result<FieldElement<P>, anyhow::Error> {
+    let shares = shares
+        .iter()
+        .map(|share| super::Share {
+            point: share.point,
+            value: share.value.value(),
+        })
+        .collect::<Vec<_>>();
+    super::recover_secret(&shares, P).map(FieldElement::new)
+}
+
+/// Trial division primality test, usable in constant evaluation.
+const fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n < 4 {
+        return true;
+    }
+    if n.is_multiple_of(2) {
+        return false;
+    }
+    let mut divisor = 3;
+    while divisor <= n / divisor {
+        if n.is_multiple_of(divisor) {
+            return false;
+        }
+        divisor += 2;
+    }
+    true
+}
+
+/// Whether `p` is a safe prime, i.e. a prime such that `(p - 1) / 2` is also prime.
+///
+/// The multiplicative group of a safe prime field has no small subgroup besides `{1, -1}`, as needed by commitment schemes.
+pub const fn is_safe_prime(p: u64) -> bool {
+    is_prime(p) && p > 2 && is_prime((p - 1) / 2)
+}
+
+/// Smallest generator of the multiplicative group of the safe prime field `Z/pZ`, `None` if `p` is not a safe prime.
+///
+/// The group has order `2q` with `q = (p - 1) / 2` prime, `g` is a generator if neither `g^2` nor `g^q` is one.
+pub fn find_generator(p: u64) -> Option<u64> {
+    if !is_safe_prime(p) {
+        return None;
+    }
+    let q = (p - 1) / 2;
+    (2..p).find(|g| modulo_pow(*g, 2, p) != 1 && modulo_pow(*g, q, p) != 1)
+}
+
+/// Square and multiply exponentiation modulo `n`.
+fn modulo_pow(base: u64, mut exponent: u64, n: u64) -> u64 {
+    let n = n as u128;
+    let mut base = base as u128 % n;
+    let mut result = 1 % n;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % n;
+        }
+        base = base * base % n;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type F = FieldElement<1_000_000_007>;
+
+    #[test]
+    fn test_field_arithmetic() {
+        let a = F::new(1_000_000_006);
+        let b = F::new(2);
+        assert_eq!(a + b, F::new(1));
+        assert_eq!(b - a, F::new(3));
+        assert_eq!(-F::zero(), F::zero());
+        assert_eq!(a * a, F::new(1));
+        assert_eq!(b * b.inverse().unwrap(), F::new(1));
+        assert!(F::zero().inverse().is_err());
+    }
+
+    #[test]
+    fn test_field_polynomial_interpolation() {
+        let polynomial = FieldPolynomial::new(&[F::new(3), F::new(2), F::new(1)]);
+        let points = [F::new(1), F::new(2), F::new(3)];
+        let values = points.map(|p| polynomial.evaluate(p));
+        assert_eq!(
+            FieldPolynomial::interpolate(&points, &values).unwrap(),
+            polynomial
+        );
+
+        // Without Montgomery form for the even prime, the naive arithmetic is used
+        type G = FieldElement<2>;
+        assert!(G::MONTGOMERY.is_none());
+        let polynomial = FieldPolynomial::new(&[G::new(1), G::new(1)]);
+        let points = [G::new(0), G::new(1)];
+        let values = points.map(|p| polynomial.evaluate(p));
+        assert_eq!(values, [G::new(1), G::new(0)]);
+        assert_eq!(
+            FieldPolynomial::interpolate(&points, &values).unwrap(),
+            polynomial
+        );
+    }
+
+    #[test]
+    fn test_is_prime() {
+        assert!(is_prime(2));
+        assert!(is_prime(1_000_000_007));
+        assert!(!is_prime(1));
+        assert!(!is_prime(1_000_000_008));
+        assert!(!is_prime(7 * 11));
+    }
+
+    #[test]
+    fn test_safe_primes_and_generators() {
+        for (p, g) in [
+            (5, 2),
+            (7, 3),
+            (11, 2),
+            (23, 5),
+            (47, 5),
+            (1_000_000_007, 5),
+        ] {
+            assert!(is_safe_prime(p), "{p} is a safe prime");
+            assert_eq!(find_generator(p), Some(g), "generator of {p}");
+        }
+        // 13 is prime but 6 is not
+        assert!(!is_safe_prime(13));
+        assert_eq!(find_generator(13), None);
+        assert!(!is_safe_prime(2));
+        assert!(!is_safe_prime(1_000_000_008));
+    }
+}
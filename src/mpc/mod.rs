@@ -1,16 +1,65 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
+use anyhow::anyhow;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub mod field;
+pub mod montgomery;
 mod polynomial;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Share {
     pub point: u8,
     pub value: u64,
 }
-pub fn split_secret(secret: u64, points: &[u8], n: u64) -> HashMap<u8, u64> {
+/// Splits a secret into one share per point, any `degree + 1` shares are enough to recover the secret.
+/// # Arguments
+/// * `secret` - The secret to split,
+/// * `points` - The points at which the shares are evaluated,
+/// * `degree` - The degree of the polynomial, i.e. its number of random coefficients, defaults to `points.len() - 1` so that every share is needed,
+/// * `n` - The prime modulus.
+pub fn split_secret(
+    secret: u64,
+    points: &[u8],
+    degree: Option<usize>,
+    n: u64,
+) -> Result<HashMap<u8, u64>, anyhow::Error> {
+    split_secret_with_rng(secret, points, degree, n, &mut rand::rng())
+}
+
+/// Splits a secret as [`split_secret`] does, drawing the random coefficients from the given generator, e.g. a seeded one for reproducible shares.
+pub fn split_secret_with_rng(
+    secret: u64,
+    points: &[u8],
+    degree: Option<usize>,
+    n: u64,
+    rng: &mut impl Rng,
+) -> Result<HashMap<u8, u64>, anyhow::Error> {
+    let degree = match degree {
+        Some(degree) if degree >= points.len() => {
+            return Err(anyhow!(
+                "degree {degree} must be lower than the number of points {}",
+                points.len()
+            ));
+        }
+        Some(degree) => degree,
+        None => points.len().saturating_sub(1),
+    };
+    Ok(split_secret_with_degree(secret, points, degree, n, rng))
+}
+
+fn split_secret_with_degree(
+    secret: u64,
+    points: &[u8],
+    degree: usize,
+    n: u64,
+    rng: &mut impl Rng,
+) -> HashMap<u8, u64> {
     let mut coefficients = vec![secret];
-    for _ in 1..points.len() {
-        let coeff = rand::random::<u64>() % n;
+    for _ in 0..degree {
+        let coeff = rng.random::<u64>() % n;
         coefficients.push(coeff);
     }
     let poly = polynomial::Polynomial::new(coefficients);
@@ -21,7 +70,49 @@ pub fn split_secret(secret: u64, points: &[u8], n: u64) -> HashMap<u8, u64> {
     shares
 }
 
+/// Strategy used to recover a secret from its shares.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecoveryStrategy {
+    /// Interpolates the whole polynomial and evaluates it at zero
+    #[default]
+    FullPolynomial,
+    /// Computes the value at zero of the interpolating polynomial directly, without its coefficients
+    Direct,
+    /// Interpolates the whole polynomial as [`RecoveryStrategy::FullPolynomial`] does, multiplying in Montgomery form, the modulus must be odd
+    Montgomery,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown recovery strategy `{0}`, expected `full_polynomial`, `direct` or `montgomery`")]
+pub struct ParseRecoveryStrategyError(String);
+
+impl FromStr for RecoveryStrategy {
+    type Err = ParseRecoveryStrategyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full_polynomial" => Ok(Self::FullPolynomial),
+            "direct" => Ok(Self::Direct),
+            "montgomery" => Ok(Self::Montgomery),
+            _ => Err(ParseRecoveryStrategyError(s.to_string())),
+        }
+    }
+}
+
+/// Recovers a secret from its shares with the default strategy.
+/// # Arguments
+/// * `shares` - The shares, at distinct points,
+/// * `n` - The prime modulus.
 pub fn recover_secret(shares: &[Share], n: u64) -> Result<u64, anyhow::Error> {
+    recover_secret_with_strategy(shares, n, RecoveryStrategy::default())
+}
+
+/// Recovers a secret from its shares with the given strategy, every strategy recovers the same secret.
+pub fn recover_secret_with_strategy(
+    shares: &[Share],
+    n: u64,
+    strategy: RecoveryStrategy,
+) -> Result<u64, anyhow::Error> {
     let mut points = Vec::with_capacity(shares.len());
     let mut values = Vec::with_capacity(shares.len());
     for share in shares {
@@ -29,9 +120,159 @@ pub fn recover_secret(shares: &[Share], n: u64) -> Result<u64, anyhow::Error> {
         values.push(share.value);
     }
 
-    let poly = polynomial::Polynomial::interpolate(&points, &values, n)?;
+    match strategy {
+        RecoveryStrategy::FullPolynomial => {
+            let poly = polynomial::Polynomial::interpolate(&points, &values, n)?;
+            Ok(poly.evaluate_at_zero())
+        }
+        RecoveryStrategy::Direct => polynomial::interpolate_at_zero(&points, &values, n),
+        RecoveryStrategy::Montgomery => {
+            let field = montgomery::Montgomery::new(n).ok_or_else(|| {
+                anyhow!("Montgomery form requires an odd modulus below 2^63, got {n}")
+            })?;
+            let poly = polynomial::Polynomial::interpolate_montgomery(&points, &values, &field)?;
+            Ok(poly.evaluate_at_zero())
+        }
+    }
+}
 
-    Ok(poly.evaluate_at_zero())
+/// Checks a recovered secret against a recomputation of the Lagrange interpolation at zero, logging its terms at the debug level.
+///
+/// This is a diagnostic aid for a wrong result, it is independent of the recovery strategy.
+/// # Arguments
+/// * `shares` - The shares the secret has been recovered from,
+/// * `expected_shares_count` - The number of shares the recovery is expected to use,
+/// * `secret` - The recovered secret,
+/// * `n` - The prime modulus.
+pub fn check_recovered_secret(
+    shares: &[Share],
+    expected_shares_count: usize,
+    secret: u64,
+    n: u64,
+) -> Result<(), anyhow::Error> {
+    if shares.len() != expected_shares_count {
+        return Err(anyhow!(
+            "self check: secret recovered from {} shares, {expected_shares_count} expected",
+            shares.len()
+        ));
+    }
+    if secret >= n {
+        return Err(anyhow!(
+            "self check: recovered secret {secret} is not below the modulus {n}"
+        ));
+    }
+    let points = shares
+        .iter()
+        .map(|share| share.point as u64)
+        .collect::<Vec<_>>();
+    let values = shares.iter().map(|share| share.value).collect::<Vec<_>>();
+    let terms = polynomial::lagrange_terms_at_zero(&points, &values, n)?;
+    let mut recomputed = 0_u128;
+    for (share, term) in shares.iter().zip(&terms) {
+        tracing::debug!(
+            "self check: share at point {} with value {} contributes the term {term}",
+            share.point,
+            share.value
+        );
+        recomputed = (recomputed + *term as u128) % n as u128;
+    }
+    if recomputed as u64 != secret {
+        return Err(anyhow!(
+            "self check: recovered secret {secret} differs from the recomputed secret {recomputed}"
+        ));
+    }
+    tracing::debug!(
+        "self check: secret {secret} recovered from {} shares",
+        shares.len()
+    );
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum VerifiedRecoveryError {
+    #[error(
+        "two disjoint subsets of {threshold} shares are needed for a verified recovery, {available} shares available"
+    )]
+    InsufficientShares { available: usize, threshold: usize },
+    #[error("recoveries from two disjoint subsets of shares disagree: {first} and {second}")]
+    Disagreement { first: u64, second: u64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// Recovers a secret shared with a polynomial of degree `threshold - 1` and verifies it against a second recovery.
+///
+/// The secret is recovered from the `threshold` shares of lowest points and from the `threshold` shares of highest points, the two subsets being disjoint.
+/// A share which has been tampered with makes the recoveries disagree.
+/// # Arguments
+/// * `shares` - The shares, at least `2 * threshold` of them,
+/// * `threshold` - The number of shares needed to recover the secret,
+/// * `n` - The prime modulus.
+pub fn recover_secret_verified(
+    shares: &[Share],
+    threshold: usize,
+    n: u64,
+) -> Result<u64, VerifiedRecoveryError> {
+    recover_secret_verified_with_strategy(shares, threshold, n, RecoveryStrategy::default())
+}
+
+/// Recovers and verifies a secret as [`recover_secret_verified`] does, with the given strategy.
+pub fn recover_secret_verified_with_strategy(
+    shares: &[Share],
+    threshold: usize,
+    n: u64,
+    strategy: RecoveryStrategy,
+) -> Result<u64, VerifiedRecoveryError> {
+    if threshold == 0 || shares.len() < 2 * threshold {
+        return Err(VerifiedRecoveryError::InsufficientShares {
+            available: shares.len(),
+            threshold,
+        });
+    }
+    let mut shares = shares.to_vec();
+    shares.sort_by_key(|share| share.point);
+    let first = recover_secret_with_strategy(&shares[..threshold], n, strategy)?;
+    let second = recover_secret_with_strategy(&shares[shares.len() - threshold..], n, strategy)?;
+    if first != second {
+        return Err(VerifiedRecoveryError::Disagreement { first, second });
+    }
+    Ok(first)
+}
+
+/// Sums the own share with the shares received from the peers, modulo the prime.
+/// # Arguments
+/// * `own_share` - The share kept by the server,
+/// * `received` - The shares received from the peers,
+/// * `n` - The prime modulus.
+pub fn sum_shares(own_share: u64, received: impl IntoIterator<Item = u64>, n: u64) -> u64 {
+    received
+        .into_iter()
+        .fold(own_share as u128 % n as u128, |sum, share| {
+            (sum + share as u128) % n as u128
+        }) as u64
+}
+
+/// Encodes a signed integer in the field, a negative integer `x` is mapped to `n + x`.
+///
+/// The encoding is additive: the sum of encoded integers decodes with [`decode_signed`] to their integer sum, as long as its absolute value is at most `(n - 1) / 2`.
+/// # Arguments
+/// * `x` - The signed integer, its absolute value must be at most `(n - 1) / 2`,
+/// * `n` - The prime modulus.
+pub fn encode_signed(x: i64, n: u64) -> u64 {
+    (x as i128).rem_euclid(n as i128) as u64
+}
+
+/// Decodes a field element encoded with [`encode_signed`], elements above `(n - 1) / 2` are negative integers.
+/// # Arguments
+/// * `value` - The field element, e.g. the sum of encoded integers,
+/// * `n` - The prime modulus.
+pub fn decode_signed(value: u64, n: u64) -> i64 {
+    let value = value % n;
+    if value > (n - 1) / 2 {
+        -((n - value) as i64)
+    } else {
+        value as i64
+    }
 }
 
 #[cfg(test)]
@@ -44,7 +285,7 @@ mod tests {
         let secret = rand::random::<u64>() % n;
         let points_len = rand::random::<u8>() % 100 + 3; // at least 3 points
         let points = (1..=points_len).collect::<Vec<u8>>();
-        let shares = split_secret(secret, &points, n);
+        let shares = split_secret(secret, &points, None, n).unwrap();
         let share_vec: Vec<Share> = shares
             .iter()
             .map(|(k, v)| Share {
@@ -55,4 +296,153 @@ mod tests {
         let recovered_secret = recover_secret(&share_vec, n).unwrap();
         assert_eq!(secret, recovered_secret);
     }
+
+    #[test]
+    fn test_recovery_strategies_agree() {
+        let n = 1_000_000_007;
+        for _ in 0..200 {
+            let secret = rand::random::<u64>() % n;
+            let points_len = rand::random::<u8>() % 30 + 1;
+            let points = (1..=points_len).collect::<Vec<u8>>();
+            let degree = rand::random_range(0..points.len());
+            let shares = split_secret(secret, &points, Some(degree), n)
+                .unwrap()
+                .into_iter()
+                .map(|(point, value)| Share { point, value })
+                .collect::<Vec<Share>>();
+
+            let full_polynomial =
+                recover_secret_with_strategy(&shares, n, RecoveryStrategy::FullPolynomial).unwrap();
+            let direct =
+                recover_secret_with_strategy(&shares, n, RecoveryStrategy::Direct).unwrap();
+            let montgomery =
+                recover_secret_with_strategy(&shares, n, RecoveryStrategy::Montgomery).unwrap();
+            assert_eq!(full_polynomial, direct);
+            assert_eq!(full_polynomial, montgomery);
+            assert_eq!(direct, secret);
+        }
+
+        // Shares at the same point are rejected by both strategies
+        let shares = vec![Share { point: 1, value: 5 }, Share { point: 1, value: 7 }];
+        for strategy in [
+            RecoveryStrategy::FullPolynomial,
+            RecoveryStrategy::Direct,
+            RecoveryStrategy::Montgomery,
+        ] {
+            assert!(recover_secret_with_strategy(&shares, n, strategy).is_err());
+            assert!(recover_secret_with_strategy(&[], n, strategy).is_err());
+        }
+    }
+
+    #[test]
+    fn test_signed_integers_sum() {
+        let n = 1_000_000_007;
+        assert_eq!(encode_signed(-1, n), n - 1);
+        assert_eq!(decode_signed(n - 1, n), -1);
+        assert_eq!(
+            decode_signed(encode_signed(-500_000_003, n), n),
+            -500_000_003
+        );
+        assert_eq!(decode_signed(encode_signed(500_000_003, n), n), 500_000_003);
+
+        let inputs = [1_200, -3_400, 56, -7, 0, 8_900, -10_000];
+        let points = (1..=3).collect::<Vec<u8>>();
+        let shares = inputs
+            .iter()
+            .map(|input| split_secret(encode_signed(*input, n), &points, None, n).unwrap())
+            .collect::<Vec<_>>();
+        let sum_shares_vec = points
+            .iter()
+            .map(|point| Share {
+                point: *point,
+                value: shares
+                    .iter()
+                    .fold(0, |sum, shares| sum_shares(sum, [shares[point]], n)),
+            })
+            .collect::<Vec<Share>>();
+        assert_eq!(
+            decode_signed(recover_secret(&sum_shares_vec, n).unwrap(), n),
+            inputs.iter().sum::<i64>()
+        );
+    }
+
+    #[test]
+    fn test_sum_shares() {
+        let n = 1_000_000_007;
+        assert_eq!(sum_shares(1, [2, 3], n), 6);
+        assert_eq!(sum_shares(n - 1, [n - 1, 2], n), 0);
+
+        // Summing the shares of two secrets gives a share of the sum of the secrets
+        let first_secret = rand::random::<u64>() % n;
+        let second_secret = rand::random::<u64>() % n;
+        let points = (1..=4).collect::<Vec<u8>>();
+        let first_shares = split_secret(first_secret, &points, None, n).unwrap();
+        let second_shares = split_secret(second_secret, &points, None, n).unwrap();
+        let sum_share_vec = points
+            .iter()
+            .map(|point| Share {
+                point: *point,
+                value: sum_shares(first_shares[point], [second_shares[point]], n),
+            })
+            .collect::<Vec<Share>>();
+        assert_eq!(
+            recover_secret(&sum_share_vec, n).unwrap(),
+            (first_secret + second_secret) % n
+        );
+    }
+
+    #[test]
+    fn test_verified_recovery_detects_tampered_share() {
+        let n = 1_000_000_007;
+        let secret = rand::random::<u64>() % n;
+        let points = (1..=5).collect::<Vec<u8>>();
+        let shares = split_secret(secret, &points, Some(1), n).unwrap();
+        let mut share_vec: Vec<Share> = shares
+            .iter()
+            .map(|(k, v)| Share {
+                point: *k,
+                value: *v,
+            })
+            .collect();
+        assert_eq!(recover_secret_verified(&share_vec, 2, n).unwrap(), secret);
+
+        share_vec.sort_by_key(|share| share.point);
+        share_vec[0].value = (share_vec[0].value + 1) % n;
+        assert!(matches!(
+            recover_secret_verified(&share_vec, 2, n),
+            Err(VerifiedRecoveryError::Disagreement { .. })
+        ));
+        assert!(matches!(
+            recover_secret_verified(&share_vec, 3, n),
+            Err(VerifiedRecoveryError::InsufficientShares { .. })
+        ));
+    }
+
+    #[test]
+    fn test_secret_sharing_with_degree() {
+        let n = 1_000_000_007;
+        let secret = rand::random::<u64>() % n;
+        let points = (1..=7).collect::<Vec<u8>>();
+        let degree = 3;
+        let shares = split_secret(secret, &points, Some(degree), n).unwrap();
+        let mut share_vec: Vec<Share> = shares
+            .iter()
+            .map(|(k, v)| Share {
+                point: *k,
+                value: *v,
+            })
+            .collect();
+        share_vec.sort_by_key(|share| share.point);
+
+        // Any `degree + 1` shares recover the secret
+        assert_eq!(recover_secret(&share_vec[..degree + 1], n).unwrap(), secret);
+        assert_eq!(
+            recover_secret(&share_vec[points.len() - degree - 1..], n).unwrap(),
+            secret
+        );
+        // Fewer shares interpolate a polynomial of lower degree, yielding garbage
+        assert_ne!(recover_secret(&share_vec[..degree], n).unwrap(), secret);
+
+        assert!(split_secret(secret, &points, Some(points.len()), n).is_err());
+    }
 }
@@ -0,0 +1,123 @@
+//! Modular multiplication in Montgomery form.
+//!
+//! A value `a` is represented as `a * R mod n` with `R = 2^64`, a product of two represented values is then reduced with shifts and
+//! multiplications instead of the `u128` division of `%`. Converting in and out of the representation costs a multiplication each,
+//! it pays off for computations chaining many multiplications, e.g. the interpolation of a polynomial.
+
+/// Parameters of the Montgomery form for an odd modulus, computed once per modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery {
+    modulus: u64,
+    /// `-n^(-1) mod R`
+    modulus_neg_inv: u64,
+    /// `R^2 mod n`, converts a value into the representation with a single multiplication
+    r2: u64,
+}
+
+impl Montgomery {
+    /// Computes the parameters of the modulus, `None` if the modulus is even or not below `2^63`.
+    pub const fn new(modulus: u64) -> Option<Self> {
+        if modulus.is_multiple_of(2) || modulus >= 1 << 63 {
+            return None;
+        }
+        // Newton iteration, every iteration doubles the number of correct low bits of the inverse,
+        // starting from 3 as `n * n = 1 mod 8` for an odd `n`
+        let mut inv = modulus;
+        let mut i = 0;
+        while i < 5 {
+            inv = inv.wrapping_mul(2_u64.wrapping_sub(modulus.wrapping_mul(inv)));
+            i += 1;
+        }
+        let r = ((1_u128 << 64) % modulus as u128) as u64;
+        Some(Self {
+            modulus,
+            modulus_neg_inv: inv.wrapping_neg(),
+            r2: ((r as u128 * r as u128) % modulus as u128) as u64,
+        })
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Converts a value into the representation.
+    pub fn to_montgomery(&self, value: u64) -> u64 {
+        self.mul(value % self.modulus, self.r2)
+    }
+
+    /// Converts a represented value back, the result is reduced in `[0, n)`.
+    pub fn from_montgomery(&self, value: u64) -> u64 {
+        self.reduce(value.into())
+    }
+
+    /// Multiplies two represented values.
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /// Adds two represented values.
+    pub fn add(&self, a: u64, b: u64) -> u64 {
+        // No overflow as the modulus is below 2^63
+        let sum = a + b;
+        if sum >= self.modulus {
+            sum - self.modulus
+        } else {
+            sum
+        }
+    }
+
+    /// Subtracts two represented values.
+    pub fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b { a - b } else { a + self.modulus - b }
+    }
+
+    /// Computes `t * R^(-1) mod n` for `t < n * R`.
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.modulus_neg_inv);
+        // `t + m * n` is divisible by R and below 2^128 as the modulus is below 2^63
+        let reduced = ((t + m as u128 * self.modulus as u128) >> 64) as u64;
+        if reduced >= self.modulus {
+            reduced - self.modulus
+        } else {
+            reduced
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_montgomery_arithmetic_matches_naive_arithmetic() {
+        for modulus in [3, 257, 1_000_000_007, (1 << 61) - 1, (1 << 63) - 25] {
+            let field = Montgomery::new(modulus).unwrap();
+            for _ in 0..1_000 {
+                let a = rand::random::<u64>();
+                let b = rand::random::<u64>();
+                let (a_mont, b_mont) = (field.to_montgomery(a), field.to_montgomery(b));
+                let (a, b) = (a % modulus, b % modulus);
+                assert_eq!(field.from_montgomery(a_mont), a);
+                assert_eq!(
+                    field.from_montgomery(field.mul(a_mont, b_mont)),
+                    (a as u128 * b as u128 % modulus as u128) as u64
+                );
+                assert_eq!(
+                    field.from_montgomery(field.add(a_mont, b_mont)),
+                    ((a as u128 + b as u128) % modulus as u128) as u64
+                );
+                assert_eq!(
+                    field.from_montgomery(field.sub(a_mont, b_mont)),
+                    ((a as u128 + modulus as u128 - b as u128) % modulus as u128) as u64
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_rejects_unsupported_moduli() {
+        assert!(Montgomery::new(2).is_none());
+        assert!(Montgomery::new(1_000_000_008).is_none());
+        assert!(Montgomery::new((1 << 63) + 1).is_none());
+    }
+}
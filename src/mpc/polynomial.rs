@@ -1,5 +1,7 @@
 use anyhow::anyhow;
 
+use super::montgomery::Montgomery;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Polynomial {
     /// Coefficients in ascending order, i.e. [1, 2, 3] -> 1 + 2x + 3x^2
@@ -36,10 +38,15 @@ impl Polynomial {
         self.coefficients[0]
     }
 
+    /// Interpolates the polynomial of lowest degree going through the coordinates.
+    ///
+    /// Cancelling leading coefficients are trimmed, e.g. values of a constant polynomial interpolate to a polynomial of degree 0 and all-zero values interpolate to the zero polynomial, without coefficients.
+    /// # Arguments
+    /// * `points` - The distinct points of the coordinates, at least one,
+    /// * `values` - The values at the points,
+    /// * `modulo` - The prime modulus.
     pub fn interpolate(points: &[u64], values: &[u64], modulo: u64) -> Result<Self, anyhow::Error> {
-        if points.len() != values.len() {
-            return Err(anyhow!("points and values must have the same length"));
-        }
+        validate_coordinates(points, values, modulo)?;
         let master_numerator = Self::interpolate_from_roots(points, modulo);
 
         let mut coefficients = vec![0; points.len()];
@@ -65,6 +72,75 @@ impl Polynomial {
         Ok(Self::new(coefficients))
     }
 
+    /// Evaluates the polynomial as [`Polynomial::evaluate`] does, multiplying in Montgomery form.
+    pub fn evaluate_montgomery(&self, point: u64, field: &Montgomery) -> u64 {
+        let point = field.to_montgomery(point);
+        let result = self.coefficients.iter().rev().fold(0, |acc, &c| {
+            field.add(field.mul(acc, point), field.to_montgomery(c))
+        });
+        field.from_montgomery(result)
+    }
+
+    /// Interpolates the polynomial as [`Polynomial::interpolate`] does, multiplying in Montgomery form.
+    ///
+    /// Values are kept in the representation for the whole interpolation, only the inversion of the weights goes back to the standard form.
+    pub fn interpolate_montgomery(
+        points: &[u64],
+        values: &[u64],
+        field: &Montgomery,
+    ) -> Result<Self, anyhow::Error> {
+        let modulo = field.modulus();
+        validate_coordinates(points, values, modulo)?;
+        let points = points
+            .iter()
+            .map(|&p| field.to_montgomery(p))
+            .collect::<Vec<u64>>();
+
+        // Coefficients of prod(x - x_i), the leading coefficient is one
+        let mut master_numerator = Vec::with_capacity(points.len() + 1);
+        master_numerator.push(field.to_montgomery(1));
+        for (i, &root) in points.iter().enumerate() {
+            master_numerator.push(field.to_montgomery(1));
+            for j in (1..=i).rev() {
+                master_numerator[j] = field.sub(
+                    master_numerator[j - 1],
+                    field.mul(master_numerator[j], root),
+                );
+            }
+            master_numerator[0] = field.sub(0, field.mul(master_numerator[0], root));
+        }
+
+        let mut coefficients = vec![0; points.len()];
+        let mut numerator = vec![0; points.len()];
+        for (&point, &value) in points.iter().zip(values) {
+            // Division by the monic `x - point`, the remainder is zero as `point` is a root
+            let degree = numerator.len() - 1;
+            numerator[degree] = master_numerator[degree + 1];
+            for j in (0..degree).rev() {
+                numerator[j] =
+                    field.add(master_numerator[j + 1], field.mul(point, numerator[j + 1]));
+            }
+            let denominator = numerator
+                .iter()
+                .rev()
+                .fold(0, |acc, &c| field.add(field.mul(acc, point), c));
+            let weight = field.mul(
+                field.to_montgomery(value),
+                field.to_montgomery(modulo_inv(field.from_montgomery(denominator), modulo)?),
+            );
+            for (coefficient, &c) in coefficients.iter_mut().zip(&numerator) {
+                *coefficient = field.add(*coefficient, field.mul(c, weight));
+            }
+        }
+
+        Ok(Self::new(
+            coefficients
+                .into_iter()
+                .map(|c| field.from_montgomery(c))
+                .collect(),
+        ))
+    }
+
     fn div(&self, other: &Self, n: u64) -> Result<(Self, Self), anyhow::Error> {
         if other.coefficients.is_empty() {
             return Err(anyhow!("unable to divide by zero coefficients"));
@@ -148,6 +224,68 @@ impl Polynomial {
     }
 }
 
+/// Computes the value at zero of the polynomial interpolating the coordinates, without computing its coefficients.
+///
+/// The value is the sum of the values weighted by the Lagrange basis polynomials at zero, i.e. `y_i * prod(x_j / (x_j - x_i))`, in `O(n^2)` multiplications and a single inversion per point.
+/// # Arguments
+/// * `points` - The distinct points of the coordinates, at least one,
+/// * `values` - The values at the points,
+/// * `modulo` - The prime modulus.
+pub fn interpolate_at_zero(
+    points: &[u64],
+    values: &[u64],
+    modulo: u64,
+) -> Result<u64, anyhow::Error> {
+    Ok(lagrange_terms_at_zero(points, values, modulo)?
+        .into_iter()
+        .fold(0_u128, |sum, term| (sum + term as u128) % modulo as u128) as u64)
+}
+
+/// Computes the terms summed by [`interpolate_at_zero`], i.e. `y_i * prod(x_j / (x_j - x_i))` for each coordinate, in the order of the points.
+pub fn lagrange_terms_at_zero(
+    points: &[u64],
+    values: &[u64],
+    modulo: u64,
+) -> Result<Vec<u64>, anyhow::Error> {
+    validate_coordinates(points, values, modulo)?;
+    let modulo_as_u128: u128 = modulo.into();
+    let points = points.iter().map(|p| p % modulo).collect::<Vec<u64>>();
+
+    let mut terms = Vec::with_capacity(points.len());
+    for (i, (&point, &value)) in points.iter().zip(values).enumerate() {
+        let mut numerator = 1_u128;
+        let mut denominator = 1_u128;
+        for (j, &other_point) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            numerator = numerator * other_point as u128 % modulo_as_u128;
+            denominator =
+                denominator * ((other_point + modulo - point) % modulo) as u128 % modulo_as_u128;
+        }
+        let weight = numerator * modulo_inv(denominator as u64, modulo)? as u128 % modulo_as_u128;
+        terms.push(((value % modulo) as u128 * weight % modulo_as_u128) as u64);
+    }
+    Ok(terms)
+}
+
+/// Validates the coordinates of an interpolation: as many values as points, at least one point and distinct points.
+fn validate_coordinates(points: &[u64], values: &[u64], modulo: u64) -> Result<(), anyhow::Error> {
+    if points.len() != values.len() {
+        return Err(anyhow!("points and values must have the same length"));
+    }
+    // Without any point, the zero polynomial would be silently returned
+    if points.is_empty() {
+        return Err(anyhow!("at least one point is needed to interpolate"));
+    }
+    let mut sorted_points = points.iter().map(|p| p % modulo).collect::<Vec<u64>>();
+    sorted_points.sort_unstable();
+    if sorted_points.windows(2).any(|w| w[0] == w[1]) {
+        return Err(anyhow!("points must be distinct to interpolate"));
+    }
+    Ok(())
+}
+
 /// Computes a^(-1) (mod n) using the Extended Euclidean Algorithm
 /// Returns None if a has no inverse mod n (i.e. if gcd(a, n) != 1)
 pub fn modulo_inv(a: u64, n: u64) -> Result<u64, anyhow::Error> {
@@ -265,6 +403,47 @@ mod tests {
         assert_eq!(remainder, Polynomial::new(vec![2, 2])); // 2x + 2
     }
 
+    #[test]
+    fn test_interpolation_of_all_zero_values() {
+        let n: u64 = 1_000_000_007;
+        let p = Polynomial::interpolate(&[1, 2, 3, 4], &[0, 0, 0, 0], n).unwrap();
+        assert_eq!(p, Polynomial::new(vec![]));
+        assert_eq!(p.evaluate_at_zero(), 0);
+        assert_eq!(p.evaluate(5, n), 0);
+    }
+
+    #[test]
+    fn test_interpolation_of_a_single_point() {
+        let n: u64 = 1_000_000_007;
+        let p = Polynomial::interpolate(&[3], &[42], n).unwrap();
+        assert_eq!(p, Polynomial::new(vec![42]));
+        assert_eq!(p.evaluate_at_zero(), 42);
+
+        let p = Polynomial::interpolate(&[3], &[0], n).unwrap();
+        assert_eq!(p, Polynomial::new(vec![]));
+    }
+
+    #[test]
+    fn test_interpolation_to_a_constant() {
+        let n: u64 = 1_000_000_007;
+        let constant = rand::random::<u64>() % n;
+        let points: Vec<u64> = (1..=10).collect();
+        let values = vec![constant; points.len()];
+        let p = Polynomial::interpolate(&points, &values, n).unwrap();
+        // Higher degree coefficients cancel out and are trimmed
+        assert_eq!(p, Polynomial::new(vec![constant]));
+        assert_eq!(p.evaluate_at_zero(), constant);
+    }
+
+    #[test]
+    fn test_interpolation_rejects_degenerate_points() {
+        let n: u64 = 1_000_000_007;
+        assert!(Polynomial::interpolate(&[], &[], n).is_err());
+        assert!(Polynomial::interpolate(&[1, 2], &[3], n).is_err());
+        assert!(Polynomial::interpolate(&[1, 2, 1], &[3, 4, 3], n).is_err());
+        assert!(Polynomial::interpolate(&[1, n + 1], &[3, 4], n).is_err());
+    }
+
     #[test]
     fn test_interpolation_from_coordinates() {
         let n: u64 = 1_000_000_007;
@@ -281,4 +460,42 @@ mod tests {
             assert_eq!(p.evaluate(x, n), y);
         }
     }
+
+    #[test]
+    fn test_montgomery_matches_naive_arithmetic() {
+        let n: u64 = 1_000_000_007;
+        let field = Montgomery::new(n).unwrap();
+
+        let poly = Polynomial::new((0..50).map(|_| rand::random::<u64>() % n).collect());
+        for _ in 0..100 {
+            let point = rand::random::<u64>();
+            assert_eq!(
+                poly.evaluate_montgomery(point, &field),
+                poly.evaluate(point, n)
+            );
+        }
+
+        let number_of_points: u64 = rand::random_range(2..=100);
+        let points: Vec<u64> = (0..number_of_points).collect();
+        let values: Vec<u64> = (0..number_of_points)
+            .map(|_| rand::random::<u64>() % n)
+            .collect();
+        let constant = rand::random::<u64>() % n;
+        for (points, values) in [
+            (points.clone(), values),
+            (points.clone(), vec![0; points.len()]),
+            (points.clone(), vec![constant; points.len()]),
+            (vec![3], vec![42]),
+            (vec![3], vec![0]),
+        ] {
+            assert_eq!(
+                Polynomial::interpolate_montgomery(&points, &values, &field).unwrap(),
+                Polynomial::interpolate(&points, &values, n).unwrap()
+            );
+        }
+
+        assert!(Polynomial::interpolate_montgomery(&[], &[], &field).is_err());
+        assert!(Polynomial::interpolate_montgomery(&[1, 2], &[3], &field).is_err());
+        assert!(Polynomial::interpolate_montgomery(&[1, n + 1], &[3, 4], &field).is_err());
+    }
 }
@@ -0,0 +1,218 @@
+use std::sync::Mutex;
+
+use uuid::Uuid;
+
+use super::{
+    peer_client::{
+        AdditionProcessProgress, AdditionProcessProgressQuery, PeerClient, PeerClientError,
+        ProcessFinalSum,
+    },
+    peer_messages::PeerMessagePayload,
+};
+
+/// Request the node intended to send to a peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DryRunRequest {
+    FetchProcessProgress {
+        peer_id: u8,
+        process_id: Uuid,
+        shares_sum: Option<u64>,
+        sent_share: Option<u64>,
+    },
+    NotifyProcessProgress {
+        peer_id: u8,
+        process_id: Uuid,
+    },
+    PushProcessProgress {
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+    },
+    FetchFinalSum {
+        peer_id: u8,
+        process_id: Uuid,
+    },
+    FetchOngoingProcessIds {
+        peer_id: u8,
+    },
+}
+
+/// Peer client logging the intended requests instead of sending them.
+///
+/// Peers are simulated as contributing a zero input with a zero polynomial, their shares are then zero and their shares sum is the share sent by the node.
+/// A process then deterministically completes with the node's own input as sum.
+#[derive(Default)]
+pub struct DryRunPeerClient {
+    requests: Mutex<Vec<DryRunRequest>>,
+}
+
+impl DryRunPeerClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests recorded so far, in the order they were intended to be sent.
+    pub fn requests(&self) -> Vec<DryRunRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn record(&self, request: DryRunRequest) {
+        tracing::info!("[dry run] skipping peer request: {:?}", request);
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(request);
+    }
+}
+
+#[async_trait::async_trait]
+impl PeerClient for DryRunPeerClient {
+    async fn fetch_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+        query: AdditionProcessProgressQuery,
+    ) -> Result<AdditionProcessProgress, PeerClientError> {
+        self.record(DryRunRequest::FetchProcessProgress {
+            peer_id,
+            process_id,
+            shares_sum: query.shares_sum,
+            sent_share: query.sent_share,
+        });
+        // The peer discloses its shares sum once the node sends its own
+        let shares_sum = query
+            .shares_sum
+            .map(|_| query.sent_share.unwrap_or_default());
+        Ok(AdditionProcessProgress {
+            share: 0,
+            shares_sum,
+            completed: false,
+        })
+    }
+
+    async fn notify_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<(), PeerClientError> {
+        self.record(DryRunRequest::NotifyProcessProgress {
+            peer_id,
+            process_id,
+        });
+        Ok(())
+    }
+
+    async fn push_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+    ) -> Result<(), PeerClientError> {
+        self.record(DryRunRequest::PushProcessProgress {
+            peer_id,
+            process_id,
+            payload,
+        });
+        Ok(())
+    }
+
+    /// Simulated peers do not report a final sum.
+    async fn fetch_final_sum(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<ProcessFinalSum, PeerClientError> {
+        self.record(DryRunRequest::FetchFinalSum {
+            peer_id,
+            process_id,
+        });
+        Ok(ProcessFinalSum { final_sum: None })
+    }
+
+    /// Simulated peers have no process of their own.
+    async fn fetch_ongoing_process_ids(&self, peer_id: u8) -> Result<Vec<Uuid>, PeerClientError> {
+        self.record(DryRunRequest::FetchOngoingProcessIds { peer_id });
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::*;
+    use crate::{
+        Peer,
+        domains::additions::{
+            AdditionProcess, CreateProcessRequest, PeerPoints,
+            completion::ProcessCompletions,
+            notifier::Notifier,
+            orchestrator::{OrchestratorSwitch, setup_addition_process_orchestrator},
+            repository::{AdditionProcessRepository, InMemoryAdditionProcessRepository},
+        },
+        metrics::Metrics,
+    };
+
+    #[tokio::test]
+    async fn test_dry_run_process_completes_with_logged_requests() {
+        // Unreachable peers, any network call would fail
+        let peers = vec![
+            Peer::new(2, "http://127.0.0.1:9".to_string()),
+            Peer::new(3, "http://127.0.0.1:9".to_string()),
+        ];
+        let repository = Arc::new(InMemoryAdditionProcessRepository::new());
+        let peer_client = Arc::new(DryRunPeerClient::new());
+        let (mut orchestrator, notifier) = setup_addition_process_orchestrator(
+            repository.clone(),
+            peer_client.clone(),
+            1,
+            Arc::new(Metrics::new()),
+            Arc::new(ProcessCompletions::default()),
+            Arc::new(OrchestratorSwitch::default()),
+            5,
+        );
+        tokio::spawn(async move { orchestrator.run().await });
+
+        let process_id = Uuid::new_v4();
+        let request =
+            CreateProcessRequest::new(process_id, 1, &[2, 3], None, None, &PeerPoints::default())
+                .unwrap();
+        let input = request.input_shares.input;
+        let sent_shares = request.input_shares.shares_to_send.clone();
+        repository.create_process(request).await.unwrap();
+
+        let mut sum = None;
+        for _ in 0..50 {
+            notifier.ping();
+            if let AdditionProcess::Completed(process) =
+                repository.get_process(process_id).await.unwrap()
+            {
+                sum = Some(process.final_sum);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(sum, Some(input));
+
+        let requests = peer_client.requests();
+        for peer in &peers {
+            for in_shares_sum_round in [false, true] {
+                assert!(requests.iter().any(|request| matches!(
+                    request,
+                    DryRunRequest::FetchProcessProgress {
+                        peer_id,
+                        process_id: requested_process_id,
+                        shares_sum,
+                        sent_share,
+                    } if *peer_id == peer.id
+                        && *requested_process_id == process_id
+                        && shares_sum.is_some() == in_shares_sum_round
+                        && *sent_share == sent_shares.get(&peer.id).cloned()
+                )));
+            }
+        }
+    }
+}
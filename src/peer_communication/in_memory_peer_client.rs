@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::anyhow;
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    http::{Method, Request, header},
+    response::Response,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use super::{
+    paths,
+    peer_client::{
+        AdditionProcessProgress, AdditionProcessProgressBatchEntry,
+        AdditionProcessProgressBatchItem, AdditionProcessProgressQuery, CORRELATION_ID_HEADER,
+        OngoingProcesses, PeerClient, PeerClientError, ProcessFinalSum, batch_entries_progresses,
+    },
+    peer_messages::PeerMessagePayload,
+};
+
+/// Routers of the nodes running in the same process, by peer ID.
+///
+/// Requests to a registered node are handled by its router directly, without going through the network.
+#[derive(Default)]
+pub struct InMemoryPeerBroker {
+    routers: RwLock<HashMap<u8, Router>>,
+}
+
+impl InMemoryPeerBroker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, peer_id: u8, router: Router) {
+        self.routers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(peer_id, router);
+    }
+
+    /// Handles a request with the router of a node.
+    /// # Arguments
+    /// * `peer_id` - The ID of the node,
+    /// * `request` - The request, its URI is the path and query of the endpoint.
+    pub async fn handle(
+        &self,
+        peer_id: u8,
+        request: Request<Body>,
+    ) -> Result<Response, PeerClientError> {
+        let router = self
+            .routers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&peer_id)
+            .cloned()
+            .ok_or(PeerClientError::UnknownPeer(peer_id))?;
+        match router.oneshot(request).await {
+            Ok(response) => Ok(response),
+            Err(infallible) => match infallible {},
+        }
+    }
+}
+
+/// Peer client sending the requests to the nodes registered in an [`InMemoryPeerBroker`].
+///
+/// Requests go through the same routes as with the HTTP client, only the transport differs.
+pub struct InMemoryPeerClient {
+    server_peer_id: u8,
+    broker: Arc<InMemoryPeerBroker>,
+}
+
+impl InMemoryPeerClient {
+    pub fn new(server_peer_id: u8, broker: Arc<InMemoryPeerBroker>) -> Self {
+        Self {
+            server_peer_id,
+            broker,
+        }
+    }
+
+    /// Sends a request to a peer and returns the body of its successful response.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer,
+    /// * `method` - The method of the request,
+    /// * `uri` - The path and query of the request,
+    /// * `process_id` - The ID of the process the request is about, forwarded as correlation ID,
+    /// * `body` - The JSON body of the request, if any.
+    async fn send(
+        &self,
+        peer_id: u8,
+        method: Method,
+        uri: String,
+        process_id: Option<Uuid>,
+        body: Option<impl Serialize>,
+    ) -> Result<Bytes, PeerClientError> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("X-PEER-ID", self.server_peer_id.to_string());
+        if let Some(process_id) = process_id {
+            request = request.header(CORRELATION_ID_HEADER, process_id.to_string());
+        }
+        let body = match body {
+            Some(body) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(serde_json::to_vec(&body).map_err(|e| {
+                    PeerClientError::Transport(anyhow!("{e}").context("serializing request body"))
+                })?)
+            }
+            None => Body::empty(),
+        };
+        let request = request.body(body).map_err(|e| {
+            PeerClientError::Transport(anyhow!("{e}").context("building in-memory request"))
+        })?;
+
+        let response = self.broker.handle(peer_id, request).await?;
+        if !response.status().is_success() {
+            return Err(PeerClientError::UnexpectedStatus {
+                peer_id,
+                status: response.status(),
+            });
+        }
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .map_err(|e| {
+                PeerClientError::Transport(anyhow!("{e}").context("reading in-memory response"))
+            })
+    }
+}
+
+fn decode<T: DeserializeOwned>(
+    peer_id: u8,
+    body: &[u8],
+    context: &str,
+) -> Result<T, PeerClientError> {
+    serde_json::from_slice(body).map_err(|e| PeerClientError::Decode {
+        peer_id,
+        source: anyhow!("{e}").context(context.to_string()),
+    })
+}
+
+#[async_trait::async_trait]
+impl PeerClient for InMemoryPeerClient {
+    async fn fetch_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+        query: AdditionProcessProgressQuery,
+    ) -> Result<AdditionProcessProgress, PeerClientError> {
+        let query = serde_urlencoded::to_string(&query).map_err(|e| {
+            PeerClientError::Transport(anyhow!("{e}").context("encoding progress query"))
+        })?;
+        let uri = format!(
+            "{}?{query}",
+            paths::process_path(paths::PROCESS_PROGRESS, process_id)
+        );
+        let body = self
+            .send(peer_id, Method::GET, uri, Some(process_id), None::<()>)
+            .await?;
+        decode(peer_id, &body, "parsing process progress response")
+    }
+
+    async fn fetch_process_progress_batch(
+        &self,
+        peer_id: u8,
+        items: Vec<AdditionProcessProgressBatchItem>,
+    ) -> Result<HashMap<Uuid, Result<AdditionProcessProgress, PeerClientError>>, PeerClientError>
+    {
+        let body = self
+            .send(
+                peer_id,
+                Method::POST,
+                paths::addition_path(paths::PROGRESS_BATCH),
+                None,
+                Some(items),
+            )
+            .await?;
+        let entries = decode::<Vec<AdditionProcessProgressBatchEntry>>(
+            peer_id,
+            &body,
+            "parsing process progress batch response",
+        )?;
+        Ok(batch_entries_progresses(peer_id, entries))
+    }
+
+    async fn notify_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<(), PeerClientError> {
+        self.send(
+            peer_id,
+            Method::POST,
+            paths::addition_path(paths::PROGRESS_NOTIFICATION),
+            Some(process_id),
+            None::<()>,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn push_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+    ) -> Result<(), PeerClientError> {
+        self.send(
+            peer_id,
+            Method::POST,
+            paths::process_path(paths::PROCESS_RECEIVE, process_id),
+            Some(process_id),
+            Some(payload),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn fetch_final_sum(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<ProcessFinalSum, PeerClientError> {
+        let body = self
+            .send(
+                peer_id,
+                Method::GET,
+                paths::process_path(paths::PROCESS_FINAL_SUM, process_id),
+                Some(process_id),
+                None::<()>,
+            )
+            .await?;
+        decode(peer_id, &body, "parsing final sum response")
+    }
+
+    async fn fetch_ongoing_process_ids(&self, peer_id: u8) -> Result<Vec<Uuid>, PeerClientError> {
+        let body = self
+            .send(
+                peer_id,
+                Method::GET,
+                paths::addition_path(paths::ONGOING_PROCESSES),
+                None,
+                None::<()>,
+            )
+            .await?;
+        decode::<OngoingProcesses>(peer_id, &body, "parsing ongoing processes response")
+            .map(|ongoing| ongoing.process_ids)
+    }
+}
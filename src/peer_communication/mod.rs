@@ -1,36 +1,83 @@
 use std::sync::Arc;
 
+use tokio_util::sync::CancellationToken;
+
+pub mod dry_run_peer_client;
+pub mod in_memory_peer_client;
 mod outbox_relayer;
 mod outbox_repository;
 mod outbox_sender;
+pub mod paths;
 pub mod peer_client;
 mod peer_messages;
+#[cfg(test)]
+pub(crate) mod scripted_peer_client;
 
-use crate::Peer;
-use outbox_relayer::OutboxPeerMessagesRelayer;
+use crate::Config;
+use dry_run_peer_client::DryRunPeerClient;
+use outbox_relayer::{AbandonPolicy, OutboxPeerMessagesRelayer, RetryPolicy};
 use outbox_repository::InMemoryOutboxRepository;
 use outbox_sender::OutboxPeerMessagesSender;
 
-pub use outbox_sender::PeerMessagesSender;
-use peer_client::HttpPeerClient;
-pub use peer_messages::PeerMessage;
+pub use outbox_sender::{PeerMessagesSender, SentMessagesReport};
+use peer_client::{HttpPeerClient, HttpPeerClientOptions, PeerClient};
+pub use peer_messages::{PeerMessage, PeerMessagePayload};
 
 pub fn setup_peer_communication(
-    server_peer_id: u8,
-    peers: &[Peer],
+    config: &Config,
+) -> Result<
+    (
+        Arc<dyn PeerClient>,
+        OutboxPeerMessagesSender,
+        OutboxPeerMessagesRelayer,
+        IntervalPing,
+    ),
+    anyhow::Error,
+> {
+    let peer_client: Arc<dyn PeerClient> = if config.dry_run {
+        tracing::warn!("Dry run mode enabled, requests to peers are logged instead of being sent");
+        Arc::new(DryRunPeerClient::new())
+    } else {
+        Arc::new(HttpPeerClient::new(
+            config.server_peer_id,
+            &config.peers,
+            HttpPeerClientOptions {
+                http2_prior_knowledge: config.peer_http2_prior_knowledge,
+                compression: config.peer_compression,
+                values_as_strings: config.values_as_strings,
+            },
+        )?)
+    };
+    Ok(setup_peer_communication_with_client(config, peer_client))
+}
+
+/// Sets up the communication with the peers through the given client, e.g. an in-memory client for simulated peers.
+pub fn setup_peer_communication_with_client(
+    config: &Config,
+    peer_client: Arc<dyn PeerClient>,
 ) -> (
-    Arc<HttpPeerClient>,
+    Arc<dyn PeerClient>,
     OutboxPeerMessagesSender,
     OutboxPeerMessagesRelayer,
     IntervalPing,
 ) {
-    let peer_client = Arc::new(peer_client::HttpPeerClient::new(server_peer_id, peers));
-
+    let server_peer_id = config.server_peer_id;
     let (tx, rx) = tokio::sync::mpsc::channel::<()>(100);
 
-    let repository = Arc::new(InMemoryOutboxRepository::new(tx.clone()));
+    let repository =
+        Arc::new(InMemoryOutboxRepository::new(tx.clone()).with_item_ttl(config.outbox_item_ttl));
     let messages_sender = OutboxPeerMessagesSender::new(server_peer_id, repository.clone());
-    let messages_relayer = OutboxPeerMessagesRelayer::new(repository, rx, 10, peer_client.clone());
+    let messages_relayer = OutboxPeerMessagesRelayer::new(
+        repository,
+        rx,
+        10,
+        peer_client.clone(),
+        AbandonPolicy::default(),
+    )
+    .with_retry_policy(RetryPolicy {
+        jitter: config.outbox_retry_jitter,
+        ..RetryPolicy::default()
+    });
     let relayer_pinger = IntervalPing::new(tx);
     (
         peer_client,
@@ -42,19 +89,79 @@ pub fn setup_peer_communication(
 
 pub struct IntervalPing {
     channel_sender: tokio::sync::mpsc::Sender<()>,
+    shutdown: CancellationToken,
 }
 impl IntervalPing {
     pub fn new(channel_sender: tokio::sync::mpsc::Sender<()>) -> Self {
-        Self { channel_sender }
+        Self {
+            channel_sender,
+            shutdown: CancellationToken::new(),
+        }
     }
 
-    pub async fn run(&self) -> Result<(), anyhow::Error> {
-        let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+    /// Sets the token stopping the interval ping loop once cancelled, the loop otherwise runs forever.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Runs the interval ping loop, sending a ping to the relayer at the specified interval until the shutdown token is cancelled.
+    /// # Arguments
+    /// * `interval` - The duration between each ping.
+    pub async fn run(&self, interval: std::time::Duration) -> Result<(), anyhow::Error> {
+        let mut interval = tokio::time::interval(interval);
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = self.shutdown.cancelled() => {
+                    tracing::debug!("Shutdown requested, stopping relayer interval ping");
+                    return Ok(());
+                }
+                _ = interval.tick() => {}
+            }
             if let Err(e) = self.channel_sender.send(()).await {
                 tracing::error!("Error sending ping to sender channel: {}", e);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_interval_ping_ticks_at_configured_interval() {
+        let (channel_sender, mut channel_receiver) = tokio::sync::mpsc::channel(100);
+        let interval_ping = IntervalPing::new(channel_sender);
+        let ping_loop =
+            tokio::spawn(async move { interval_ping.run(Duration::from_millis(250)).await });
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        ping_loop.abort();
+        let mut pings = 0;
+        while channel_receiver.try_recv().is_ok() {
+            pings += 1;
+        }
+        // The first tick is immediate, then one tick every 250ms
+        assert!((2..=4).contains(&pings), "{pings} pings sent");
+    }
+
+    #[tokio::test]
+    async fn test_interval_ping_stops_on_shutdown() {
+        let (channel_sender, _channel_receiver) = tokio::sync::mpsc::channel(100);
+        let shutdown = CancellationToken::new();
+        let interval_ping = IntervalPing::new(channel_sender).with_shutdown(shutdown.clone());
+        let ping_loop =
+            tokio::spawn(async move { interval_ping.run(Duration::from_millis(100)).await });
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        shutdown.cancel();
+        let result = tokio::time::timeout(Duration::from_secs(1), ping_loop)
+            .await
+            .expect("the interval ping loop should stop on shutdown")
+            .unwrap();
+        assert!(result.is_ok());
+    }
+}
@@ -1,10 +1,72 @@
 use futures::{StreamExt, stream};
-use std::sync::Arc;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use uuid::Uuid;
 
 use super::outbox_repository::{OutboxItem, OutboxRepository};
-use super::peer_client::PeerClient;
+use super::peer_client::{PeerClient, PeerClientError};
 use super::peer_messages::PeerMessage;
+use crate::metrics::Heartbeats;
+
+/// Policy deciding when a failed outbox item is abandoned instead of retried.
+#[derive(Clone, Copy, Debug)]
+pub struct AbandonPolicy {
+    /// Number of attempts after which an item failing with a retryable error is abandoned.
+    pub max_attempts: u8,
+    /// Whether items failing with a non retryable error, e.g. a `400` response, are abandoned at once.
+    pub abandon_non_retryable: bool,
+}
+
+impl Default for AbandonPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            abandon_non_retryable: true,
+        }
+    }
+}
+
+impl AbandonPolicy {
+    fn should_abandon(&self, error: &PeerClientError, attempts: u8) -> bool {
+        if self.abandon_non_retryable && !error.is_retryable() {
+            return true;
+        }
+        attempts >= self.max_attempts
+    }
+}
+
+/// Policy deciding when a failed outbox item is retried.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Delay before a failed item is retried.
+    pub delay: Duration,
+    /// Fraction of the delay randomly added to it, so that items failing together are not retried at the same instant.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_secs(1),
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn retry_delay(&self, rng: &mut impl Rng) -> Duration {
+        if self.jitter <= 0.0 {
+            return self.delay;
+        }
+        self.delay + self.delay.mul_f64(self.jitter * rng.random::<f64>())
+    }
+}
+
+/// Name of the outbox relayer in the heartbeats of the background tasks.
+pub const HEARTBEAT_TASK: &str = "outbox_relayer";
 
 /// Relayer for sending outbox items to their respective peers.
 /// It listens for signals on a channel to trigger dispatching of outbox items.
@@ -17,6 +79,14 @@ pub struct OutboxPeerMessagesRelayer {
     batch_size: usize,
     /// Peer client
     peer_client: Arc<dyn PeerClient>,
+    /// Policy for abandoning failed items.
+    abandon_policy: AbandonPolicy,
+    /// Policy for retrying failed items.
+    retry_policy: RetryPolicy,
+    /// Generator of the jitter of the retries.
+    rng: Mutex<StdRng>,
+    /// Heartbeats the relayer beats on each dispatch, if monitored.
+    heartbeats: Option<Arc<Heartbeats>>,
 }
 
 impl OutboxPeerMessagesRelayer {
@@ -25,55 +95,98 @@ impl OutboxPeerMessagesRelayer {
         channel_receiver: tokio::sync::mpsc::Receiver<()>,
         batch_size: usize,
         peer_client: Arc<dyn PeerClient>,
+        abandon_policy: AbandonPolicy,
     ) -> Self {
         Self {
             outbox_repository,
             channel_receiver,
             batch_size,
             peer_client,
+            abandon_policy,
+            retry_policy: RetryPolicy::default(),
+            rng: Mutex::new(StdRng::from_os_rng()),
+            heartbeats: None,
         }
     }
+
+    /// Retries failed items according to the given policy instead of after a fixed delay of one second.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Beats the given heartbeats on each dispatch, so that a dead relayer is reported.
+    pub fn with_heartbeats(mut self, heartbeats: Arc<Heartbeats>) -> Self {
+        self.heartbeats = Some(heartbeats);
+        self
+    }
 }
 
 impl OutboxPeerMessagesRelayer {
     /// Runs the relayer, continuously listening for signals to poll and dispatch outbox items.
     pub async fn run(&mut self) {
+        self.beat();
         while self.channel_receiver.recv().await.is_some() {
+            self.beat();
             if let Err(e) = self.poll_and_dispatch().await {
                 tracing::error!("Error during poll and dispatch: {}", e);
             }
         }
     }
 
+    fn beat(&self) {
+        if let Some(heartbeats) = &self.heartbeats {
+            heartbeats.beat(HEARTBEAT_TASK);
+        }
+    }
+
     /// Polls the outbox repository for items ready to send and dispatches them.
+    /// Expired items are abandoned beforehand, whatever their number of attempts.
     async fn poll_and_dispatch(&self) -> Result<(), anyhow::Error> {
+        let expired_items = self
+            .outbox_repository
+            .dequeue_expired_items()
+            .map_err(|e| e.context("dequeue expired outbox items"))?;
+        for item in &expired_items {
+            tracing::warn!(
+                "Abandoning outbox item {} for peer {} after {} attempts: time to live exceeded",
+                item.id,
+                item.message.peer_id(),
+                item.attempts
+            );
+        }
+
         let items = self
             .outbox_repository
             .get_items_ready_to_send(self.batch_size)
             .map_err(|e| e.context("poll and dispatch of outbox items"))?;
 
-        let item_extracts = items
-            .iter()
-            .map(|item| (item.id, item.attempts))
-            .collect::<Vec<(Uuid, u8)>>();
-
+        // Results are yielded in completion order, each result carries the extract of its item
         let bodies = stream::iter(items)
-            .map(|item| async move { self.dispatch(item).await })
+            .map(|item| async move {
+                let (id, attempts) = (item.id, item.attempts);
+                (id, attempts, self.dispatch(item).await)
+            })
             .buffer_unordered(5);
-        let results: Vec<Result<(), anyhow::Error>> = bodies.collect().await;
+        let results: Vec<(Uuid, u8, Result<(), PeerClientError>)> = bodies.collect().await;
 
         let mut success_ids = Vec::new();
         let mut to_be_retried_ids = Vec::new();
         let mut to_be_abandoned = Vec::new();
-        for (index, result) in results.into_iter().enumerate() {
+        for (id, attempts, result) in results {
             match result {
-                Ok(()) => success_ids.push(item_extracts[index].0),
-                Err(_) => {
-                    let attempts = item_extracts[index].1;
-                    if attempts >= 5 {
-                        to_be_abandoned.push(item_extracts[index].0);
+                Ok(()) => success_ids.push(id),
+                Err(e) => {
+                    if self.abandon_policy.should_abandon(&e, attempts) {
+                        tracing::warn!(
+                            "Abandoning outbox item {} after {} attempts: {}",
+                            id,
+                            attempts,
+                            e
+                        );
+                        to_be_abandoned.push(id);
                     } else {
-                        to_be_retried_ids.push(item_extracts[index].0);
+                        to_be_retried_ids.push(id);
                     }
                 }
             }
@@ -91,15 +204,18 @@ impl OutboxPeerMessagesRelayer {
                 to_be_retried_ids.len()
             );
 
-            self.outbox_repository
-                .re_enqueue_messages(&to_be_retried_ids, std::time::Duration::from_secs(1))
-                .map_err(|e| e.context("re-enqueue failed outbox items"))?;
+            for id in to_be_retried_ids {
+                let delay = {
+                    let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+                    self.retry_policy.retry_delay(&mut *rng)
+                };
+                self.outbox_repository
+                    .re_enqueue_messages(&[id], delay)
+                    .map_err(|e| e.context("re-enqueue failed outbox items"))?;
+            }
         }
         if !to_be_abandoned.is_empty() {
-            tracing::warn!(
-                "Outbox dispatch abandoning {} items after max attempts",
-                to_be_abandoned.len()
-            );
+            tracing::warn!("Outbox dispatch abandoning {} items", to_be_abandoned.len());
             self.outbox_repository
                 .dequeue_messages(&to_be_abandoned)
                 .map_err(|e| e.context("dequeue abandoned outbox items"))?;
@@ -110,11 +226,256 @@ impl OutboxPeerMessagesRelayer {
 
     /// Dispatches a single outbox item to its designated peer.
     /// The item is mapped to an HTTP POST request.
-    async fn dispatch(&self, item: OutboxItem) -> Result<(), anyhow::Error> {
+    async fn dispatch(&self, item: OutboxItem) -> Result<(), PeerClientError> {
         match item.message {
-            PeerMessage::NotifyProcessProgress { peer_id } => {
-                self.peer_client.notify_process_progress(peer_id).await
+            PeerMessage::NotifyProcessProgress {
+                peer_id,
+                process_id,
+                ..
+            } => {
+                self.peer_client
+                    .notify_process_progress(peer_id, process_id)
+                    .await
+            }
+            PeerMessage::PushProcessProgress {
+                peer_id,
+                process_id,
+                payload,
+                ..
+            } => {
+                self.peer_client
+                    .push_process_progress(peer_id, process_id, payload)
+                    .await
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use reqwest::StatusCode;
+
+    use super::super::outbox_repository::InMemoryOutboxRepository;
+    use super::super::outbox_sender::{OutboxPeerMessagesSender, PeerMessagesSender};
+    use super::super::scripted_peer_client::{PeerMethod, ScriptedPeerClient, ScriptedResponse};
+    use super::*;
+
+    /// Peer client answering `400` to the notifications of peer 2 and failing to connect to peer 3
+    fn failing_peer_client() -> Arc<ScriptedPeerClient> {
+        Arc::new(
+            ScriptedPeerClient::new()
+                .respond(
+                    PeerMethod::NotifyProcessProgress,
+                    2,
+                    ScriptedResponse::Status(StatusCode::BAD_REQUEST),
+                )
+                .respond(
+                    PeerMethod::NotifyProcessProgress,
+                    3,
+                    ScriptedResponse::Unreachable,
+                ),
+        )
+    }
+
+    /// Peer client failing to connect to peer 2 for the notifications, other peers receive them
+    fn dead_peer_client() -> Arc<ScriptedPeerClient> {
+        Arc::new(ScriptedPeerClient::new().respond(
+            PeerMethod::NotifyProcessProgress,
+            2,
+            ScriptedResponse::Unreachable,
+        ))
+    }
+
+    /// Peers which received a notification, in order, the dead peer 2 never does.
+    fn notified_peer_ids(peer_client: &ScriptedPeerClient) -> Vec<u8> {
+        peer_client
+            .calls(PeerMethod::NotifyProcessProgress)
+            .into_iter()
+            .filter(|peer_id| *peer_id != 2)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_failure_is_abandoned_and_retryable_is_retried() {
+        let (tx, rx) = tokio::sync::mpsc::channel::<()>(10);
+        let repository = Arc::new(InMemoryOutboxRepository::new(tx));
+        let process_id = Uuid::new_v4();
+        let relayer = OutboxPeerMessagesRelayer::new(
+            repository.clone(),
+            rx,
+            10,
+            failing_peer_client(),
+            AbandonPolicy::default(),
+        );
+        let items = repository
+            .enqueue_messages(vec![
+                PeerMessage::notify_process_progress(2, process_id),
+                PeerMessage::notify_process_progress(3, process_id),
+            ])
+            .await
+            .unwrap();
+
+        relayer.poll_and_dispatch().await.unwrap();
+
+        let remaining = repository
+            .dequeue_messages(&items.iter().map(|item| item.id).collect::<Vec<_>>())
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].message.peer_id(), 3);
+        assert_eq!(remaining[0].attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_items_retried_together_are_spread_by_jitter() {
+        let (tx, rx) = tokio::sync::mpsc::channel::<()>(10);
+        let repository = Arc::new(InMemoryOutboxRepository::new(tx));
+        let process_id = Uuid::new_v4();
+        let retry_policy = RetryPolicy {
+            delay: Duration::from_secs(1),
+            jitter: 0.5,
+        };
+        let mut relayer = OutboxPeerMessagesRelayer::new(
+            repository.clone(),
+            rx,
+            10,
+            failing_peer_client(),
+            AbandonPolicy::default(),
+        )
+        .with_retry_policy(retry_policy);
+        relayer.rng = Mutex::new(StdRng::seed_from_u64(7));
+        let items = repository
+            .enqueue_messages(vec![PeerMessage::notify_process_progress(3, process_id); 2])
+            .await
+            .unwrap();
+
+        let before_dispatch = chrono::Utc::now();
+        relayer.poll_and_dispatch().await.unwrap();
+        let after_dispatch = chrono::Utc::now();
+
+        let retried = repository
+            .dequeue_messages(&items.iter().map(|item| item.id).collect::<Vec<_>>())
+            .unwrap();
+        assert_eq!(retried.len(), 2);
+        assert_ne!(retried[0].scheduled_at, retried[1].scheduled_at);
+        for item in retried {
+            assert!(item.scheduled_at >= before_dispatch + chrono::Duration::seconds(1));
+            assert!(item.scheduled_at <= after_dispatch + chrono::Duration::milliseconds(1500));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dead_peer_backlog_does_not_delay_healthy_peer() {
+        let (tx, rx) = tokio::sync::mpsc::channel::<()>(10);
+        let repository = Arc::new(InMemoryOutboxRepository::new(tx));
+        let process_id = Uuid::new_v4();
+        let peer_client = dead_peer_client();
+        let relayer = OutboxPeerMessagesRelayer::new(
+            repository.clone(),
+            rx,
+            5,
+            peer_client.clone(),
+            AbandonPolicy::default(),
+        );
+        repository
+            .enqueue_messages(vec![
+                PeerMessage::notify_process_progress(2, process_id);
+                20
+            ])
+            .await
+            .unwrap();
+        repository
+            .enqueue_messages(vec![PeerMessage::notify_process_progress(3, process_id); 2])
+            .await
+            .unwrap();
+
+        relayer.poll_and_dispatch().await.unwrap();
+
+        assert_eq!(notified_peer_ids(&peer_client), vec![3, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_expired_item_is_abandoned_before_max_attempts() {
+        let (tx, rx) = tokio::sync::mpsc::channel::<()>(10);
+        let repository = Arc::new(
+            InMemoryOutboxRepository::new(tx).with_item_ttl(Some(Duration::from_millis(300))),
+        );
+        let relayer = OutboxPeerMessagesRelayer::new(
+            repository.clone(),
+            rx,
+            10,
+            failing_peer_client(),
+            AbandonPolicy {
+                max_attempts: 100,
+                abandon_non_retryable: true,
+            },
+        )
+        .with_retry_policy(RetryPolicy {
+            delay: Duration::from_millis(10),
+            jitter: 0.0,
+        });
+        // Peer 3 can never be reached, the item is retried on every dispatch
+        let item = repository
+            .enqueue_messages(vec![PeerMessage::notify_process_progress(
+                3,
+                Uuid::new_v4(),
+            )])
+            .await
+            .unwrap()
+            .remove(0);
+
+        relayer.poll_and_dispatch().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        relayer.poll_and_dispatch().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let ready_items = repository.get_items_ready_to_send(10).unwrap();
+        assert_eq!(ready_items.len(), 1);
+        assert_eq!(ready_items[0].id, item.id);
+        assert_eq!(ready_items[0].attempts, 2);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        // Expired items are no longer sent, even before being swept
+        assert!(repository.get_items_ready_to_send(10).unwrap().is_empty());
+        relayer.poll_and_dispatch().await.unwrap();
+        assert_eq!(repository.count_items().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_dispatches_retried_item_without_waiting_for_a_ping() {
+        let (tx, rx) = tokio::sync::mpsc::channel::<()>(10);
+        let repository = Arc::new(InMemoryOutboxRepository::new(tx));
+        let sender = OutboxPeerMessagesSender::new(1, repository.clone());
+        // Peer 2 stays unreachable, each dispatch of its item is counted as an attempt
+        let peer_client = dead_peer_client();
+        let mut relayer = OutboxPeerMessagesRelayer::new(
+            repository.clone(),
+            rx,
+            10,
+            peer_client.clone(),
+            AbandonPolicy::default(),
+        )
+        .with_retry_policy(RetryPolicy {
+            delay: Duration::from_millis(10),
+            jitter: 0.0,
+        });
+        tokio::spawn(async move { relayer.run().await });
+        sender
+            .send_messages(vec![
+                PeerMessage::notify_process_progress(2, Uuid::new_v4()),
+                PeerMessage::notify_process_progress(3, Uuid::new_v4()),
+            ])
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(notified_peer_ids(&peer_client), vec![3]);
+
+        // No interval ping runs, the item ready for a retry is only dispatched by the flush
+        assert_eq!(sender.pending_messages().unwrap(), 1);
+        sender.flush().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let items = repository.get_items_ready_to_send(10).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].attempts, 2);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
+    cmp::Reverse,
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, MutexGuard},
 };
 
 use super::peer_messages::PeerMessage;
@@ -42,25 +43,69 @@ pub trait OutboxRepository: Send + Sync {
     ) -> Result<(), anyhow::Error>;
 
     /// Retrieves a list of outbox items that are ready to be sent, up to a specified limit.
+    /// Expired items are never picked, whatever their number of attempts.
+    /// Items are picked in a round-robin fashion across peers, so that the backlog of an unreachable peer does not delay the other peers.
+    /// The items of a peer are picked by decreasing priority of their process.
     /// # Arguments
     /// * `limit` - The maximum number of outbox items to retrieve.
     /// # Returns
     /// * A vector of `OutboxItem` representing the items ready to be sent.
     fn get_items_ready_to_send(&self, limit: usize) -> Result<Vec<OutboxItem>, anyhow::Error>;
+
+    /// Dequeues the items older than the time to live of the outbox, if any.
+    /// # Returns
+    /// * A vector of `OutboxItem` representing the expired items.
+    fn dequeue_expired_items(&self) -> Result<Vec<OutboxItem>, anyhow::Error>;
+
+    /// Counts the outbox items not delivered yet, whether they are ready to be sent or scheduled for a retry.
+    fn count_items(&self) -> Result<usize, anyhow::Error>;
+
+    /// Pings the dispatcher channel so that the items ready to send are dispatched at once.
+    /// If the channel is full, a dispatch is already pending and the ping is skipped.
+    fn ping_dispatcher(&self) -> Result<(), anyhow::Error>;
 }
 
+/// Item of the outbox.
+///
+/// Wall-clock times are only informative, the scheduling and the expiry rely on the monotonic times so that a jump of the system clock, e.g. an NTP correction, does not stall nor hasten a delivery.
 #[derive(Clone)]
 pub struct OutboxItem {
     pub id: Uuid,
     pub message: PeerMessage,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub scheduled_at: chrono::DateTime<chrono::Utc>,
+    /// Monotonic time of the creation of the item
+    pub enqueued_at: tokio::time::Instant,
+    /// Monotonic time from which the item is ready to be sent
+    pub ready_at: tokio::time::Instant,
     pub attempts: u8,
 }
 
+impl OutboxItem {
+    /// Key ordering the deliveries, messages of higher priority processes come first.
+    /// Items of the same priority scheduled at the same time are ordered by creation then by ID so that the order is deterministic.
+    fn delivery_order(
+        &self,
+    ) -> (
+        Reverse<u8>,
+        tokio::time::Instant,
+        tokio::time::Instant,
+        Uuid,
+    ) {
+        (
+            Reverse(self.message.priority()),
+            self.ready_at,
+            self.enqueued_at,
+            self.id,
+        )
+    }
+}
+
 pub struct InMemoryOutboxRepository {
     items: Arc<Mutex<HashMap<Uuid, OutboxItem>>>,
     channel_sender: tokio::sync::mpsc::Sender<()>,
+    /// Duration after the creation of an item after which it expires, items never expire if not set
+    item_ttl: Option<std::time::Duration>,
 }
 
 impl InMemoryOutboxRepository {
@@ -68,8 +113,28 @@ impl InMemoryOutboxRepository {
         Self {
             items: Arc::new(Mutex::new(HashMap::new())),
             channel_sender: sender,
+            item_ttl: None,
         }
     }
+
+    /// Expires the items once the given duration has elapsed since their creation, so that the lifetime of an item is bounded by elapsed time as well as by its attempts.
+    pub fn with_item_ttl(mut self, item_ttl: Option<std::time::Duration>) -> Self {
+        self.item_ttl = item_ttl;
+        self
+    }
+
+    fn is_expired(&self, item: &OutboxItem, now: tokio::time::Instant) -> bool {
+        self.item_ttl
+            .is_some_and(|ttl| now.saturating_duration_since(item.enqueued_at) >= ttl)
+    }
+
+    /// Locks the items, recovering them if a thread panicked while holding the lock.
+    ///
+    /// Every item update is completed under the lock, a panic can not leave an item half updated, the items are therefore still consistent.
+    /// Failing on a poisoned lock would instead stop the delivery of every message for good.
+    fn lock_items(&self) -> MutexGuard<'_, HashMap<Uuid, OutboxItem>> {
+        self.items.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 #[async_trait::async_trait]
@@ -80,15 +145,16 @@ impl OutboxRepository for InMemoryOutboxRepository {
     ) -> Result<Vec<OutboxItem>, anyhow::Error> {
         let items = {
             let mut items = Vec::new();
-            let mut items_lock = self.items.lock().map_err(|e| {
-                anyhow!("{e}").context("failed to lock items mutex while enquing multiple")
-            })?;
+            let mut items_lock = self.lock_items();
+            let (created_at, enqueued_at) = (chrono::Utc::now(), tokio::time::Instant::now());
             for message in messages {
                 let item = OutboxItem {
                     id: Uuid::new_v4(),
                     message,
-                    created_at: chrono::Utc::now(),
-                    scheduled_at: chrono::Utc::now(),
+                    created_at,
+                    scheduled_at: created_at,
+                    enqueued_at,
+                    ready_at: enqueued_at,
                     attempts: 0,
                 };
                 items_lock.insert(item.id, item.clone());
@@ -107,16 +173,14 @@ impl OutboxRepository for InMemoryOutboxRepository {
         ids: &[Uuid],
         delay: std::time::Duration,
     ) -> Result<(), anyhow::Error> {
-        let mut items_lock = self
-            .items
-            .lock()
-            .map_err(|e| anyhow!("{e}").context("failed to lock items mutex while re-enquing"))?;
-        let now = chrono::Utc::now();
+        let mut items_lock = self.lock_items();
+        let (now, monotonic_now) = (chrono::Utc::now(), tokio::time::Instant::now());
         for id in ids {
             let item = items_lock.get_mut(id).ok_or_else(|| {
                 anyhow!("Outbox item with id {id} not found").context("re-enqueueing items")
             })?;
             item.attempts += 1;
+            item.ready_at = monotonic_now + delay;
             item.scheduled_at = now
                 + chrono::Duration::from_std(delay).map_err(|e| {
                     anyhow!("{e}").context("converting std::time::Duration to chrono::Duration")
@@ -127,10 +191,7 @@ impl OutboxRepository for InMemoryOutboxRepository {
 
     fn dequeue_messages(&self, ids: &[Uuid]) -> Result<Vec<OutboxItem>, anyhow::Error> {
         let mut items = Vec::new();
-        let mut items_lock = self
-            .items
-            .lock()
-            .map_err(|e| anyhow!("{e}").context("failed to lock items mutex while dequeing"))?;
+        let mut items_lock = self.lock_items();
         for id in ids {
             if let Some(item) = items_lock.remove(id) {
                 items.push(item);
@@ -140,16 +201,240 @@ impl OutboxRepository for InMemoryOutboxRepository {
     }
 
     fn get_items_ready_to_send(&self, limit: usize) -> Result<Vec<OutboxItem>, anyhow::Error> {
-        let items_lock = self.items.lock().map_err(|e| {
-            anyhow!("{e}").context("failed to lock items mutex while getting ready to send")
-        })?;
-        let now = chrono::Utc::now();
-        let mut ready_items: Vec<OutboxItem> = items_lock
+        let items_lock = self.lock_items();
+        let now = tokio::time::Instant::now();
+        let mut ready_items_per_peer: HashMap<u8, Vec<&OutboxItem>> = HashMap::new();
+        for item in items_lock
             .values()
-            .filter(|item| item.scheduled_at <= now)
-            .cloned()
-            .collect();
-        ready_items.sort_by_key(|item| item.scheduled_at);
-        Ok(ready_items.into_iter().take(limit).collect())
+            .filter(|item| item.ready_at <= now && !self.is_expired(item, now))
+        {
+            ready_items_per_peer
+                .entry(item.message.peer_id())
+                .or_default()
+                .push(item);
+        }
+        // Queues are served starting with the peer having the highest priority then oldest ready item
+        let mut queues = ready_items_per_peer
+            .into_values()
+            .map(|mut queue| {
+                queue.sort_by_key(|item| item.delivery_order());
+                queue.into_iter()
+            })
+            .collect::<Vec<_>>();
+        queues.sort_by_key(|queue| queue.as_slice().first().map(|item| item.delivery_order()));
+
+        let mut selected_items = Vec::new();
+        while selected_items.len() < limit {
+            let mut exhausted = true;
+            for queue in &mut queues {
+                if selected_items.len() >= limit {
+                    break;
+                }
+                if let Some(item) = queue.next() {
+                    selected_items.push(item.clone());
+                    exhausted = false;
+                }
+            }
+            if exhausted {
+                break;
+            }
+        }
+        Ok(selected_items)
+    }
+
+    fn dequeue_expired_items(&self) -> Result<Vec<OutboxItem>, anyhow::Error> {
+        let mut items_lock = self.lock_items();
+        let now = tokio::time::Instant::now();
+        let expired_ids = items_lock
+            .values()
+            .filter(|item| self.is_expired(item, now))
+            .map(|item| item.id)
+            .collect::<Vec<_>>();
+        Ok(expired_ids
+            .into_iter()
+            .filter_map(|id| items_lock.remove(&id))
+            .collect())
+    }
+
+    fn count_items(&self) -> Result<usize, anyhow::Error> {
+        Ok(self.lock_items().len())
+    }
+
+    fn ping_dispatcher(&self) -> Result<(), anyhow::Error> {
+        match self.channel_sender.try_send(()) {
+            Ok(()) | Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => Ok(()),
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                Err(anyhow!("outbox dispatcher channel is closed"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_outbox_operations_survive_a_panic_while_locked() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        let repository = Arc::new(InMemoryOutboxRepository::new(tx));
+        let process_id = Uuid::new_v4();
+        repository
+            .enqueue_messages(vec![PeerMessage::notify_process_progress(2, process_id)])
+            .await
+            .unwrap();
+
+        let poisoning_repository = repository.clone();
+        let panicked = std::thread::spawn(move || {
+            let _items_lock = poisoning_repository.items.lock().unwrap();
+            panic!("panic while holding the outbox lock");
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert!(repository.items.is_poisoned());
+
+        let enqueued = repository
+            .enqueue_messages(vec![PeerMessage::notify_process_progress(3, process_id)])
+            .await
+            .unwrap();
+        let ready_items = repository.get_items_ready_to_send(10).unwrap();
+        assert_eq!(ready_items.len(), 2);
+        repository
+            .re_enqueue_messages(&[enqueued[0].id], std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(repository.get_items_ready_to_send(10).unwrap().len(), 1);
+        let dequeued = repository
+            .dequeue_messages(&ready_items.iter().map(|item| item.id).collect::<Vec<_>>())
+            .unwrap();
+        assert_eq!(dequeued.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_items_scheduled_at_the_same_time_are_ordered_deterministically() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        let repository = InMemoryOutboxRepository::new(tx);
+        let process_id = Uuid::new_v4();
+        repository
+            .enqueue_messages(
+                [2, 2, 2, 3, 3, 2]
+                    .map(|peer_id| PeerMessage::notify_process_progress(peer_id, process_id))
+                    .to_vec(),
+            )
+            .await
+            .unwrap();
+        let now = tokio::time::Instant::now();
+        for item in repository.lock_items().values_mut() {
+            item.enqueued_at = now;
+            item.ready_at = now;
+        }
+
+        let ready_items = repository.get_items_ready_to_send(10).unwrap();
+        let ids = ready_items.iter().map(|item| item.id).collect::<Vec<_>>();
+        for _ in 0..10 {
+            assert_eq!(
+                repository
+                    .get_items_ready_to_send(10)
+                    .unwrap()
+                    .iter()
+                    .map(|item| item.id)
+                    .collect::<Vec<_>>(),
+                ids
+            );
+        }
+        // Each peer queue is ordered by ID, the queue with the lowest first ID is served first
+        let queue_ids = |peer_id: u8| {
+            let mut queue_ids = ready_items
+                .iter()
+                .filter(|item| item.message.peer_id() == peer_id)
+                .map(|item| item.id)
+                .collect::<Vec<_>>();
+            queue_ids.sort();
+            queue_ids
+        };
+        let (mut first_queue, mut second_queue) = (queue_ids(2), queue_ids(3));
+        if second_queue[0] < first_queue[0] {
+            std::mem::swap(&mut first_queue, &mut second_queue);
+        }
+        let mut expected_ids = Vec::<Uuid>::new();
+        for i in 0..4 {
+            expected_ids.extend(first_queue.get(i).copied());
+            expected_ids.extend(second_queue.get(i).copied());
+        }
+        assert_eq!(ids, expected_ids);
+    }
+
+    #[tokio::test]
+    async fn test_higher_priority_items_are_sent_first() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        let repository = InMemoryOutboxRepository::new(tx);
+        let (low_priority_process_id, high_priority_process_id) = (Uuid::new_v4(), Uuid::new_v4());
+        repository
+            .enqueue_messages(vec![
+                PeerMessage::notify_process_progress(2, low_priority_process_id),
+                PeerMessage::notify_process_progress(3, low_priority_process_id),
+            ])
+            .await
+            .unwrap();
+        repository
+            .enqueue_messages(vec![
+                PeerMessage::notify_process_progress(3, high_priority_process_id).with_priority(5),
+            ])
+            .await
+            .unwrap();
+
+        let ready_items = repository.get_items_ready_to_send(1).unwrap();
+        assert_eq!(ready_items.len(), 1);
+        assert_eq!(ready_items[0].message.priority(), 5);
+        // Within the queue of a peer, the high priority item is served before the older one
+        let peer_3_items = repository
+            .get_items_ready_to_send(10)
+            .unwrap()
+            .into_iter()
+            .filter(|item| item.message.peer_id() == 3)
+            .map(|item| item.message.priority())
+            .collect::<Vec<_>>();
+        assert_eq!(peer_3_items, vec![5, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_timing_is_unaffected_by_a_clock_jump() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        let repository = InMemoryOutboxRepository::new(tx)
+            .with_item_ttl(Some(std::time::Duration::from_secs(60)));
+        let item = repository
+            .enqueue_messages(vec![PeerMessage::notify_process_progress(
+                2,
+                Uuid::new_v4(),
+            )])
+            .await
+            .unwrap()
+            .remove(0);
+        repository
+            .re_enqueue_messages(&[item.id], std::time::Duration::from_millis(100))
+            .unwrap();
+        // The system clock jumps an hour backward, the wall-clock times are now an hour ahead of it
+        for item in repository.lock_items().values_mut() {
+            item.created_at += chrono::Duration::hours(1);
+            item.scheduled_at += chrono::Duration::hours(1);
+        }
+
+        assert!(repository.get_items_ready_to_send(10).unwrap().is_empty());
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        let ready_items = repository.get_items_ready_to_send(10).unwrap();
+        assert_eq!(ready_items.len(), 1);
+        assert_eq!(ready_items[0].id, item.id);
+
+        // The system clock jumps two hours forward, the item neither becomes ready early nor expires
+        repository
+            .re_enqueue_messages(&[item.id], std::time::Duration::from_millis(100))
+            .unwrap();
+        for item in repository.lock_items().values_mut() {
+            item.created_at -= chrono::Duration::hours(2);
+            item.scheduled_at -= chrono::Duration::hours(2);
+        }
+        assert!(repository.get_items_ready_to_send(10).unwrap().is_empty());
+        assert!(repository.dequeue_expired_items().unwrap().is_empty());
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        assert_eq!(repository.get_items_ready_to_send(10).unwrap().len(), 1);
     }
 }
@@ -10,25 +10,41 @@ use thiserror::Error;
 #[async_trait::async_trait]
 pub trait PeerMessagesSender: Send + Sync {
     /// Send multiple messages to their respective peers.
+    /// Messages addressed to the server's own peer ID are rejected, the other messages are still sent.
     /// # Arguments
     /// * `messages` - A vector of messages to send, each containing the peer ID, process ID, and payload.
+    /// # Returns
+    /// * The peers whose message was accepted for delivery and the peers whose message was rejected.
     /// # Errors
-    /// * `PeerMessagesSenderError::OwnPeerId` - If attempting to send a message to own peer ID.
     /// * `PeerMessagesSenderError::Unknown` - For any other errors.
     async fn send_messages(
         &self,
         messages: Vec<PeerMessage>,
-    ) -> Result<(), PeerMessagesSenderError>;
+    ) -> Result<SentMessagesReport, PeerMessagesSenderError>;
+
+    /// Number of messages accepted for delivery and not delivered yet.
+    fn pending_messages(&self) -> Result<usize, PeerMessagesSenderError>;
+
+    /// Delivers the pending messages ready to be sent at once instead of on the next scheduled dispatch.
+    /// Messages scheduled for a later retry are not hastened.
+    fn flush(&self) -> Result<(), PeerMessagesSenderError>;
 }
 
 #[derive(Debug, Error)]
 pub enum PeerMessagesSenderError {
-    #[error("Attempted to send message to own peer ID {0}")]
-    OwnPeerId(u8),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
+/// Outcome of sending messages, per peer.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SentMessagesReport {
+    /// Peers whose message was enqueued, it is delivered with retries.
+    pub enqueued_peer_ids: Vec<u8>,
+    /// Peers whose message was rejected, e.g. the server's own peer ID.
+    pub rejected_peer_ids: Vec<u8>,
+}
+
 pub struct OutboxPeerMessagesSender {
     server_peer_id: u8,
     outbox_repository: Arc<dyn OutboxRepository>,
@@ -48,18 +64,73 @@ impl PeerMessagesSender for OutboxPeerMessagesSender {
     async fn send_messages(
         &self,
         messages: Vec<PeerMessage>,
-    ) -> Result<(), PeerMessagesSenderError> {
-        if messages.is_empty() {
-            return Ok(());
+    ) -> Result<SentMessagesReport, PeerMessagesSenderError> {
+        let (own_messages, messages): (Vec<PeerMessage>, Vec<PeerMessage>) = messages
+            .into_iter()
+            .partition(|m| m.peer_id() == self.server_peer_id);
+        let report = SentMessagesReport {
+            enqueued_peer_ids: messages.iter().map(PeerMessage::peer_id).collect(),
+            rejected_peer_ids: own_messages.iter().map(PeerMessage::peer_id).collect(),
+        };
+        if !report.rejected_peer_ids.is_empty() {
+            tracing::warn!(
+                "Rejected {} messages addressed to own peer ID {}",
+                report.rejected_peer_ids.len(),
+                self.server_peer_id
+            );
         }
-        if messages.iter().any(|m| m.peer_id() == self.server_peer_id) {
-            return Err(PeerMessagesSenderError::OwnPeerId(self.server_peer_id));
+        if messages.is_empty() {
+            return Ok(report);
         }
         self.outbox_repository
             .enqueue_messages(messages)
             .await
             .map_err(|e| anyhow!(e).context("enqueuing messages to outbox repository"))?;
 
-        Ok(())
+        Ok(report)
+    }
+
+    fn pending_messages(&self) -> Result<usize, PeerMessagesSenderError> {
+        Ok(self
+            .outbox_repository
+            .count_items()
+            .map_err(|e| e.context("counting outbox items"))?)
+    }
+
+    fn flush(&self) -> Result<(), PeerMessagesSenderError> {
+        Ok(self
+            .outbox_repository
+            .ping_dispatcher()
+            .map_err(|e| e.context("flushing outbox"))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::outbox_repository::InMemoryOutboxRepository;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_messages_to_own_peer_id_are_rejected() {
+        let (tx, _rx) = tokio::sync::mpsc::channel::<()>(10);
+        let repository = Arc::new(InMemoryOutboxRepository::new(tx));
+        let sender = OutboxPeerMessagesSender::new(1, repository.clone());
+
+        let report = sender
+            .send_messages(PeerMessage::notify_process_progress_to_all(
+                [1, 2, 3],
+                uuid::Uuid::new_v4(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            SentMessagesReport {
+                enqueued_peer_ids: vec![2, 3],
+                rejected_peer_ids: vec![1],
+            }
+        );
+        assert_eq!(repository.get_items_ready_to_send(10).unwrap().len(), 2);
+        assert_eq!(sender.pending_messages().unwrap(), 2);
     }
 }
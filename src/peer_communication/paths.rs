@@ -0,0 +1,55 @@
+//! Paths of the endpoints called by peers.
+//!
+//! They are shared by the peer client and the router registration so that they can not diverge.
+
+use uuid::Uuid;
+
+/// Prefix under which the addition endpoints are nested.
+pub const ADDITIONS: &str = "/additions";
+/// Notification of the progress of a process, relative to [`ADDITIONS`].
+pub const PROGRESS_NOTIFICATION: &str = "/progress-notification";
+/// Progress of a process, relative to [`ADDITIONS`].
+pub const PROCESS_PROGRESS: &str = "/{id}/progress";
+/// Progress of several processes at once, relative to [`ADDITIONS`].
+pub const PROGRESS_BATCH: &str = "/progress/batch";
+/// Progress pushed to a process, relative to [`ADDITIONS`].
+pub const PROCESS_RECEIVE: &str = "/{id}/receive";
+
+/// Ongoing processes a peer participates in, relative to [`ADDITIONS`].
+pub const ONGOING_PROCESSES: &str = "/ongoing";
+
+/// Final sum of a process, relative to [`ADDITIONS`].
+pub const PROCESS_FINAL_SUM: &str = "/{id}/final-sum";
+
+/// Builds the absolute path of an addition endpoint.
+/// # Arguments
+/// * `endpoint` - The path of the endpoint relative to [`ADDITIONS`], e.g. [`PROGRESS_NOTIFICATION`].
+pub fn addition_path(endpoint: &str) -> String {
+    format!("{ADDITIONS}{endpoint}")
+}
+
+/// Builds the absolute path of an addition endpoint of a process.
+/// # Arguments
+/// * `endpoint` - The path of the endpoint relative to [`ADDITIONS`], e.g. [`PROCESS_PROGRESS`],
+/// * `process_id` - The ID of the process, substituted to the `{id}` segment.
+pub fn process_path(endpoint: &str, process_id: Uuid) -> String {
+    addition_path(&endpoint.replace("{id}", &process_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_path() {
+        let process_id = Uuid::new_v4();
+        assert_eq!(
+            process_path(PROCESS_PROGRESS, process_id),
+            format!("/additions/{process_id}/progress")
+        );
+        assert_eq!(
+            addition_path(PROGRESS_NOTIFICATION),
+            "/additions/progress-notification"
+        );
+    }
+}
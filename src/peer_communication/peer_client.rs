@@ -1,107 +1,623 @@
 use std::collections::HashMap;
 
 use anyhow::anyhow;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use uuid::Uuid;
 
-use crate::Peer;
+use crate::{Peer, value_encoding};
+
+use super::{paths, peer_messages::PeerMessagePayload};
+
+/// Header carrying the ID correlating the requests exchanged between peers, it is the ID of the process the request is about.
+/// Every node uses the same process ID, the logs of a process can then be stitched together across nodes.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Maximum number of processes whose progress is fetched in a single batch request.
+pub const MAX_PROGRESS_BATCH_SIZE: usize = 100;
 
 #[async_trait::async_trait]
 pub trait PeerClient: Send + Sync {
+    /// Fetches the progress of a process from a peer.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer to fetch the progress from,
+    /// * `process_id` - The ID of the process,
+    /// * `query` - The shares sum of the server once computed and the share it sent to the peer.
     async fn fetch_process_progress(
         &self,
         peer_id: u8,
         process_id: Uuid,
-    ) -> Result<AdditionProcessProgress, anyhow::Error>;
+        query: AdditionProcessProgressQuery,
+    ) -> Result<AdditionProcessProgress, PeerClientError>;
+
+    /// Fetches the progress of several processes from a peer in a single request.
+    ///
+    /// The default implementation fetches the progress of each process with [`PeerClient::fetch_process_progress`].
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer to fetch the progresses from,
+    /// * `items` - The processes and their query, at most [`MAX_PROGRESS_BATCH_SIZE`] of them.
+    /// # Returns
+    /// * The progress or the error of each process, by process ID.
+    async fn fetch_process_progress_batch(
+        &self,
+        peer_id: u8,
+        items: Vec<AdditionProcessProgressBatchItem>,
+    ) -> Result<HashMap<Uuid, Result<AdditionProcessProgress, PeerClientError>>, PeerClientError>
+    {
+        let mut progresses = HashMap::with_capacity(items.len());
+        for item in items {
+            let progress = self
+                .fetch_process_progress(peer_id, item.process_id, item.query)
+                .await;
+            progresses.insert(item.process_id, progress);
+        }
+        Ok(progresses)
+    }
+
+    /// Notifies a peer that a process progressed, the peer then polls its ongoing processes.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer to notify,
+    /// * `process_id` - The ID of the process which progressed, forwarded as correlation ID.
+    async fn notify_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<(), PeerClientError>;
+
+    /// Pushes the progress of a process to a peer.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer to push the progress to,
+    /// * `process_id` - The ID of the process,
+    /// * `payload` - The share or shares sum for the peer.
+    async fn push_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+    ) -> Result<(), PeerClientError>;
+
+    /// Fetches the final sum of a process reconstructed by a peer.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer to fetch the final sum from,
+    /// * `process_id` - The ID of the process.
+    async fn fetch_final_sum(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<ProcessFinalSum, PeerClientError>;
+
+    /// Lists the ongoing processes of a peer in which the server participates.
+    ///
+    /// The default implementation lists no process.
+    /// # Arguments
+    /// * `peer_id` - The ID of the peer to list the processes of.
+    async fn fetch_ongoing_process_ids(&self, _peer_id: u8) -> Result<Vec<Uuid>, PeerClientError> {
+        Ok(vec![])
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PeerClientError {
+    #[error("Peer ID {0} not found")]
+    UnknownPeer(u8),
+    #[error("Peer {peer_id} responded with HTTP {status}")]
+    UnexpectedStatus { peer_id: u8, status: StatusCode },
+    #[error("Failed to decode response from peer {peer_id}: {source}")]
+    Decode { peer_id: u8, source: anyhow::Error },
+    #[error(transparent)]
+    Transport(anyhow::Error),
+}
+
+impl PeerClientError {
+    /// Whether the failed request may succeed if retried.
+    ///
+    /// Transport failures, server errors, timeouts and rate limiting are transient.
+    /// Other client errors, unknown peers and undecodable responses will fail the same way on retry.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PeerClientError::UnknownPeer(_) => false,
+            PeerClientError::UnexpectedStatus { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::REQUEST_TIMEOUT
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            PeerClientError::Decode { .. } => false,
+            PeerClientError::Transport(_) => true,
+        }
+    }
+}
 
-    async fn notify_process_progress(&self, peer_id: u8) -> Result<(), anyhow::Error>;
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AdditionProcessProgressQuery {
+    /// Shares sum of the caller, only set once it has computed it.
+    /// The peer records the caller in the shares sum round and only discloses its own shares sum to the callers it recorded there.
+    #[serde(default)]
+    pub shares_sum: Option<u64>,
+    /// Share the caller generated for the peer, used by the peer to detect a caller whose process state changed.
+    #[serde(default)]
+    pub sent_share: Option<u64>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AdditionProcessProgress {
+    #[serde(with = "crate::value_encoding::value")]
     pub share: u64,
+    #[serde(default, with = "crate::value_encoding::optional_value")]
     pub shares_sum: Option<u64>,
+    /// Whether the process is completed on the responding peer, it only answers until its completed processes retention elapses
+    #[serde(default)]
+    pub completed: bool,
+}
+
+/// Process whose progress is fetched within a batch.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AdditionProcessProgressBatchItem {
+    pub process_id: Uuid,
+    #[serde(flatten)]
+    pub query: AdditionProcessProgressQuery,
+}
+
+/// Progress of a process within a batch, with the status the single process request would have responded with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AdditionProcessProgressBatchEntry {
+    pub process_id: Uuid,
+    pub status: u16,
+    /// Progress of the process, only set for a successful status
+    #[serde(default)]
+    pub progress: Option<AdditionProcessProgress>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OngoingProcesses {
+    pub process_ids: Vec<Uuid>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProcessFinalSum {
+    /// Final sum reconstructed by the peer, `None` if the process is not completed
+    #[serde(default, with = "crate::value_encoding::optional_value")]
+    pub final_sum: Option<u64>,
 }
 
 pub struct HttpPeerClient {
     server_peer_id: u8,
-    peer_urls: HashMap<u8, String>,
+    peers: HashMap<u8, Peer>,
     client: reqwest::Client,
+    values_as_strings: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HttpPeerClientOptions {
+    /// Whether requests are sent using HTTP/2 without TLS (h2c) with prior knowledge, peers must then accept HTTP/2.
+    pub http2_prior_knowledge: bool,
+    /// Whether gzip responses are accepted, uncompressed responses are still handled.
+    pub compression: bool,
+    /// Whether the values pushed to the peers are serialized as decimal strings instead of JSON numbers.
+    pub values_as_strings: bool,
 }
 
 impl HttpPeerClient {
-    pub fn new(server_peer_id: u8, peers: &[Peer]) -> Self {
-        let peer_urls = peers
+    pub fn new(
+        server_peer_id: u8,
+        peers: &[Peer],
+        options: HttpPeerClientOptions,
+    ) -> Result<Self, anyhow::Error> {
+        let peers = peers
             .iter()
-            .map(|p| (p.id, p.url.clone()))
-            .collect::<HashMap<u8, String>>();
+            .map(|p| (p.id, p.clone()))
+            .collect::<HashMap<u8, Peer>>();
 
-        Self {
-            server_peer_id,
-            peer_urls,
-            client: reqwest::Client::new(),
+        let mut client_builder = reqwest::Client::builder().gzip(options.compression);
+        if options.http2_prior_knowledge {
+            client_builder = client_builder.http2_prior_knowledge();
         }
+        let client = client_builder
+            .build()
+            .map_err(|e| anyhow!("{e}").context("building peer HTTP client"))?;
+
+        Ok(Self {
+            server_peer_id,
+            peers,
+            client,
+            values_as_strings: options.values_as_strings,
+        })
+    }
+
+    fn endpoint_url(&self, peer_id: u8, path: &str) -> Result<String, PeerClientError> {
+        let peer = self
+            .peers
+            .get(&peer_id)
+            .ok_or(PeerClientError::UnknownPeer(peer_id))?;
+        Ok(peer.endpoint_url(path))
     }
 }
 
 #[async_trait::async_trait]
 impl PeerClient for HttpPeerClient {
-    async fn notify_process_progress(&self, peer_id: u8) -> Result<(), anyhow::Error> {
-        let peer_url = self
-            .peer_urls
-            .get(&peer_id)
-            .ok_or_else(|| anyhow!("Peer ID {} not found", peer_id))?;
+    async fn notify_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<(), PeerClientError> {
+        let url =
+            self.endpoint_url(peer_id, &paths::addition_path(paths::PROGRESS_NOTIFICATION))?;
 
         let response = self
             .client
-            .post(format!("{}/additions/progress-notification", peer_url))
+            .post(url)
             .header("X-PEER-ID", self.server_peer_id.to_string())
+            .header(CORRELATION_ID_HEADER, process_id.to_string())
             .send()
             .await
-            .map_err(|e| anyhow!("{e}").context("notifying peer of process progress"))?;
+            .map_err(|e| {
+                PeerClientError::Transport(
+                    anyhow!("{e}").context("notifying peer of process progress"),
+                )
+            })?;
 
         if !response.status().is_success() {
-            return Err(anyhow!(
-                "Failed to notify peer {} of process progress: HTTP {}",
+            return Err(PeerClientError::UnexpectedStatus {
                 peer_id,
-                response.status()
-            ));
+                status: response.status(),
+            });
         }
 
         Ok(())
     }
 
+    async fn push_process_progress(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+    ) -> Result<(), PeerClientError> {
+        let url = self.endpoint_url(
+            peer_id,
+            &paths::process_path(paths::PROCESS_RECEIVE, process_id),
+        )?;
+
+        // The payload is serialized when the request is built
+        let request = value_encoding::sync_scope(self.values_as_strings, || {
+            self.client.post(url).json(&payload)
+        });
+        let response = request
+            .header("X-PEER-ID", self.server_peer_id.to_string())
+            .header(CORRELATION_ID_HEADER, process_id.to_string())
+            .send()
+            .await
+            .map_err(|e| {
+                PeerClientError::Transport(
+                    anyhow!("{e}").context("pushing process progress to peer"),
+                )
+            })?;
+
+        if !response.status().is_success() {
+            return Err(PeerClientError::UnexpectedStatus {
+                peer_id,
+                status: response.status(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_final_sum(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<ProcessFinalSum, PeerClientError> {
+        let url = self.endpoint_url(
+            peer_id,
+            &paths::process_path(paths::PROCESS_FINAL_SUM, process_id),
+        )?;
+
+        let response = self
+            .client
+            .get(url)
+            .header("X-PEER-ID", self.server_peer_id.to_string())
+            .header(CORRELATION_ID_HEADER, process_id.to_string())
+            .send()
+            .await
+            .map_err(|e| {
+                PeerClientError::Transport(anyhow!("{e}").context("fetching final sum from peer"))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(PeerClientError::UnexpectedStatus {
+                peer_id,
+                status: response.status(),
+            });
+        }
+
+        response
+            .json::<ProcessFinalSum>()
+            .await
+            .map_err(|e| PeerClientError::Decode {
+                peer_id,
+                source: anyhow!("{e}").context("parsing final sum response"),
+            })
+    }
+
+    async fn fetch_ongoing_process_ids(&self, peer_id: u8) -> Result<Vec<Uuid>, PeerClientError> {
+        let url = self.endpoint_url(peer_id, &paths::addition_path(paths::ONGOING_PROCESSES))?;
+
+        let response = self
+            .client
+            .get(url)
+            .header("X-PEER-ID", self.server_peer_id.to_string())
+            .send()
+            .await
+            .map_err(|e| {
+                PeerClientError::Transport(
+                    anyhow!("{e}").context("listing ongoing processes of peer"),
+                )
+            })?;
+
+        if !response.status().is_success() {
+            return Err(PeerClientError::UnexpectedStatus {
+                peer_id,
+                status: response.status(),
+            });
+        }
+
+        response
+            .json::<OngoingProcesses>()
+            .await
+            .map(|ongoing| ongoing.process_ids)
+            .map_err(|e| PeerClientError::Decode {
+                peer_id,
+                source: anyhow!("{e}").context("parsing ongoing processes response"),
+            })
+    }
+
+    async fn fetch_process_progress_batch(
+        &self,
+        peer_id: u8,
+        items: Vec<AdditionProcessProgressBatchItem>,
+    ) -> Result<HashMap<Uuid, Result<AdditionProcessProgress, PeerClientError>>, PeerClientError>
+    {
+        let url = self.endpoint_url(peer_id, &paths::addition_path(paths::PROGRESS_BATCH))?;
+
+        // The batch is about several processes, it does not carry a correlation ID
+        let response = self
+            .client
+            .post(url)
+            .json(&items)
+            .header("X-PEER-ID", self.server_peer_id.to_string())
+            .send()
+            .await
+            .map_err(|e| {
+                PeerClientError::Transport(
+                    anyhow!("{e}").context("fetching process progress batch from peer"),
+                )
+            })?;
+
+        if !response.status().is_success() {
+            return Err(PeerClientError::UnexpectedStatus {
+                peer_id,
+                status: response.status(),
+            });
+        }
+
+        let entries = response
+            .json::<Vec<AdditionProcessProgressBatchEntry>>()
+            .await
+            .map_err(|e| PeerClientError::Decode {
+                peer_id,
+                source: anyhow!("{e}").context("parsing process progress batch response"),
+            })?;
+
+        Ok(batch_entries_progresses(peer_id, entries))
+    }
+
     async fn fetch_process_progress(
         &self,
         peer_id: u8,
         process_id: Uuid,
-    ) -> Result<AdditionProcessProgress, anyhow::Error> {
-        let peer_url = self
-            .peer_urls
-            .get(&peer_id)
-            .ok_or_else(|| anyhow!("Peer ID {} not found", peer_id))?;
+        query: AdditionProcessProgressQuery,
+    ) -> Result<AdditionProcessProgress, PeerClientError> {
+        let url = self.endpoint_url(
+            peer_id,
+            &paths::process_path(paths::PROCESS_PROGRESS, process_id),
+        )?;
 
         let response = self
             .client
-            .get(format!("{}/additions/{}/progress", peer_url, process_id))
+            .get(url)
+            .query(&query)
             .header("X-PEER-ID", self.server_peer_id.to_string())
+            .header(CORRELATION_ID_HEADER, process_id.to_string())
             .send()
             .await
-            .map_err(|e| anyhow!("{e}").context("fetching process progress from peer"))?;
+            .map_err(|e| {
+                PeerClientError::Transport(
+                    anyhow!("{e}").context("fetching process progress from peer"),
+                )
+            })?;
 
         if !response.status().is_success() {
-            return Err(anyhow!(
-                "Failed to fetch process progress from peer {}: HTTP {}",
+            return Err(PeerClientError::UnexpectedStatus {
                 peer_id,
-                response.status()
-            ));
+                status: response.status(),
+            });
         }
 
         let progress = response
             .json::<AdditionProcessProgress>()
             .await
-            .map_err(|e| anyhow!("{e}").context("parsing process progress response"))?;
+            .map_err(|e| PeerClientError::Decode {
+                peer_id,
+                source: anyhow!("{e}").context("parsing process progress response"),
+            })?;
 
         Ok(progress)
     }
 }
+
+/// Maps the entries of a batch response to the progress or the error of each process.
+pub(crate) fn batch_entries_progresses(
+    peer_id: u8,
+    entries: Vec<AdditionProcessProgressBatchEntry>,
+) -> HashMap<Uuid, Result<AdditionProcessProgress, PeerClientError>> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let status =
+                StatusCode::from_u16(entry.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            let progress = if status.is_success() {
+                entry.progress.ok_or_else(|| PeerClientError::Decode {
+                    peer_id,
+                    source: anyhow!("missing progress of process {}", entry.process_id),
+                })
+            } else {
+                Err(PeerClientError::UnexpectedStatus { peer_id, status })
+            };
+            (entry.process_id, progress)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_url_without_path_prefix() {
+        let client = HttpPeerClient::new(
+            1,
+            &[
+                Peer::new(2, "http://localhost:3001".to_string()),
+                Peer::new(3, "http://localhost:3002/".to_string()),
+            ],
+            HttpPeerClientOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            client
+                .endpoint_url(2, "/additions/progress-notification")
+                .unwrap(),
+            "http://localhost:3001/additions/progress-notification"
+        );
+        assert_eq!(
+            client
+                .endpoint_url(3, "additions/progress-notification")
+                .unwrap(),
+            "http://localhost:3002/additions/progress-notification"
+        );
+        assert!(client.endpoint_url(4, "/additions").is_err());
+    }
+
+    #[test]
+    fn test_error_retryability() {
+        let status_error = |status| PeerClientError::UnexpectedStatus { peer_id: 2, status };
+        assert!(!status_error(StatusCode::BAD_REQUEST).is_retryable());
+        assert!(!status_error(StatusCode::NOT_FOUND).is_retryable());
+        assert!(status_error(StatusCode::INTERNAL_SERVER_ERROR).is_retryable());
+        assert!(status_error(StatusCode::SERVICE_UNAVAILABLE).is_retryable());
+        assert!(status_error(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(!PeerClientError::UnknownPeer(4).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_connection_error_is_retryable() {
+        // Nothing listens on the discard port
+        let client = HttpPeerClient::new(
+            1,
+            &[Peer::new(2, "http://127.0.0.1:9".to_string())],
+            HttpPeerClientOptions::default(),
+        )
+        .unwrap();
+        let error = client
+            .notify_process_progress(2, Uuid::new_v4())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, PeerClientError::Transport(_)));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_endpoint_url_with_path_prefix() {
+        let client = HttpPeerClient::new(
+            1,
+            &[
+                Peer::new(2, "http://gateway/node-2".to_string()),
+                Peer::new(3, "http://gateway/node-3/".to_string()),
+            ],
+            HttpPeerClientOptions::default(),
+        )
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        assert_eq!(
+            client
+                .endpoint_url(2, &format!("/additions/{process_id}/progress"))
+                .unwrap(),
+            format!("http://gateway/node-2/additions/{process_id}/progress")
+        );
+        assert_eq!(
+            client
+                .endpoint_url(3, &format!("/additions/{process_id}/progress"))
+                .unwrap(),
+            format!("http://gateway/node-3/additions/{process_id}/progress")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_peer_requests_carry_process_id_as_correlation_id() {
+        let (header_sender, mut header_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let app = axum::Router::new().route(
+            &paths::addition_path(paths::PROGRESS_NOTIFICATION),
+            axum::routing::post(move |headers: axum::http::HeaderMap| async move {
+                let _ = header_sender.send(headers.get(CORRELATION_ID_HEADER).cloned());
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = HttpPeerClient::new(
+            1,
+            &[Peer::new(2, format!("http://{addr}"))],
+            HttpPeerClientOptions::default(),
+        )
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        client.notify_process_progress(2, process_id).await.unwrap();
+
+        let correlation_id = header_receiver.recv().await.unwrap().unwrap();
+        assert_eq!(correlation_id.to_str().unwrap(), process_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_progress_is_not_retryable() {
+        let app = axum::Router::new().route(
+            &paths::addition_path(paths::PROCESS_PROGRESS),
+            axum::routing::get(|| async { "{\"share\": \"not a number\"}" }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = HttpPeerClient::new(
+            1,
+            &[Peer::new(2, format!("http://{addr}"))],
+            HttpPeerClientOptions::default(),
+        )
+        .unwrap();
+        let Err(error) = client
+            .fetch_process_progress(
+                2,
+                Uuid::new_v4(),
+                AdditionProcessProgressQuery {
+                    shares_sum: None,
+                    sent_share: None,
+                },
+            )
+            .await
+        else {
+            panic!("malformed progress was decoded");
+        };
+        assert!(matches!(error, PeerClientError::Decode { peer_id: 2, .. }));
+        assert!(!error.is_retryable());
+    }
+}
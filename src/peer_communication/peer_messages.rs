@@ -1,16 +1,139 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
 #[derive(Clone)]
 pub enum PeerMessage {
-    NotifyProcessProgress { peer_id: u8 },
+    NotifyProcessProgress {
+        peer_id: u8,
+        /// Process whose progress triggered the notification
+        process_id: Uuid,
+        /// Priority of the process, higher priority messages are delivered first
+        priority: u8,
+    },
+    PushProcessProgress {
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+        priority: u8,
+    },
+}
+
+/// Progress of a process pushed to a peer, the peer applies it without waiting for its next poll.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PeerMessagePayload {
+    /// Share generated by the sender for the peer
+    Share {
+        #[serde(with = "crate::value_encoding::value")]
+        value: u64,
+    },
+    /// Shares sum computed by the sender
+    SharesSum {
+        #[serde(with = "crate::value_encoding::value")]
+        value: u64,
+    },
 }
 
 impl PeerMessage {
-    pub fn notify_process_progress(peer_id: u8) -> Self {
-        Self::NotifyProcessProgress { peer_id }
+    pub fn notify_process_progress(peer_id: u8, process_id: Uuid) -> Self {
+        Self::NotifyProcessProgress {
+            peer_id,
+            process_id,
+            priority: 0,
+        }
+    }
+
+    /// Builds the progress notifications of the given peers, ordered by peer ID so that sends are reproducible.
+    /// # Arguments
+    /// * `peer_ids` - The IDs of the peers to notify,
+    /// * `process_id` - The ID of the process whose progress triggered the notifications.
+    pub fn notify_process_progress_to_all(
+        peer_ids: impl IntoIterator<Item = u8>,
+        process_id: Uuid,
+    ) -> Vec<Self> {
+        let mut peer_ids = peer_ids.into_iter().collect::<Vec<u8>>();
+        peer_ids.sort_unstable();
+        peer_ids
+            .into_iter()
+            .map(|peer_id| Self::notify_process_progress(peer_id, process_id))
+            .collect()
+    }
+
+    pub fn push_process_progress(
+        peer_id: u8,
+        process_id: Uuid,
+        payload: PeerMessagePayload,
+    ) -> Self {
+        Self::PushProcessProgress {
+            peer_id,
+            process_id,
+            payload,
+            priority: 0,
+        }
+    }
+
+    /// Sets the priority of the message to the one of its process, `0` by default.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        match &mut self {
+            PeerMessage::NotifyProcessProgress { priority: p, .. } => *p = priority,
+            PeerMessage::PushProcessProgress { priority: p, .. } => *p = priority,
+        }
+        self
     }
 
     pub fn peer_id(&self) -> u8 {
         match self {
-            PeerMessage::NotifyProcessProgress { peer_id } => *peer_id,
+            PeerMessage::NotifyProcessProgress { peer_id, .. } => *peer_id,
+            PeerMessage::PushProcessProgress { peer_id, .. } => *peer_id,
+        }
+    }
+
+    pub fn priority(&self) -> u8 {
+        match self {
+            PeerMessage::NotifyProcessProgress { priority, .. } => *priority,
+            PeerMessage::PushProcessProgress { priority, .. } => *priority,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_process_progress_to_all_is_ordered_by_peer_id() {
+        let messages = PeerMessage::notify_process_progress_to_all([5, 2, 9, 3], Uuid::new_v4());
+        assert_eq!(
+            messages
+                .iter()
+                .map(PeerMessage::peer_id)
+                .collect::<Vec<_>>(),
+            vec![2, 3, 5, 9]
+        );
+    }
+
+    #[test]
+    fn test_payload_wire_format() {
+        // Format decoded by the receive handler of the peers, values are accepted as numbers or strings
+        assert_eq!(
+            serde_json::from_str::<PeerMessagePayload>(r#"{"type":"share","value":5}"#).unwrap(),
+            PeerMessagePayload::Share { value: 5 }
+        );
+        assert_eq!(
+            serde_json::from_str::<PeerMessagePayload>(r#"{"type":"shares_sum","value":"7"}"#)
+                .unwrap(),
+            PeerMessagePayload::SharesSum { value: 7 }
+        );
+        for payload in [
+            PeerMessagePayload::Share { value: u64::MAX },
+            PeerMessagePayload::SharesSum { value: 0 },
+        ] {
+            let encoded = serde_json::to_string(&payload).unwrap();
+            assert_eq!(
+                serde_json::from_str::<PeerMessagePayload>(&encoded).unwrap(),
+                payload
+            );
         }
+        assert!(serde_json::from_str::<PeerMessagePayload>(r#"{"type":"new_process"}"#).is_err());
     }
 }
@@ -0,0 +1,227 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+use super::{
+    peer_client::{
+        AdditionProcessProgress, AdditionProcessProgressQuery, PeerClient, PeerClientError,
+        ProcessFinalSum,
+    },
+    peer_messages::PeerMessagePayload,
+};
+
+/// Method of the peer client whose responses are scripted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PeerMethod {
+    FetchProcessProgress,
+    NotifyProcessProgress,
+    PushProcessProgress,
+    FetchFinalSum,
+    FetchOngoingProcessIds,
+}
+
+/// Scripted response of a peer, it must match the method it answers.
+#[derive(Clone)]
+pub enum ScriptedResponse {
+    Progress(AdditionProcessProgress),
+    Delivered,
+    FinalSum(Option<u64>),
+    ProcessIds(Vec<Uuid>),
+    /// The peer responds with the given HTTP status
+    Status(StatusCode),
+    /// The peer can not be connected to
+    Unreachable,
+}
+
+impl ScriptedResponse {
+    /// Progress of a peer sending the given share, without shares sum.
+    pub fn share(share: u64) -> Self {
+        Self::Progress(AdditionProcessProgress {
+            share,
+            shares_sum: None,
+            completed: false,
+        })
+    }
+
+    fn into_result(self, peer_id: u8) -> Result<Self, PeerClientError> {
+        match self {
+            Self::Status(status) => Err(PeerClientError::UnexpectedStatus { peer_id, status }),
+            Self::Unreachable => Err(PeerClientError::Transport(anyhow::anyhow!(
+                "peer unreachable"
+            ))),
+            response => Ok(response),
+        }
+    }
+}
+
+/// Peer client answering each method with responses scripted per peer, recording the calls.
+///
+/// Queued responses of a peer are answered first, in order. The peer then repeats its scripted response, or the response scripted for every peer.
+/// Without any script, progresses are fetched with a zero share, messages are delivered, final sums are unknown and no ongoing process is listed.
+#[derive(Default)]
+pub struct ScriptedPeerClient {
+    queued: Mutex<HashMap<(PeerMethod, u8), VecDeque<ScriptedResponse>>>,
+    repeated: HashMap<(PeerMethod, u8), ScriptedResponse>,
+    repeated_for_all: HashMap<PeerMethod, ScriptedResponse>,
+    delay: Option<Duration>,
+    calls: Mutex<Vec<(PeerMethod, u8)>>,
+    answered_calls: Mutex<Vec<(PeerMethod, u8)>>,
+}
+
+impl ScriptedPeerClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every call of the method to the peer with the response, once its queued responses are exhausted.
+    pub fn respond(mut self, method: PeerMethod, peer_id: u8, response: ScriptedResponse) -> Self {
+        self.repeated.insert((method, peer_id), response);
+        self
+    }
+
+    /// Answers every call of the method to a peer without its own script with the response.
+    pub fn respond_to_all(mut self, method: PeerMethod, response: ScriptedResponse) -> Self {
+        self.repeated_for_all.insert(method, response);
+        self
+    }
+
+    /// Answers the next calls of the method to the peer with the responses, in order.
+    pub fn queue(
+        mut self,
+        method: PeerMethod,
+        peer_id: u8,
+        responses: impl IntoIterator<Item = ScriptedResponse>,
+    ) -> Self {
+        self.queued
+            .get_mut()
+            .unwrap()
+            .entry((method, peer_id))
+            .or_default()
+            .extend(responses);
+        self
+    }
+
+    /// Answers every call after the delay.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Peers called with the method, in the order the calls started.
+    pub fn calls(&self, method: PeerMethod) -> Vec<u8> {
+        Self::peer_ids(&self.calls, method)
+    }
+
+    /// Peers called with the method whose call was answered, in order.
+    /// A call dropped before its delay elapsed is never answered.
+    pub fn answered_calls(&self, method: PeerMethod) -> Vec<u8> {
+        Self::peer_ids(&self.answered_calls, method)
+    }
+
+    fn peer_ids(calls: &Mutex<Vec<(PeerMethod, u8)>>, method: PeerMethod) -> Vec<u8> {
+        calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(called_method, _)| *called_method == method)
+            .map(|(_, peer_id)| *peer_id)
+            .collect()
+    }
+
+    async fn call(
+        &self,
+        method: PeerMethod,
+        peer_id: u8,
+    ) -> Result<ScriptedResponse, PeerClientError> {
+        self.calls.lock().unwrap().push((method, peer_id));
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        let queued = self
+            .queued
+            .lock()
+            .unwrap()
+            .get_mut(&(method, peer_id))
+            .and_then(VecDeque::pop_front);
+        let response = queued
+            .or_else(|| self.repeated.get(&(method, peer_id)).cloned())
+            .or_else(|| self.repeated_for_all.get(&method).cloned())
+            .unwrap_or(match method {
+                PeerMethod::FetchProcessProgress => ScriptedResponse::share(0),
+                PeerMethod::NotifyProcessProgress | PeerMethod::PushProcessProgress => {
+                    ScriptedResponse::Delivered
+                }
+                PeerMethod::FetchFinalSum => ScriptedResponse::FinalSum(None),
+                PeerMethod::FetchOngoingProcessIds => ScriptedResponse::ProcessIds(vec![]),
+            });
+        self.answered_calls.lock().unwrap().push((method, peer_id));
+        response.into_result(peer_id)
+    }
+}
+
+#[async_trait::async_trait]
+impl PeerClient for ScriptedPeerClient {
+    async fn fetch_process_progress(
+        &self,
+        peer_id: u8,
+        _process_id: Uuid,
+        _query: AdditionProcessProgressQuery,
+    ) -> Result<AdditionProcessProgress, PeerClientError> {
+        match self.call(PeerMethod::FetchProcessProgress, peer_id).await? {
+            ScriptedResponse::Progress(progress) => Ok(progress),
+            _ => panic!("scripted response of peer {peer_id} is not a progress"),
+        }
+    }
+
+    async fn notify_process_progress(
+        &self,
+        peer_id: u8,
+        _process_id: Uuid,
+    ) -> Result<(), PeerClientError> {
+        match self
+            .call(PeerMethod::NotifyProcessProgress, peer_id)
+            .await?
+        {
+            ScriptedResponse::Delivered => Ok(()),
+            _ => panic!("scripted response of peer {peer_id} is not a delivery"),
+        }
+    }
+
+    async fn push_process_progress(
+        &self,
+        peer_id: u8,
+        _process_id: Uuid,
+        _payload: PeerMessagePayload,
+    ) -> Result<(), PeerClientError> {
+        match self.call(PeerMethod::PushProcessProgress, peer_id).await? {
+            ScriptedResponse::Delivered => Ok(()),
+            _ => panic!("scripted response of peer {peer_id} is not a delivery"),
+        }
+    }
+
+    async fn fetch_final_sum(
+        &self,
+        peer_id: u8,
+        _process_id: Uuid,
+    ) -> Result<ProcessFinalSum, PeerClientError> {
+        match self.call(PeerMethod::FetchFinalSum, peer_id).await? {
+            ScriptedResponse::FinalSum(final_sum) => Ok(ProcessFinalSum { final_sum }),
+            _ => panic!("scripted response of peer {peer_id} is not a final sum"),
+        }
+    }
+
+    async fn fetch_ongoing_process_ids(&self, peer_id: u8) -> Result<Vec<Uuid>, PeerClientError> {
+        match self
+            .call(PeerMethod::FetchOngoingProcessIds, peer_id)
+            .await?
+        {
+            ScriptedResponse::ProcessIds(process_ids) => Ok(process_ids),
+            _ => panic!("scripted response of peer {peer_id} is not a list of processes"),
+        }
+    }
+}
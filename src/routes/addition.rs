@@ -1,53 +1,237 @@
+use anyhow::anyhow;
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    response::IntoResponse,
     routing::{delete, get, post},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
 use tracing::info;
 use uuid::Uuid;
 
 use crate::{
-    Peer, domains,
-    peer_communication::{PeerMessage, peer_client::AdditionProcessProgress},
+    Peer,
+    domains::{
+        self,
+        additions::{completion::ProcessCompletion, transcript::TranscriptEntry},
+    },
+    peer_communication::{
+        PeerMessage, PeerMessagePayload, SentMessagesReport, paths,
+        peer_client::{
+            AdditionProcessProgress, AdditionProcessProgressBatchEntry,
+            AdditionProcessProgressBatchItem, AdditionProcessProgressQuery,
+            MAX_PROGRESS_BATCH_SIZE, OngoingProcesses, ProcessFinalSum,
+        },
+    },
 };
 
-use super::{ApiError, RouterState};
+use super::{Admin, ApiError, PeerJson, ProcessInitiator, RouterState, rate_limit};
 
-pub fn addition_router() -> Router<RouterState> {
+/// # Arguments
+/// * `inbound_concurrency` - The maximum number of requests of the peers handled concurrently, further requests are rejected with `503`.
+pub fn addition_router(inbound_concurrency: usize) -> Router<RouterState> {
+    let peer_router = Router::new()
+        .route(paths::PROCESS_PROGRESS, get(get_process_progress))
+        .route(paths::PROGRESS_BATCH, post(get_process_progress_batch))
+        .route(paths::PROCESS_RECEIVE, post(receive_pushed_progress))
+        .route(paths::PROCESS_FINAL_SUM, get(get_process_final_sum))
+        .route(paths::ONGOING_PROCESSES, get(get_ongoing_processes))
+        .route(
+            paths::PROGRESS_NOTIFICATION,
+            post(notify_internal_process_orchestrator),
+        );
     Router::new()
         .route("/", post(create_process))
+        .route("/await", post(create_and_await_process))
         .route("/{id}", delete(delete_process))
         .route("/{id}", get(get_process))
-        .route("/{id}/progress", get(get_process_progress))
-        .route(
-            "/progress-notification",
-            post(notify_internal_process_orchestrator),
-        )
+        .route("/by-key/{key}", get(get_process_by_external_key))
+        .route("/{id}/reconcile", get(reconcile_process))
+        .route("/{id}/peers", get(get_process_peers))
+        .route("/{id}/force-complete", post(force_complete_process))
+        .route("/{id}/resend-share/{peer_id}", post(resend_share))
+        .route("/{id}/transcript", get(get_process_transcript))
+        .merge(rate_limit::with_concurrency_limit(
+            peer_router,
+            inbound_concurrency,
+        ))
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct CreatedProcessResponse {
     pub process_id: Uuid,
     pub input: u64,
+    /// Decoded input of the server, if the input is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_input: Option<i64>,
+    /// Peers whose progress notification is being delivered, with retries
+    #[serde(default)]
+    pub notified_peers: Vec<u8>,
+    /// Peers which could not be notified, they only pick the process up when polling on their own
+    #[serde(default)]
+    pub pending_peers: Vec<u8>,
 }
 #[derive(Serialize, Deserialize)]
 pub struct CreateProcessHttpBody {
     pub process_id: Uuid,
+    /// Input of the server, a random input is generated if not provided unless explicit inputs are required
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<u16>,
+    /// Signed input of the server, between `-32768` and `32767`, exclusive with `input`. Every participant of the process must use a signed input for the sum to be decoded as a signed integer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_input: Option<i64>,
+    /// Key of the process in the client application, e.g. a job ID, the process can then be retrieved on `GET /additions/by-key/{key}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_key: Option<String>,
+    /// Priority of the process, `0` by default, higher priority processes are polled and their peer messages delivered first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
 }
 async fn create_process(
     State(state): State<RouterState>,
+    _initiator: ProcessInitiator,
     Json(payload): Json<CreateProcessHttpBody>,
 ) -> Result<(StatusCode, Json<CreatedProcessResponse>), ApiError> {
+    let (created_process, notifications_report) =
+        create_and_notify_process(&state, payload).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(CreatedProcessResponse {
+            process_id: created_process.id(),
+            input: created_process.input_shares().input,
+            signed_input: signed_input(created_process.input_shares()),
+            notified_peers: notifications_report.enqueued_peer_ids,
+            pending_peers: notifications_report.rejected_peer_ids,
+        }),
+    ))
+}
+
+/// Creates a process and waits for its completion, up to the configured timeout.
+async fn create_and_await_process(
+    State(state): State<RouterState>,
+    _initiator: ProcessInitiator,
+    Json(payload): Json<CreateProcessHttpBody>,
+) -> Result<(StatusCode, Json<GetProcessResponse>), ApiError> {
+    // Subscribing before the creation guarantees that the completion is not missed
+    let mut completions = state.completions.subscribe();
+    let (created_process, _) = create_and_notify_process(&state, payload).await?;
+    let process_id = created_process.id();
+
+    let wait_for_completion = async {
+        loop {
+            match completions.recv().await {
+                Ok(completion) if completion.process_id == process_id => {
+                    return Ok(completion.final_sum);
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    // Completions were missed, the process state tells whether it completed
+                    if let domains::additions::AdditionProcess::Completed(p) = state
+                        .addition
+                        .get_process(process_id)
+                        .await
+                        .map_err(|e| e.context("retrieving process after missed completions"))?
+                    {
+                        return Ok(p.final_sum);
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err(anyhow!("process completions channel closed"));
+                }
+            }
+        }
+    };
+    tokio::time::timeout(state.await_completion_timeout, wait_for_completion)
+        .await
+        .map_err(|_| {
+            ApiError::Timeout(format!(
+                "process {process_id} not completed after {:?}",
+                state.await_completion_timeout
+            ))
+        })?
+        .map_err(|e: anyhow::Error| e.context("awaiting process completion"))?;
+    let completed_process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving completed process"))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetProcessResponse::from(&completed_process)),
+    ))
+}
+
+/// Creates a process with the current peers as participants and notifies them.
+/// Failing to notify the peers does not fail the creation, the returned report tells which peers are notified.
+async fn create_and_notify_process(
+    state: &RouterState,
+    payload: CreateProcessHttpBody,
+) -> Result<(domains::additions::AdditionProcess, SentMessagesReport), ApiError> {
+    state.ensure_active()?;
+    let CreateProcessHttpBody {
+        process_id,
+        input,
+        signed_input,
+        external_key,
+        priority,
+    } = payload;
+    let input = match (input, signed_input) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::BadRequest(
+                "a process can not be created with both an input and a signed input".to_string(),
+            ));
+        }
+        (Some(input), None) => Some(domains::additions::ProcessInput::Unsigned(input)),
+        (None, Some(signed_input)) => {
+            let signed_input = i16::try_from(signed_input).map_err(|_| {
+                ApiError::BadRequest(format!(
+                    "signed input {signed_input} must be between {} and {}",
+                    i16::MIN,
+                    i16::MAX
+                ))
+            })?;
+            Some(domains::additions::ProcessInput::Signed(signed_input))
+        }
+        (None, None) => None,
+    };
+    if input.is_none() && state.require_explicit_input {
+        return Err(ApiError::BadRequest(
+            "an input is required to create a process".to_string(),
+        ));
+    }
+    if external_key.as_ref().is_some_and(|key| key.is_empty()) {
+        return Err(ApiError::BadRequest(
+            "the external key of a process can not be empty".to_string(),
+        ));
+    }
+    let peers = state.current_peers();
+    // Tampering detection needs two disjoint subsets of shares, it may not fit anymore once peers are removed
+    let verification_threshold = state
+        .verification_threshold
+        .filter(|threshold| 2 * threshold <= peers.len() + 1);
+    if verification_threshold.is_none() && state.verification_threshold.is_some() {
+        tracing::warn!(
+            "Not enough participants for tampering detection, process {process_id} is created without it"
+        );
+    }
     let create_process_request = domains::additions::CreateProcessRequest::new(
-        payload.process_id,
+        process_id,
         state.server_peer_id,
-        &state.peers.iter().map(|p| p.id).collect::<Vec<_>>(),
+        &peers.iter().map(|p| p.id).collect::<Vec<_>>(),
+        verification_threshold,
+        input,
+        &state.peer_points,
     )
     .map_err(|e| match e {
         domains::additions::CreateProcessRequestError::Unknown(err) => ApiError::from(err),
-    })?;
+    })?
+    .with_external_key(external_key)
+    .with_priority(priority.unwrap_or_default());
 
     let created_process = state
         .addition
@@ -57,33 +241,128 @@ async fn create_process(
 
     info!("addition process {} created", created_process.id());
 
-    if let Err(e) = state
+    if state.solo_mode {
+        let completed_process = complete_solo_process(state, &created_process).await?;
+        return Ok((completed_process, SentMessagesReport::default()));
+    }
+
+    // A misconfigured peer sharing the server's own peer ID is never messaged, it is reported as pending
+    let (own_peer_ids, peer_ids): (Vec<u8>, Vec<u8>) = peers
+        .iter()
+        .map(|p| p.id)
+        .partition(|peer_id| *peer_id == state.server_peer_id);
+    if !own_peer_ids.is_empty() {
+        tracing::warn!(
+            "peers are configured with the server's own peer ID {}, they are not notified of process {process_id}",
+            state.server_peer_id
+        );
+    }
+    let mut notifications_report = match state
         .peer_messages_sender
         .send_messages(
-            state
-                .peers
-                .iter()
-                .map(|p| PeerMessage::notify_process_progress(p.id))
+            PeerMessage::notify_process_progress_to_all(peer_ids.clone(), process_id)
+                .into_iter()
+                .map(|message| message.with_priority(created_process.priority()))
                 .collect(),
         )
         .await
     {
-        tracing::error!("error sending initial shares to peers: {}", e);
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!("error sending initial shares to peers: {}", e);
+            SentMessagesReport {
+                enqueued_peer_ids: vec![],
+                rejected_peer_ids: peer_ids,
+            }
+        }
+    };
+    notifications_report.rejected_peer_ids.extend(own_peer_ids);
+
+    // Shares are pushed as well, sparing a poll to the peers which already created the process
+    let mut share_pushes = created_process
+        .input_shares()
+        .shares_to_send
+        .iter()
+        .filter(|(peer_id, _)| **peer_id != state.server_peer_id)
+        .map(|(peer_id, share)| {
+            PeerMessage::push_process_progress(
+                *peer_id,
+                process_id,
+                PeerMessagePayload::Share { value: *share },
+            )
+            .with_priority(created_process.priority())
+        })
+        .collect::<Vec<_>>();
+    share_pushes.sort_by_key(PeerMessage::peer_id);
+    if let Err(e) = state.peer_messages_sender.send_messages(share_pushes).await {
+        tracing::error!("error pushing initial shares to peers: {}", e);
     }
 
-    Ok((
-        StatusCode::OK,
-        Json(CreatedProcessResponse {
-            process_id: created_process.id(),
-            input: created_process.input_shares().input,
-        }),
-    ))
+    Ok((created_process, notifications_report))
+}
+
+/// Completes a process created without peers, its shares sum and its final sum are then its own share, i.e. its input.
+async fn complete_solo_process(
+    state: &RouterState,
+    process: &domains::additions::AdditionProcess,
+) -> Result<domains::additions::AdditionProcess, ApiError> {
+    let _lock = state.addition.lock_process(process.id()).await;
+    let domains::additions::AdditionProcess::AwaitingPeerShares(p) = process else {
+        return Ok(process.clone());
+    };
+    let request =
+        domains::additions::ReceiveSharesRequest::new(p, HashMap::new()).map_err(|e| match e {
+            domains::additions::ReceiveSharesRequestError::UnexpectedPeer { .. } => {
+                ApiError::BadRequest(e.to_string())
+            }
+            domains::additions::ReceiveSharesRequestError::Unknown(err) => ApiError::from(err),
+        })?;
+    let domains::additions::AdditionProcess::AwaitingPeerSharesSum(p) = state
+        .addition
+        .receive_shares(request)
+        .await
+        .map_err(|e| e.context("computing shares sum of solo process"))?
+    else {
+        return Err(ApiError::from(anyhow!(
+            "solo process {} did not compute its shares sum",
+            p.id
+        )));
+    };
+    let request = domains::additions::ReceiveSharesSumsRequest::new(
+        &p,
+        HashMap::new(),
+        state.server_peer_id,
+        0,
+        &state.peer_points,
+        state.recovery,
+    )
+    .map_err(|e| anyhow!(e).context("creating receive shares sums request"))?;
+    let completed_process = state
+        .addition
+        .receive_shares_sums(request)
+        .await
+        .map_err(|e| e.context("completing solo process"))?;
+
+    if let domains::additions::AdditionProcess::Completed(p) = &completed_process {
+        state
+            .metrics
+            .process_completion_duration
+            .observe(p.completion_duration());
+        state.completions.publish(ProcessCompletion {
+            process_id: p.id,
+            final_sum: p.final_sum,
+        });
+        info!("solo addition process {} completed", p.id);
+    }
+
+    Ok(completed_process)
 }
 
 async fn delete_process(
     State(state): State<RouterState>,
     Path(process_id): Path<Uuid>,
 ) -> Result<StatusCode, ApiError> {
+    state.ensure_active()?;
     state
         .addition
         .delete_process(process_id)
@@ -95,11 +374,134 @@ async fn delete_process(
     Ok(StatusCode::OK)
 }
 
+/// Round of the protocol a process is at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessState {
+    AwaitingPeerShares,
+    AwaitingPeerSharesSum,
+    /// Final sum reconstructed, awaiting its confirmation by the peers
+    Reconstructed,
+    Completed,
+    Unrecoverable,
+    Tampered,
+}
+
+/// Number of contributions received from the peers of a process versus the number expected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceivedCount {
+    pub received: usize,
+    pub expected: usize,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetProcessResponse {
     pub process_id: Uuid,
     pub input: u64,
+    #[serde(default, with = "crate::value_encoding::optional_value")]
     pub sum: Option<u64>,
+    /// Final sum reconstructed by the server awaiting its confirmation by the peers, if the process is reconstructed
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::value_encoding::optional_value"
+    )]
+    pub candidate_sum: Option<u64>,
+    /// Decoded input of the server, if the input is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_input: Option<i64>,
+    /// Sum decoded as a signed integer, if the input is signed and the process is completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_sum: Option<i64>,
+    pub state: ProcessState,
+    /// Shares received from the peers, unknown once the process is unrecoverable
+    pub shares: ReceivedCount,
+    /// Shares sums received from the peers, unknown once the process is unrecoverable
+    pub shares_sums: ReceivedCount,
+    /// Reason why the process can not be completed, if it is unrecoverable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unrecoverable_reason: Option<String>,
+    /// Explanation of the stall of the process, e.g. a peer persistently unable to produce our share
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostic: Option<String>,
+    /// Whether the sum of the inputs may wrap around the prime, the sum is then only the integer sum of the inputs modulo the prime
+    #[serde(default)]
+    pub wrapped: bool,
+}
+
+impl From<&domains::additions::AdditionProcess> for GetProcessResponse {
+    fn from(process: &domains::additions::AdditionProcess) -> Self {
+        let sum = match process {
+            domains::additions::AdditionProcess::Completed(p) => Some(p.final_sum),
+            _ => None,
+        };
+        let candidate_sum = match process {
+            domains::additions::AdditionProcess::Reconstructed(p) => Some(p.candidate_sum),
+            _ => None,
+        };
+        let unrecoverable_reason = match process {
+            domains::additions::AdditionProcess::Unrecoverable(p) => Some(p.reason.clone()),
+            domains::additions::AdditionProcess::Tampered(p) => {
+                Some(format!("tampering detected: {}", p.reason))
+            }
+            _ => None,
+        };
+        let diagnostic = match process {
+            domains::additions::AdditionProcess::AwaitingPeerShares(p) => p.diagnostic.clone(),
+            _ => None,
+        };
+        let (state, received_shares_sums) = match process {
+            domains::additions::AdditionProcess::AwaitingPeerShares(_) => {
+                (ProcessState::AwaitingPeerShares, 0)
+            }
+            domains::additions::AdditionProcess::AwaitingPeerSharesSum(p) => (
+                ProcessState::AwaitingPeerSharesSum,
+                p.received_shares_sums.len(),
+            ),
+            domains::additions::AdditionProcess::Reconstructed(p) => {
+                (ProcessState::Reconstructed, p.received_shares_sums.len())
+            }
+            domains::additions::AdditionProcess::Completed(p) => {
+                (ProcessState::Completed, p.received_shares_sums.len())
+            }
+            domains::additions::AdditionProcess::Unrecoverable(_) => {
+                (ProcessState::Unrecoverable, 0)
+            }
+            domains::additions::AdditionProcess::Tampered(p) => {
+                (ProcessState::Tampered, p.received_shares_sums.len())
+            }
+        };
+        let peers_count = process.input_shares().shares_to_send.len();
+        GetProcessResponse {
+            process_id: process.id(),
+            input: process.input_shares().input,
+            sum,
+            candidate_sum,
+            signed_input: signed_input(process.input_shares()),
+            signed_sum: sum
+                .filter(|_| process.input_shares().signed)
+                .map(domains::additions::decode_signed),
+            state,
+            shares: ReceivedCount {
+                received: process.received_shares().map_or(0, HashMap::len),
+                expected: peers_count,
+            },
+            shares_sums: ReceivedCount {
+                received: received_shares_sums,
+                expected: peers_count,
+            },
+            unrecoverable_reason,
+            diagnostic,
+            wrapped: process.input_shares().sum_may_wrap(),
+        }
+    }
+}
+
+/// Decoded input of the server, if the input is signed.
+fn signed_input(input_shares: &domains::additions::InputShares) -> Option<i64> {
+    input_shares
+        .signed
+        .then(|| domains::additions::decode_signed(input_shares.input))
 }
 
 async fn get_process(
@@ -111,48 +513,525 @@ async fn get_process(
         .get_process(process_id)
         .await
         .map_err(|e| e.context("retrieving process"))?;
-    let sum = match &process {
-        domains::additions::AdditionProcess::Completed(p) => Some(p.final_sum),
-        _ => None,
-    };
-    Ok((
-        StatusCode::OK,
-        Json(GetProcessResponse {
-            process_id,
-            input: process.input_shares().input,
-            sum,
-        }),
-    ))
+    Ok((StatusCode::OK, Json(GetProcessResponse::from(&process))))
+}
+
+/// Retrieves a process by the key given by the client application at its creation.
+async fn get_process_by_external_key(
+    State(state): State<RouterState>,
+    Path(external_key): Path<String>,
+) -> Result<(StatusCode, Json<GetProcessResponse>), ApiError> {
+    let process = state
+        .addition
+        .get_process_by_external_key(&external_key)
+        .await
+        .map_err(|e| e.context("retrieving process by external key"))?
+        .ok_or(ApiError::NotFound)?;
+    Ok((StatusCode::OK, Json(GetProcessResponse::from(&process))))
 }
 
 async fn get_process_progress(
     State(state): State<RouterState>,
     peer: Peer,
     Path(process_id): Path<Uuid>,
+    Query(query): Query<AdditionProcessProgressQuery>,
 ) -> Result<Json<AdditionProcessProgress>, ApiError> {
+    let progress = process_progress_for_peer(&state, &peer, process_id, query).await?;
+
+    Ok(Json(progress))
+}
+
+/// Returns the progress of several processes for the requesting peer.
+///
+/// Each entry carries the status the single process request would have responded with, a failing process does not fail the batch.
+async fn get_process_progress_batch(
+    State(state): State<RouterState>,
+    peer: Peer,
+    PeerJson(items): PeerJson<Vec<AdditionProcessProgressBatchItem>>,
+) -> Result<Json<Vec<AdditionProcessProgressBatchEntry>>, ApiError> {
+    if items.len() > MAX_PROGRESS_BATCH_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "at most {MAX_PROGRESS_BATCH_SIZE} processes can be fetched in a batch"
+        )));
+    }
+
+    let mut entries = Vec::with_capacity(items.len());
+    for item in items {
+        let entry =
+            match process_progress_for_peer(&state, &peer, item.process_id, item.query).await {
+                Ok(progress) => AdditionProcessProgressBatchEntry {
+                    process_id: item.process_id,
+                    status: StatusCode::OK.as_u16(),
+                    progress: Some(progress),
+                },
+                Err(e) => AdditionProcessProgressBatchEntry {
+                    process_id: item.process_id,
+                    status: e.into_response().status().as_u16(),
+                    progress: None,
+                },
+            };
+        entries.push(entry);
+    }
+
+    Ok(Json(entries))
+}
+
+async fn process_progress_for_peer(
+    state: &RouterState,
+    peer: &Peer,
+    process_id: Uuid,
+    query: AdditionProcessProgressQuery,
+) -> Result<AdditionProcessProgress, ApiError> {
+    throttle_peer(state, peer, process_id)?;
     let process = state
         .addition
         .get_process(process_id)
         .await
         .map_err(|e| e.context("retrieving process before getting progress"))?;
 
+    if let domains::additions::AdditionProcess::Unrecoverable(p) = &process {
+        return Err(ApiError::Conflict(format!(
+            "process is unrecoverable: {}",
+            p.reason
+        )));
+    }
+    // A peer sending a share different from the one we received has lost and re-derived its process state
+    if let (Some(sent_share), Some(received_share)) = (
+        query.sent_share,
+        process
+            .received_shares()
+            .and_then(|shares| shares.get(&peer.id)),
+    ) && domains::additions::reduce_share(sent_share) != *received_share
+    {
+        let reason = format!("share of peer {} changed since it was received", peer.id);
+        if !matches!(process, domains::additions::AdditionProcess::Completed(_)) {
+            tracing::error!("Process {} is unrecoverable: {}", process_id, reason);
+            state
+                .addition
+                .mark_unrecoverable(process_id, reason.clone())
+                .await
+                .map_err(|e| e.context("marking process as unrecoverable"))?;
+        }
+        return Err(ApiError::Conflict(reason));
+    }
+
     let peer_share = process
         .input_shares()
         .shares_to_send
         .get(&peer.id)
         .ok_or_else(|| ApiError::BadRequest("no share found for this peer".to_string()))?;
+    // A peer presenting its shares sum has received every share, it has reached the shares sum round
+    if query.shares_sum.is_some() {
+        state.shares_sum_round_peers.record(process_id, peer.id);
+    }
+    // The shares sum is only disclosed to peers seen in the shares sum round, a lagging peer would otherwise learn it early
+    let in_shares_sum_round = state.shares_sum_round_peers.contains(process_id, peer.id)
+        || process
+            .received_shares_sums()
+            .is_some_and(|shares_sums| shares_sums.contains_key(&peer.id));
     let shares_sum = match &process {
+        _ if !in_shares_sum_round => None,
         domains::additions::AdditionProcess::AwaitingPeerSharesSum(p) => Some(p.shares_sum),
+        // Peers keep receiving the shares sum until they reconstruct the candidate sum to confirm
+        domains::additions::AdditionProcess::Reconstructed(p) => Some(p.shares_sum),
         domains::additions::AdditionProcess::Completed(p) => Some(p.shares_sum),
+        // Peers keep receiving the shares sum so that they detect the tampering as well
+        domains::additions::AdditionProcess::Tampered(p) => Some(p.shares_sum),
         _ => None,
     };
 
-    Ok(Json(AdditionProcessProgress {
+    Ok(AdditionProcessProgress {
         share: *peer_share,
         shares_sum,
+        completed: matches!(process, domains::additions::AdditionProcess::Completed(_)),
+    })
+}
+
+/// Rejects the request if the peer exceeded its rate limit for the process, so that a flooding peer does not hold the lock of the process.
+fn throttle_peer(state: &RouterState, peer: &Peer, process_id: Uuid) -> Result<(), ApiError> {
+    if state
+        .peer_process_rate_limiter
+        .try_acquire(process_id, peer.id)
+    {
+        Ok(())
+    } else {
+        Err(ApiError::TooManyRequests(format!(
+            "peer {} exceeded its rate limit for process {process_id}",
+            peer.id
+        )))
+    }
+}
+
+/// Applies the progress pushed by a peer without waiting for the next poll.
+///
+/// Responds `200` if the progress is applied and `202` if it does not apply to the current state of the process, it is then picked up by the regular polls.
+/// Pushed progress is not applied while the orchestrator is paused, nor when pushed by a peer simulated as silent.
+async fn receive_pushed_progress(
+    State(state): State<RouterState>,
+    peer: Peer,
+    Path(process_id): Path<Uuid>,
+    PeerJson(payload): PeerJson<PeerMessagePayload>,
+) -> Result<StatusCode, ApiError> {
+    throttle_peer(&state, &peer, process_id)?;
+    if state.orchestrator_switch.is_paused() {
+        return Ok(StatusCode::ACCEPTED);
+    }
+    // Silent peers are simulated as never responding, their pushes are left unapplied
+    if state.silent_peer_ids.contains(&peer.id) {
+        return Ok(StatusCode::ACCEPTED);
+    }
+    let _lock = state.addition.lock_process(process_id).await;
+    let process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving process before receiving pushed progress"))?;
+    if !process.input_shares().shares_to_send.contains_key(&peer.id) {
+        return Err(ApiError::BadRequest(format!(
+            "peer {} does not participate in the process",
+            peer.id
+        )));
+    }
+
+    if matches!(payload, PeerMessagePayload::SharesSum { .. }) {
+        state.shares_sum_round_peers.record(process_id, peer.id);
+    }
+    let applied = match (payload, &process) {
+        (
+            PeerMessagePayload::Share { value },
+            domains::additions::AdditionProcess::AwaitingPeerShares(p),
+        ) => receive_pushed_share(&state, p, peer.id, value).await?,
+        (
+            PeerMessagePayload::SharesSum { value },
+            domains::additions::AdditionProcess::AwaitingPeerSharesSum(p),
+        ) => receive_pushed_shares_sum(&state, p, peer.id, value).await?,
+        _ => false,
+    };
+
+    Ok(if applied {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
+    })
+}
+
+/// Registers a share pushed by a peer, the shares sum is pushed in turn once every share is received.
+/// It is only pushed to the peers seen in the shares sum round, the others poll it once they reach that round.
+/// Must be called with the lock of the process held.
+async fn receive_pushed_share(
+    state: &RouterState,
+    process: &domains::additions::AwaitingPeerSharesProcess,
+    peer_id: u8,
+    share: u64,
+) -> Result<bool, ApiError> {
+    if let Some(received_share) = process.received_shares.get(&peer_id) {
+        // Shares are stored reduced, an unreduced redelivery of the same share is not a change
+        if *received_share == domains::additions::reduce_share(share) {
+            return Ok(false);
+        }
+        let reason = format!("share of peer {peer_id} changed since it was received");
+        tracing::error!("Process {} is unrecoverable: {}", process.id, reason);
+        state
+            .addition
+            .mark_unrecoverable(process.id, reason.clone())
+            .await
+            .map_err(|e| e.context("marking process as unrecoverable"))?;
+        return Err(ApiError::Conflict(reason));
+    }
+
+    let request =
+        domains::additions::ReceiveSharesRequest::new(process, HashMap::from([(peer_id, share)]))
+            .map_err(|e| match e {
+            domains::additions::ReceiveSharesRequestError::UnexpectedPeer { .. } => {
+                ApiError::BadRequest(e.to_string())
+            }
+            domains::additions::ReceiveSharesRequestError::Unknown(err) => ApiError::from(err),
+        })?;
+    let updated_process = state
+        .addition
+        .receive_shares(request)
+        .await
+        .map_err(|e| e.context("updating process with pushed share"))?;
+
+    if let domains::additions::AdditionProcess::AwaitingPeerSharesSum(p) = &updated_process {
+        let mut peer_ids = p.input_shares.peer_ids().into_iter().collect::<Vec<u8>>();
+        peer_ids.sort_unstable();
+        let shares_sum_pushes = peer_ids
+            .into_iter()
+            .filter(|peer_id| {
+                *peer_id != state.server_peer_id
+                    && state.shares_sum_round_peers.contains(p.id, *peer_id)
+            })
+            .map(|peer_id| {
+                PeerMessage::push_process_progress(
+                    peer_id,
+                    p.id,
+                    PeerMessagePayload::SharesSum {
+                        value: p.shares_sum,
+                    },
+                )
+                .with_priority(p.priority)
+            })
+            .collect();
+        if let Err(e) = state
+            .peer_messages_sender
+            .send_messages(shares_sum_pushes)
+            .await
+        {
+            tracing::error!("error pushing shares sum to peers: {}", e);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Registers a shares sum pushed by a peer, the process is completed once every shares sum is received.
+/// Must be called with the lock of the process held.
+async fn receive_pushed_shares_sum(
+    state: &RouterState,
+    process: &domains::additions::AwaitingPeerSharesSumProcess,
+    peer_id: u8,
+    shares_sum: u64,
+) -> Result<bool, ApiError> {
+    if process.received_shares_sums.contains_key(&peer_id) {
+        return Ok(false);
+    }
+
+    let request = match domains::additions::ReceiveSharesSumsRequest::new_excluding(
+        process,
+        HashMap::from([(peer_id, shares_sum)]),
+        state.server_peer_id,
+        process.input_shares.shares_to_send.len(),
+        &state.peer_points,
+        &state.excluded_shares_sum_peer_ids,
+        state.recovery,
+    ) {
+        Ok(request) => request.with_confirmation(state.confirm_quorum.is_some()),
+        Err(domains::additions::ReceiveSharesSumsRequestError::Tampered {
+            received_shares_sums,
+            reason,
+        }) => {
+            tracing::error!("Process {} has been tampered with: {}", process.id, reason);
+            state
+                .addition
+                .mark_tampered(process.id, received_shares_sums, reason)
+                .await
+                .map_err(|e| e.context("marking process as tampered"))?;
+            return Ok(true);
+        }
+        Err(domains::additions::ReceiveSharesSumsRequestError::Unknown(err)) => {
+            return Err(ApiError::from(
+                err.context("creating receive shares sums request"),
+            ));
+        }
+    };
+    let updated_process = state
+        .addition
+        .receive_shares_sums(request)
+        .await
+        .map_err(|e| e.context("updating process with pushed shares sum"))?;
+
+    if let domains::additions::AdditionProcess::Completed(p) = &updated_process {
+        state
+            .metrics
+            .process_completion_duration
+            .observe(p.completion_duration());
+        state.completions.publish(ProcessCompletion {
+            process_id: p.id,
+            final_sum: p.final_sum,
+        });
+        info!(
+            "addition process {} completed from pushed shares sums",
+            p.id
+        );
+    }
+
+    Ok(true)
+}
+
+async fn get_process_final_sum(
+    State(state): State<RouterState>,
+    _peer: Peer,
+    Path(process_id): Path<Uuid>,
+) -> Result<Json<ProcessFinalSum>, ApiError> {
+    let process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving process before getting final sum"))?;
+    // The candidate sum is reported so that peers requiring a confirmation confirm each other
+    let final_sum = match process {
+        domains::additions::AdditionProcess::Reconstructed(p) => Some(p.candidate_sum),
+        domains::additions::AdditionProcess::Completed(p) => Some(p.final_sum),
+        _ => None,
+    };
+    Ok(Json(ProcessFinalSum { final_sum }))
+}
+
+/// Lists the ongoing processes the requesting peer participates in, a restarted peer then rejoins the processes it missed.
+async fn get_ongoing_processes(
+    State(state): State<RouterState>,
+    peer: Peer,
+) -> Result<Json<OngoingProcesses>, ApiError> {
+    let processes = state
+        .addition
+        .get_ongoing_processes()
+        .await
+        .map_err(|e| e.context("retrieving ongoing processes"))?;
+    let mut process_ids = processes
+        .iter()
+        .filter(|p| p.input_shares().shares_to_send.contains_key(&peer.id))
+        .map(|p| p.id())
+        .collect::<Vec<Uuid>>();
+    process_ids.sort();
+    Ok(Json(OngoingProcesses { process_ids }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReconcileProcessResponse {
+    pub process_id: Uuid,
+    /// Final sum reconstructed by the server, `None` if the process is not completed
+    #[serde(default, with = "crate::value_encoding::optional_value")]
+    pub final_sum: Option<u64>,
+    /// Final sums reported by the participants of the process, ordered by peer ID
+    pub peers: Vec<PeerFinalSum>,
+    /// Participants which reported a final sum different from the server's one
+    pub mismatching_peers: Vec<u8>,
+    /// Whether the server and every participant completed the process with the same final sum
+    pub all_agree: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PeerFinalSum {
+    pub peer_id: u8,
+    /// Final sum reported by the peer, `None` if the peer did not complete the process or could not be reached
+    #[serde(default, with = "crate::value_encoding::optional_value")]
+    pub final_sum: Option<u64>,
+    /// Error encountered while fetching the final sum of the peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Cross-checks the final sum of a process with the final sums reconstructed by its participants.
+async fn reconcile_process(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(process_id): Path<Uuid>,
+) -> Result<Json<ReconcileProcessResponse>, ApiError> {
+    let process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving process before reconciliation"))?;
+    let final_sum = match &process {
+        domains::additions::AdditionProcess::Reconstructed(p) => Some(p.candidate_sum),
+        domains::additions::AdditionProcess::Completed(p) => Some(p.final_sum),
+        _ => None,
+    };
+
+    let mut peer_ids = process
+        .input_shares()
+        .peer_ids()
+        .into_iter()
+        .collect::<Vec<u8>>();
+    peer_ids.sort_unstable();
+    let peers = futures::future::join_all(peer_ids.into_iter().map(|peer_id| {
+        let peer_client = state.peer_client.clone();
+        async move {
+            match peer_client.fetch_final_sum(peer_id, process_id).await {
+                Ok(reported) => PeerFinalSum {
+                    peer_id,
+                    final_sum: reported.final_sum,
+                    error: None,
+                },
+                Err(e) => PeerFinalSum {
+                    peer_id,
+                    final_sum: None,
+                    error: Some(e.to_string()),
+                },
+            }
+        }
+    }))
+    .await;
+
+    let mismatching_peers = peers
+        .iter()
+        .filter(|peer| {
+            matches!((peer.final_sum, final_sum), (Some(reported), Some(own)) if reported != own)
+        })
+        .map(|peer| peer.peer_id)
+        .collect::<Vec<u8>>();
+    let all_agree = final_sum.is_some() && peers.iter().all(|peer| peer.final_sum == final_sum);
+    if !mismatching_peers.is_empty() {
+        tracing::error!(
+            "Final sum of process {process_id} disagrees with peers {:?}",
+            mismatching_peers
+        );
+    }
+
+    Ok(Json(ReconcileProcessResponse {
+        process_id,
+        final_sum,
+        peers,
+        mismatching_peers,
+        all_agree,
     }))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeerContribution {
+    pub peer_id: u8,
+    /// Whether the share of the peer has been received
+    pub share_received: bool,
+    /// Whether the shares sum of the peer has been received
+    pub sum_share_received: bool,
+}
+
+/// Reports the contributions received from each participant of a process, e.g. to find the peer a stalled process is waiting for.
+async fn get_process_peers(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(process_id): Path<Uuid>,
+) -> Result<Json<Vec<PeerContribution>>, ApiError> {
+    let process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving process before listing its peers"))?;
+    let received_shares = process.received_shares().ok_or_else(|| {
+        ApiError::Conflict(format!(
+            "process {process_id} is unrecoverable, its received contributions are not kept"
+        ))
+    })?;
+    let received_shares_sums = match &process {
+        domains::additions::AdditionProcess::AwaitingPeerSharesSum(p) => {
+            Some(&p.received_shares_sums)
+        }
+        domains::additions::AdditionProcess::Reconstructed(p) => Some(&p.received_shares_sums),
+        domains::additions::AdditionProcess::Completed(p) => Some(&p.received_shares_sums),
+        domains::additions::AdditionProcess::Tampered(p) => Some(&p.received_shares_sums),
+        _ => None,
+    };
+
+    let mut peer_ids = process
+        .input_shares()
+        .peer_ids()
+        .into_iter()
+        .collect::<Vec<u8>>();
+    peer_ids.sort_unstable();
+    Ok(Json(
+        peer_ids
+            .into_iter()
+            .map(|peer_id| PeerContribution {
+                peer_id,
+                share_received: received_shares.contains_key(&peer_id),
+                sum_share_received: received_shares_sums
+                    .is_some_and(|sums| sums.contains_key(&peer_id)),
+            })
+            .collect(),
+    ))
+}
+
 async fn notify_internal_process_orchestrator(
     State(state): State<RouterState>,
     _peer: Peer,
@@ -161,3 +1040,160 @@ async fn notify_internal_process_orchestrator(
 
     Ok(StatusCode::OK)
 }
+
+async fn force_complete_process(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(process_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<GetProcessResponse>), ApiError> {
+    let _lock = state.addition.lock_process(process_id).await;
+    let process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving process before force completion"))?;
+    let awaiting_process = match &process {
+        domains::additions::AdditionProcess::AwaitingPeerSharesSum(p) => p,
+        _ => {
+            return Err(ApiError::BadRequest(
+                "process is not awaiting shares sums".to_string(),
+            ));
+        }
+    };
+
+    // Without a verification threshold, shares are generated with a polynomial of degree equal to the number of peers,
+    // reconstruction then needs the shares sums of every participant
+    let threshold = awaiting_process
+        .input_shares
+        .verification_threshold
+        .unwrap_or(awaiting_process.input_shares.shares_to_send.len() + 1);
+    let request = match domains::additions::ReceiveSharesSumsRequest::force_complete(
+        awaiting_process,
+        state.server_peer_id,
+        threshold,
+        &state.peer_points,
+        state.recovery,
+    ) {
+        Ok(request) => request,
+        Err(e @ domains::additions::ForceCompleteRequestError::InsufficientSharesSums { .. }) => {
+            return Err(ApiError::BadRequest(e.to_string()));
+        }
+        Err(domains::additions::ForceCompleteRequestError::Tampered {
+            received_shares_sums,
+            reason,
+        }) => {
+            tracing::error!("Process {} has been tampered with: {}", process_id, reason);
+            state
+                .addition
+                .mark_tampered(process_id, received_shares_sums, reason.clone())
+                .await
+                .map_err(|e| e.context("marking process as tampered"))?;
+            return Err(ApiError::Conflict(reason));
+        }
+        Err(domains::additions::ForceCompleteRequestError::Unknown(err)) => {
+            return Err(ApiError::from(err));
+        }
+    };
+    let completed_process = state
+        .addition
+        .receive_shares_sums(request)
+        .await
+        .map_err(|e| e.context("force completing addition process"))?;
+    if let domains::additions::AdditionProcess::Completed(p) = &completed_process {
+        state
+            .metrics
+            .process_completion_duration
+            .observe(p.completion_duration());
+        state.completions.publish(ProcessCompletion {
+            process_id,
+            final_sum: p.final_sum,
+        });
+    }
+
+    info!("addition process {process_id} force completed");
+
+    Ok((
+        StatusCode::OK,
+        Json(GetProcessResponse::from(&completed_process)),
+    ))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ResendShareResponse {
+    /// Peers whose share push has been enqueued, only the targeted peer
+    pub enqueued_peers: Vec<u8>,
+}
+/// Re-enqueues the push of the share of a single peer, e.g. a peer reporting it never received it.
+///
+/// The stored share is sent again, the input is not re-split so that the peer receives the share the other participants are consistent with.
+async fn resend_share(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path((process_id, peer_id)): Path<(Uuid, u8)>,
+) -> Result<(StatusCode, Json<ResendShareResponse>), ApiError> {
+    let process = state
+        .addition
+        .get_process(process_id)
+        .await
+        .map_err(|e| e.context("retrieving process before resending a share"))?;
+    if !matches!(
+        process,
+        domains::additions::AdditionProcess::AwaitingPeerShares(_)
+            | domains::additions::AdditionProcess::AwaitingPeerSharesSum(_)
+    ) {
+        return Err(ApiError::BadRequest(
+            "shares are only resent for ongoing processes".to_string(),
+        ));
+    }
+    let share = *process
+        .input_shares()
+        .shares_to_send
+        .get(&peer_id)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "peer {peer_id} is not a participant of process {process_id}"
+            ))
+        })?;
+
+    let report = state
+        .peer_messages_sender
+        .send_messages(vec![
+            PeerMessage::push_process_progress(
+                peer_id,
+                process_id,
+                PeerMessagePayload::Share { value: share },
+            )
+            .with_priority(process.priority()),
+        ])
+        .await
+        .map_err(|e| anyhow!(e).context("enqueuing share push"))?;
+
+    info!("share of peer {peer_id} for addition process {process_id} resent");
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ResendShareResponse {
+            enqueued_peers: report.enqueued_peer_ids,
+        }),
+    ))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProcessTranscriptResponse {
+    pub process_id: Uuid,
+    /// Protocol steps executed by the server, in the order they happened
+    pub events: Vec<TranscriptEntry>,
+}
+/// Returns the transcript of a process, only available when the transcripts are recorded, `404` otherwise.
+async fn get_process_transcript(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(process_id): Path<Uuid>,
+) -> Result<Json<ProcessTranscriptResponse>, ApiError> {
+    let events = state
+        .transcripts
+        .as_ref()
+        .and_then(|transcripts| transcripts.transcript(process_id))
+        .ok_or(ApiError::NotFound)?;
+    Ok(Json(ProcessTranscriptResponse { process_id, events }))
+}
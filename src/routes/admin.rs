@@ -0,0 +1,310 @@
+use anyhow::anyhow;
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+};
+use futures::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    domains::additions::{
+        AdditionProcess, orchestrator::OrchestratorCommand, repository::RepositoryError,
+    },
+    metrics::{OrchestratorMetricsSnapshot, ProcessFailureAttempts},
+    value_encoding,
+};
+
+use super::{Admin, ApiError, RouterState, addition::GetProcessResponse};
+
+pub fn admin_router() -> Router<RouterState> {
+    Router::new()
+        .route("/export", get(export_processes))
+        .route("/import", post(import_processes))
+        .route("/processes/stream", get(stream_processes))
+        .route("/stats", get(get_statistics))
+        .route("/orchestrator", get(get_orchestrator_activity))
+        .route("/orchestrator/failures", get(get_orchestrator_failures))
+        .route("/orchestrator/pause", post(pause_orchestrator))
+        .route("/orchestrator/resume", post(resume_orchestrator))
+        .route("/outbox/flush", post(flush_outbox))
+        .route("/promote", post(promote))
+        .route("/peers/{peer_id}", delete(remove_peer))
+        .route("/peers/blocked", get(get_blocked_peers))
+        .route(
+            "/peers/{peer_id}/block",
+            post(block_peer).delete(unblock_peer),
+        )
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProcessesExport {
+    pub processes: Vec<AdditionProcess>,
+}
+
+async fn export_processes(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Result<Json<ProcessesExport>, ApiError> {
+    let processes = state
+        .addition
+        .export_all()
+        .await
+        .map_err(|e| e.context("exporting addition processes"))?;
+
+    info!("{} addition processes exported", processes.len());
+
+    Ok(Json(ProcessesExport { processes }))
+}
+
+/// Streams the summary of every process as newline-delimited JSON.
+///
+/// Processes are read one by one from a snapshot of their IDs so that the whole set is not
+/// held in memory. Processes deleted in the meantime are skipped.
+async fn stream_processes(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Result<Response, ApiError> {
+    let process_ids = state
+        .addition
+        .list_process_ids()
+        .await
+        .map_err(|e| e.context("listing addition processes"))?;
+
+    info!("streaming {} addition processes", process_ids.len());
+
+    let repository = state.addition.clone();
+    let values_as_strings = state.values_as_strings;
+    let lines = stream::iter(process_ids)
+        .then(move |process_id| {
+            let repository = repository.clone();
+            async move { repository.get_process(process_id).await }
+        })
+        .filter_map(move |result| async move {
+            match result {
+                // Lines are serialized while the body is streamed, outside of the scope of the request
+                Ok(process) => Some(
+                    value_encoding::sync_scope(values_as_strings, || {
+                        serde_json::to_string(&GetProcessResponse::from(&process))
+                    })
+                    .map(|line| format!("{line}\n"))
+                    .map_err(|e| RepositoryError::Internal(e.into())),
+                ),
+                Err(RepositoryError::NotFound(_)) => None,
+                Err(e) => Some(Err(e)),
+            }
+        });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatisticsResponse {
+    /// Number of processes in each state
+    pub awaiting_peer_shares: usize,
+    pub awaiting_peer_shares_sum: usize,
+    pub reconstructed: usize,
+    pub completed: usize,
+    pub unrecoverable: usize,
+    pub tampered: usize,
+    /// Average duration between the creation and the completion of the completed processes, in seconds
+    pub average_completion_seconds: Option<f64>,
+    /// Number of peer messages in the outbox, awaiting their delivery
+    pub outbox_depth: usize,
+}
+
+/// Aggregates the processes and the outbox, e.g. for a dashboard.
+async fn get_statistics(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Result<Json<StatisticsResponse>, ApiError> {
+    let statistics = state
+        .addition
+        .statistics()
+        .await
+        .map_err(|e| e.context("aggregating addition processes"))?;
+    let outbox_depth = state
+        .peer_messages_sender
+        .pending_messages()
+        .map_err(|e| anyhow!(e).context("counting pending peer messages"))?;
+
+    Ok(Json(StatisticsResponse {
+        awaiting_peer_shares: statistics.awaiting_peer_shares,
+        awaiting_peer_shares_sum: statistics.awaiting_peer_shares_sum,
+        reconstructed: statistics.reconstructed,
+        completed: statistics.completed,
+        unrecoverable: statistics.unrecoverable,
+        tampered: statistics.tampered,
+        average_completion_seconds: statistics
+            .average_completion_duration
+            .map(|duration| duration.as_secs_f64()),
+        outbox_depth,
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ImportProcessesResponse {
+    pub imported: usize,
+}
+
+async fn import_processes(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Json(payload): Json<ProcessesExport>,
+) -> Result<(StatusCode, Json<ImportProcessesResponse>), ApiError> {
+    state.ensure_active()?;
+    let imported = state
+        .addition
+        .import_all(payload.processes)
+        .await
+        .map_err(|e| e.context("importing addition processes"))?;
+
+    info!("{imported} addition processes imported");
+
+    // Ongoing imported processes are resumed by the orchestrator
+    state.addition_process_notifier.ping();
+
+    Ok((StatusCode::OK, Json(ImportProcessesResponse { imported })))
+}
+
+async fn get_orchestrator_activity(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Json<OrchestratorMetricsSnapshot> {
+    Json(state.metrics.orchestrator.snapshot())
+}
+
+/// Returns the consecutive failed polls of each process, as of the last completed orchestrator iteration.
+async fn get_orchestrator_failures(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Json<Vec<ProcessFailureAttempts>> {
+    Json(state.metrics.orchestrator.failures_attempts())
+}
+
+/// Pauses the orchestrator, processes are then no longer polled nor advanced by pushed progress.
+async fn pause_orchestrator(State(state): State<RouterState>, _admin: Admin) -> StatusCode {
+    state.orchestrator_switch.pause();
+    state.metrics.orchestrator.record_paused(true);
+    state
+        .addition_process_notifier
+        .send_command(OrchestratorCommand::Pause)
+        .await;
+
+    info!("orchestrator paused");
+
+    StatusCode::NO_CONTENT
+}
+
+async fn resume_orchestrator(State(state): State<RouterState>, _admin: Admin) -> StatusCode {
+    state.orchestrator_switch.resume();
+    state.metrics.orchestrator.record_paused(false);
+
+    info!("orchestrator resumed");
+
+    // Ongoing processes are polled at once
+    state
+        .addition_process_notifier
+        .send_command(OrchestratorCommand::Resume)
+        .await;
+
+    StatusCode::NO_CONTENT
+}
+
+/// Delivers the pending peer messages at once, e.g. once an unreachable peer is fixed, instead of on the next scheduled dispatch.
+async fn flush_outbox(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Result<StatusCode, ApiError> {
+    state
+        .peer_messages_sender
+        .flush()
+        .map_err(|e| anyhow!(e).context("flushing pending peer messages"))?;
+
+    info!("outbox flush requested");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Promotes a standby to active, its processes are no longer mirrored from the primary and the orchestrator resumes them.
+async fn promote(State(state): State<RouterState>, admin: Admin) -> Result<StatusCode, ApiError> {
+    if !state.standby.promote() {
+        return Err(ApiError::Conflict("node is not a standby".to_string()));
+    }
+
+    info!("standby promoted to active");
+
+    Ok(resume_orchestrator(State(state), admin).await)
+}
+
+/// Removes a peer, e.g. a permanently decommissioned node.
+///
+/// New processes are created without the peer, ongoing processes keep their original participants.
+async fn remove_peer(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(peer_id): Path<u8>,
+) -> Result<StatusCode, ApiError> {
+    let mut peers = state.peers.write().unwrap_or_else(|e| e.into_inner());
+    let position = peers
+        .iter()
+        .position(|peer| peer.id == peer_id)
+        .ok_or(ApiError::NotFound)?;
+    if peers.len() == 1 {
+        return Err(ApiError::BadRequest(
+            "at least one peer must remain".to_string(),
+        ));
+    }
+    peers.remove(position);
+
+    info!("peer {peer_id} removed, {} peers remaining", peers.len());
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BlockedPeersResponse {
+    pub peer_ids: Vec<u8>,
+}
+
+async fn get_blocked_peers(
+    State(state): State<RouterState>,
+    _admin: Admin,
+) -> Json<BlockedPeersResponse> {
+    Json(BlockedPeersResponse {
+        peer_ids: state.blocked_peers.peer_ids(),
+    })
+}
+
+/// Blocks a peer, e.g. a compromised peer: its requests are rejected and it is no longer polled.
+///
+/// The peer stays a participant of its processes, they stall until it is unblocked.
+async fn block_peer(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(peer_id): Path<u8>,
+) -> StatusCode {
+    if state.blocked_peers.block(peer_id) {
+        tracing::warn!("peer {peer_id} blocked");
+    }
+    StatusCode::NO_CONTENT
+}
+
+async fn unblock_peer(
+    State(state): State<RouterState>,
+    _admin: Admin,
+    Path(peer_id): Path<u8>,
+) -> StatusCode {
+    if state.blocked_peers.unblock(peer_id) {
+        info!("peer {peer_id} unblocked");
+    }
+    StatusCode::NO_CONTENT
+}
@@ -1,58 +1,293 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
 
+use anyhow::anyhow;
 use axum::{
     Json, Router,
-    extract::FromRequestParts,
-    http::StatusCode,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::get,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tower_http::{compression::CompressionLayer, decompression::RequestDecompressionLayer};
 use tracing::{error, warn};
 
 use crate::{
     Config, Peer,
-    domains::additions::{notifier::Notifier, repository::AdditionProcessRepository},
-    peer_communication,
+    domains::additions::{
+        PeerPoints, RecoverySettings,
+        completion::ProcessCompletions,
+        notifier::Notifier,
+        orchestrator::{BlockedPeers, OrchestratorSwitch},
+        repository::{AdditionProcessRepository, RepositoryError},
+        standby::StandbyRole,
+        transcript::ProcessTranscripts,
+    },
+    metrics::{Metrics, PeerDecodeFailures},
+    peer_communication::{self, paths, peer_client::PeerClient},
+    value_encoding,
 };
 
 pub mod addition;
+pub mod admin;
+pub mod rate_limit;
+mod shares_sum_round;
 
 #[derive(Clone)]
 pub struct RouterState {
     addition: Arc<dyn AdditionProcessRepository>,
     peer_messages_sender: Arc<dyn peer_communication::PeerMessagesSender>,
+    peer_client: Arc<dyn PeerClient>,
     addition_process_notifier: Arc<dyn Notifier>,
-    peers: Vec<Peer>,
+    /// Current peers, new processes are created with them as participants
+    peers: Arc<RwLock<Vec<Peer>>>,
     server_peer_id: u8,
+    admin_token: Option<String>,
+    metrics: Arc<Metrics>,
+    completions: Arc<ProcessCompletions>,
+    orchestrator_switch: Arc<OrchestratorSwitch>,
+    await_completion_timeout: std::time::Duration,
+    verification_threshold: Option<usize>,
+    /// Peers whose shares sums are not used to reconstruct the final sum
+    excluded_shares_sum_peer_ids: Arc<HashSet<u8>>,
+    /// Number of peers which must report the reconstructed final sum before a process is completed
+    confirm_quorum: Option<usize>,
+    require_explicit_input: bool,
+    silent_peer_ids: Vec<u8>,
+    peer_process_rate_limiter: Arc<rate_limit::ProcessRateLimiter>,
+    /// Peers seen in the shares sum round of each process, the only ones the shares sum is disclosed to
+    shares_sum_round_peers: Arc<shares_sum_round::SharesSumRoundPeers>,
+    peer_points: Arc<PeerPoints>,
+    heartbeat_timeout: std::time::Duration,
+    solo_mode: bool,
+    blocked_peers: Arc<BlockedPeers>,
+    coordinator_peer_id: Option<u8>,
+    strict_peer_payloads: bool,
+    /// Transcripts of the processes, `None` if they are not recorded
+    transcripts: Option<Arc<ProcessTranscripts>>,
+    /// Role of the node, the processes of a standby are mirrored from its primary
+    standby: Arc<StandbyRole>,
+    /// Whether the values of the responses are serialized as decimal strings
+    values_as_strings: bool,
+    /// Settings of the recovery of the final sums
+    recovery: RecoverySettings,
+}
+
+impl RouterState {
+    /// Snapshot of the current peers.
+    fn current_peers(&self) -> Vec<Peer> {
+        self.peers.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Rejects the changes of the processes of a standby, they are mirrored from its primary.
+    fn ensure_active(&self) -> Result<(), ApiError> {
+        if self.standby.is_standby() {
+            return Err(ApiError::ServiceUnavailable(
+                "node is a standby, processes are mirrored from the primary until it is promoted"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn app_router(
     config: &Config,
     addition_repository: Arc<dyn AdditionProcessRepository>,
     peer_messages_sender: Arc<dyn peer_communication::PeerMessagesSender>,
+    peer_client: Arc<dyn PeerClient>,
     addition_process_notifier: Arc<dyn Notifier>,
+    metrics: Arc<Metrics>,
+    completions: Arc<ProcessCompletions>,
+    orchestrator_switch: Arc<OrchestratorSwitch>,
+    blocked_peers: Arc<BlockedPeers>,
+    transcripts: Option<Arc<ProcessTranscripts>>,
+    standby: Arc<StandbyRole>,
 ) -> Router {
     let state = RouterState {
         addition: addition_repository,
         peer_messages_sender,
+        peer_client,
         addition_process_notifier,
-        peers: config.peers.clone(),
+        peers: Arc::new(RwLock::new(config.peers.clone())),
         server_peer_id: config.server_peer_id,
+        admin_token: config.admin_token.clone(),
+        metrics,
+        completions,
+        orchestrator_switch,
+        await_completion_timeout: config.await_completion_timeout,
+        verification_threshold: config.verification_threshold,
+        confirm_quorum: config.confirm_quorum,
+        excluded_shares_sum_peer_ids: Arc::new(
+            config
+                .excluded_shares_sum_peer_ids
+                .iter()
+                .cloned()
+                .collect(),
+        ),
+        require_explicit_input: config.require_explicit_input,
+        silent_peer_ids: config.silent_peer_ids.clone(),
+        peer_process_rate_limiter: Arc::new(rate_limit::ProcessRateLimiter::new(
+            config.peer_process_rate_limit,
+        )),
+        shares_sum_round_peers: Arc::new(shares_sum_round::SharesSumRoundPeers::new()),
+        peer_points: Arc::new(config.peer_points.clone()),
+        heartbeat_timeout: config.heartbeat_timeout,
+        solo_mode: config.solo_mode,
+        blocked_peers,
+        coordinator_peer_id: config.coordinator_peer_id,
+        strict_peer_payloads: config.strict_peer_payloads,
+        transcripts,
+        standby,
+        values_as_strings: config.values_as_strings,
+        recovery: config.recovery_settings(),
     };
-    Router::new()
+    let router = Router::new()
         .route("/health", get(get_healthcheck))
-        .nest("/additions", addition::addition_router())
+        .route("/health/peers", get(get_peers_health))
+        .route("/metrics", get(get_metrics))
+        .route("/peers", get(get_peers))
+        .nest(
+            paths::ADDITIONS,
+            addition::addition_router(config.inbound_concurrency),
+        )
+        .nest("/admin", admin::admin_router())
         .fallback(not_found_handler)
-        .with_state(state)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            scope_value_encoding,
+        ))
+        .with_state(state);
+    if config.peer_compression {
+        // Both layers follow the `Accept-Encoding` and `Content-Encoding` headers, uncompressed peers are still served
+        router.layer((
+            RequestDecompressionLayer::new().gzip(true),
+            CompressionLayer::new().gzip(true),
+        ))
+    } else {
+        router
+    }
+}
+
+/// Serializes the values of the response as configured, the response is serialized within the scope of the request.
+async fn scope_value_encoding(
+    State(state): State<RouterState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    value_encoding::scope(state.values_as_strings, next.run(request)).await
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct GetHealthcheckResponse {
     pub ok: bool,
+    /// Background tasks without heartbeat for longer than the heartbeat timeout, most likely dead
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_tasks: Vec<String>,
+    /// Background tasks alive but failing persistently, e.g. unable to read the repository
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failing_tasks: Vec<String>,
 }
-async fn get_healthcheck() -> (StatusCode, Json<GetHealthcheckResponse>) {
-    (StatusCode::OK, Json(GetHealthcheckResponse { ok: true }))
+/// Reports the node as unhealthy with `503` if a background task stopped beating or fails persistently, the server would otherwise keep serving requests while processes no longer advance.
+async fn get_healthcheck(
+    State(state): State<RouterState>,
+) -> (StatusCode, Json<GetHealthcheckResponse>) {
+    let stale_tasks = state
+        .metrics
+        .heartbeats
+        .stale_tasks(state.heartbeat_timeout);
+    let failing_tasks = state.metrics.heartbeats.failing_tasks();
+    if !stale_tasks.is_empty() || !failing_tasks.is_empty() {
+        tracing::error!(
+            "Background tasks {:?} have not beaten for more than {:?}, background tasks {:?} are failing",
+            stale_tasks,
+            state.heartbeat_timeout,
+            failing_tasks
+        );
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(GetHealthcheckResponse {
+                ok: false,
+                stale_tasks: stale_tasks.into_iter().map(str::to_string).collect(),
+                failing_tasks: failing_tasks.into_iter().map(str::to_string).collect(),
+            }),
+        );
+    }
+    (
+        StatusCode::OK,
+        Json(GetHealthcheckResponse {
+            ok: true,
+            stale_tasks: vec![],
+            failing_tasks: vec![],
+        }),
+    )
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PeerHealthResponse {
+    pub peer_id: u8,
+    /// Undecodable responses of the peer, most likely caused by incompatible versions
+    pub decode_failures: Option<PeerDecodeFailures>,
+}
+#[derive(Serialize, Deserialize)]
+pub struct GetPeersHealthResponse {
+    pub peers: Vec<PeerHealthResponse>,
+}
+/// Reports the peers whose responses could not be decoded, such failures are not retried away.
+async fn get_peers_health(State(state): State<RouterState>) -> Json<GetPeersHealthResponse> {
+    let mut decode_failures = state.metrics.peers.decode_failures();
+    Json(GetPeersHealthResponse {
+        peers: state
+            .current_peers()
+            .iter()
+            .map(|peer| PeerHealthResponse {
+                peer_id: peer.id,
+                decode_failures: decode_failures.remove(&peer.id),
+            })
+            .collect(),
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PeerResponse {
+    pub id: u8,
+    pub url: String,
+}
+#[derive(Serialize, Deserialize)]
+pub struct GetPeersResponse {
+    pub server_peer_id: u8,
+    pub peers: Vec<PeerResponse>,
+}
+async fn get_peers(State(state): State<RouterState>, _admin: Admin) -> Json<GetPeersResponse> {
+    Json(GetPeersResponse {
+        server_peer_id: state.server_peer_id,
+        peers: state
+            .current_peers()
+            .iter()
+            .map(|peer| PeerResponse {
+                id: peer.id,
+                url: peer.url.clone(),
+            })
+            .collect(),
+    })
+}
+
+async fn get_metrics(State(state): State<RouterState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        {
+            let mut output = state.metrics.render();
+            state.completions.render_metrics(&mut output);
+            output
+        },
+    )
 }
 
 async fn not_found_handler() -> impl IntoResponse {
@@ -69,6 +304,10 @@ pub enum ApiError {
     InternalServerError(anyhow::Error),
     BadRequest(String),
     Unauthorized(String),
+    Conflict(String),
+    Timeout(String),
+    TooManyRequests(String),
+    ServiceUnavailable(String),
 }
 
 impl From<anyhow::Error> for ApiError {
@@ -77,6 +316,18 @@ impl From<anyhow::Error> for ApiError {
     }
 }
 
+impl From<RepositoryError> for ApiError {
+    fn from(err: RepositoryError) -> Self {
+        match err {
+            RepositoryError::NotFound(_) => ApiError::NotFound,
+            RepositoryError::InvalidState(_)
+            | RepositoryError::AlreadyExists(_)
+            | RepositoryError::AllSharesReceived(_) => ApiError::Conflict(err.to_string()),
+            RepositoryError::Internal(e) => ApiError::InternalServerError(e),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         match self {
@@ -86,6 +337,13 @@ impl IntoResponse for ApiError {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
             }
             Self::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg).into_response(),
+            Self::Conflict(msg) => (StatusCode::CONFLICT, msg).into_response(),
+            Self::Timeout(msg) => (StatusCode::REQUEST_TIMEOUT, msg).into_response(),
+            Self::TooManyRequests(msg) => {
+                warn!("Rate limited request: {}", msg);
+                (StatusCode::TOO_MANY_REQUESTS, msg).into_response()
+            }
+            Self::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg).into_response(),
             Self::Unauthorized(msg) => {
                 warn!("Unauthorized access attempt: {}", msg);
                 StatusCode::UNAUTHORIZED.into_response()
@@ -113,15 +371,149 @@ impl FromRequestParts<RouterState> for Peer {
             .map_err(|e| ApiError::Unauthorized(format!("Invalid X-PEER-ID header: {e}")))?
             .parse::<u8>()
             .map_err(|e| ApiError::Unauthorized(format!("Invalid X-PEER-ID header: {e}")))?;
-        let related_peer =
-            state
-                .peers
-                .iter()
-                .find(|peer| peer.id == peer_id)
-                .ok_or(ApiError::Unauthorized(format!(
-                    "Unauthorized peer: {}",
-                    peer_id
-                )))?;
-        Ok(related_peer.clone())
+        let peer = state
+            .current_peers()
+            .into_iter()
+            .find(|peer| peer.id == peer_id)
+            .ok_or(ApiError::Unauthorized(format!(
+                "Unauthorized peer: {}",
+                peer_id
+            )))?;
+        if state.blocked_peers.is_blocked(peer_id) {
+            return Err(ApiError::Unauthorized(format!("Blocked peer: {peer_id}")));
+        }
+        // Logs of the request then tell which peer caused them, the request span must declare the field
+        tracing::Span::current().record("peer_id", peer.id);
+        Ok(peer)
+    }
+}
+
+/// JSON payload sent by a peer, an invalid payload is rejected with a `400` describing the problem.
+///
+/// Fields unknown to the server are ignored, unless strict peer payloads are enabled, the payload is then rejected.
+/// Unknown fields are found by comparing the payload with the serialization of its decoded value.
+pub struct PeerJson<T>(pub T);
+
+impl<T: DeserializeOwned + Serialize> FromRequest<RouterState> for PeerJson<T> {
+    type Rejection = ApiError;
+
+    async fn from_request(
+        request: axum::extract::Request,
+        state: &RouterState,
+    ) -> Result<Self, Self::Rejection> {
+        let body = Bytes::from_request(request, state).await.map_err(|e| {
+            ApiError::BadRequest(format!("invalid peer payload: {}", e.body_text()))
+        })?;
+        let payload = serde_json::from_slice::<T>(&body)
+            .map_err(|e| ApiError::BadRequest(format!("invalid peer payload: {e}")))?;
+        if state.strict_peer_payloads {
+            let raw_payload = serde_json::from_slice::<serde_json::Value>(&body)
+                .map_err(|e| ApiError::BadRequest(format!("invalid peer payload: {e}")))?;
+            let known_payload = serde_json::to_value(&payload)
+                .map_err(|e| anyhow!("{e}").context("serializing peer payload"))?;
+            if let Some(path) = find_unknown_field(&raw_payload, &known_payload, "") {
+                return Err(ApiError::BadRequest(format!(
+                    "invalid peer payload: unknown field `{path}`"
+                )));
+            }
+        }
+        Ok(PeerJson(payload))
+    }
+}
+
+/// Path of the first field of the payload missing from its known form, `null` fields are skipped as they may be omitted on serialization.
+fn find_unknown_field(
+    payload: &serde_json::Value,
+    known_payload: &serde_json::Value,
+    path: &str,
+) -> Option<String> {
+    match (payload, known_payload) {
+        (serde_json::Value::Object(fields), serde_json::Value::Object(known_fields)) => {
+            fields.iter().find_map(|(name, value)| {
+                let field_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                match known_fields.get(name) {
+                    Some(known_value) => find_unknown_field(value, known_value, &field_path),
+                    None if value.is_null() => None,
+                    None => Some(field_path),
+                }
+            })
+        }
+        (serde_json::Value::Array(items), serde_json::Value::Array(known_items)) => items
+            .iter()
+            .zip(known_items)
+            .enumerate()
+            .find_map(|(i, (item, known_item))| {
+                find_unknown_field(item, known_item, &format!("{path}[{i}]"))
+            }),
+        _ => None,
+    }
+}
+
+// #######################################################
+// ################## ADMIN RESTRICTION ##################
+// #######################################################
+
+/// Marker for requests authenticated with the admin token in the `X-ADMIN-TOKEN` header.
+pub struct Admin;
+
+impl FromRequestParts<RouterState> for Admin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &RouterState,
+    ) -> Result<Self, Self::Rejection> {
+        let admin_token = state
+            .admin_token
+            .as_ref()
+            .ok_or_else(|| ApiError::Unauthorized("Admin endpoints are disabled".to_string()))?;
+        let provided_token = parts
+            .headers
+            .get("X-ADMIN-TOKEN")
+            .ok_or_else(|| ApiError::Unauthorized("Missing X-ADMIN-TOKEN header".to_string()))?
+            .to_str()
+            .map_err(|e| ApiError::Unauthorized(format!("Invalid X-ADMIN-TOKEN header: {e}")))?;
+        if provided_token != admin_token {
+            return Err(ApiError::Unauthorized("Invalid admin token".to_string()));
+        }
+        Ok(Admin)
+    }
+}
+
+// ####################################################################
+// ################## PROCESS CREATION RESTRICTION ####################
+// ####################################################################
+
+/// Marker for requests allowed to create a process.
+///
+/// Any request is allowed unless a coordinator is configured, only the coordinator peer and admins are then allowed.
+pub struct ProcessInitiator;
+
+impl FromRequestParts<RouterState> for ProcessInitiator {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &RouterState,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(coordinator_peer_id) = state.coordinator_peer_id else {
+            return Ok(ProcessInitiator);
+        };
+        if parts.headers.contains_key("X-ADMIN-TOKEN") {
+            Admin::from_request_parts(parts, state).await?;
+            return Ok(ProcessInitiator);
+        }
+        let peer = Peer::from_request_parts(parts, state).await?;
+        if peer.id != coordinator_peer_id {
+            return Err(ApiError::Unauthorized(format!(
+                "Peer {} is not the coordinator, only peer {coordinator_peer_id} can create processes",
+                peer.id
+            )));
+        }
+        Ok(ProcessInitiator)
     }
 }
@@ -0,0 +1,134 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::{BoxError, Router, error_handling::HandleErrorLayer, http::StatusCode};
+use tower::{ServiceBuilder, limit::GlobalConcurrencyLimitLayer};
+use uuid::Uuid;
+
+/// Number of tracked windows above which expired windows are evicted.
+const EVICTION_THRESHOLD: usize = 1024;
+
+/// Fixed window rate limiter of the requests of a peer about a process.
+///
+/// Each (process, peer) pair is limited independently, a peer flooding a process does not throttle other peers or processes.
+pub struct ProcessRateLimiter {
+    max_requests_per_second: u32,
+    windows: Mutex<HashMap<(Uuid, u8), Window>>,
+}
+
+struct Window {
+    started_at: Instant,
+    requests: u32,
+}
+
+impl ProcessRateLimiter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn new(max_requests_per_second: u32) -> Self {
+        Self {
+            max_requests_per_second,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records a request of the peer about the process, returns `false` if the peer exceeded its limit for the current window.
+    pub fn try_acquire(&self, process_id: Uuid, peer_id: u8) -> bool {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= EVICTION_THRESHOLD {
+            windows.retain(|_, window| now.duration_since(window.started_at) < Self::WINDOW);
+        }
+        let window = windows.entry((process_id, peer_id)).or_insert(Window {
+            started_at: now,
+            requests: 0,
+        });
+        if now.duration_since(window.started_at) >= Self::WINDOW {
+            window.started_at = now;
+            window.requests = 0;
+        }
+        if window.requests >= self.max_requests_per_second {
+            return false;
+        }
+        window.requests += 1;
+        true
+    }
+}
+
+/// Limits the number of requests of the router handled concurrently, across all of its routes.
+///
+/// Requests above the limit are rejected right away with `503` instead of piling up on the locks of the repository.
+pub fn with_concurrency_limit<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    limit: usize,
+) -> Router<S> {
+    router.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(|_: BoxError| async {
+                StatusCode::SERVICE_UNAVAILABLE
+            }))
+            .load_shed()
+            .layer(GlobalConcurrencyLimitLayer::new(limit)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_limits_each_process_and_peer_independently() {
+        let limiter = ProcessRateLimiter::new(2);
+        let process_id = Uuid::new_v4();
+        assert!(limiter.try_acquire(process_id, 2));
+        assert!(limiter.try_acquire(process_id, 2));
+        assert!(!limiter.try_acquire(process_id, 2));
+
+        assert!(limiter.try_acquire(process_id, 3));
+        assert!(limiter.try_acquire(Uuid::new_v4(), 2));
+    }
+
+    #[tokio::test]
+    async fn test_requests_above_the_concurrency_limit_are_shed() {
+        let router = with_concurrency_limit(
+            Router::new()
+                .route(
+                    "/slow",
+                    get(|| async { tokio::time::sleep(Duration::from_millis(300)).await }),
+                )
+                .route("/fast", get(|| async {})),
+            2,
+        );
+        let request = |path: &str| {
+            router
+                .clone()
+                .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        };
+
+        let slow_requests = [
+            tokio::spawn(request("/slow")),
+            tokio::spawn(request("/slow")),
+        ];
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // The limit is shared by every route of the router
+        assert_eq!(
+            request("/fast").await.unwrap().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            request("/slow").await.unwrap().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        for slow_request in slow_requests {
+            assert_eq!(
+                slow_request.await.unwrap().unwrap().status(),
+                StatusCode::OK
+            );
+        }
+        assert_eq!(request("/fast").await.unwrap().status(), StatusCode::OK);
+    }
+}
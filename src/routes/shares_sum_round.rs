@@ -0,0 +1,79 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+/// Number of tracked processes above which the processes not seen for the retention are evicted.
+const EVICTION_THRESHOLD: usize = 1024;
+
+/// Peers seen in the shares sum round of each process, i.e. which presented or pushed their shares sum.
+///
+/// The round of a peer is only learnt from the shares sum it sent, never from the round it claims, the shares sum of the server is only disclosed to the peers recorded here.
+/// Evicting a process only delays its shares sum, its peers are recorded again on their next poll.
+pub struct SharesSumRoundPeers {
+    processes: Mutex<HashMap<Uuid, SeenPeers>>,
+}
+
+struct SeenPeers {
+    last_seen_at: Instant,
+    peer_ids: HashSet<u8>,
+}
+
+impl SharesSumRoundPeers {
+    const RETENTION: Duration = Duration::from_secs(600);
+
+    pub fn new() -> Self {
+        Self {
+            processes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the peer in the shares sum round of the process.
+    pub fn record(&self, process_id: Uuid, peer_id: u8) {
+        let now = Instant::now();
+        let mut processes = self.processes.lock().unwrap_or_else(|e| e.into_inner());
+        if processes.len() >= EVICTION_THRESHOLD {
+            processes.retain(|_, seen| now.duration_since(seen.last_seen_at) < Self::RETENTION);
+        }
+        let seen = processes.entry(process_id).or_insert(SeenPeers {
+            last_seen_at: now,
+            peer_ids: HashSet::new(),
+        });
+        seen.last_seen_at = now;
+        seen.peer_ids.insert(peer_id);
+    }
+
+    /// Whether the peer was seen in the shares sum round of the process.
+    pub fn contains(&self, process_id: Uuid, peer_id: u8) -> bool {
+        self.processes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&process_id)
+            .is_some_and(|seen| seen.peer_ids.contains(&peer_id))
+    }
+}
+
+impl Default for SharesSumRoundPeers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peers_are_recorded_per_process() {
+        let round_peers = SharesSumRoundPeers::new();
+        let process_id = Uuid::new_v4();
+        round_peers.record(process_id, 2);
+
+        assert!(round_peers.contains(process_id, 2));
+        assert!(!round_peers.contains(process_id, 3));
+        assert!(!round_peers.contains(Uuid::new_v4(), 2));
+    }
+}
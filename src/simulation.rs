@@ -0,0 +1,230 @@
+//! Nodes simulated in a single process, communicating in memory.
+//!
+//! A full addition can then be run with a single binary, e.g. for a local demo, see `SIMULATE_PEERS`.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, header},
+};
+use uuid::Uuid;
+
+use crate::{
+    Config, Peer,
+    domains::additions::{
+        completion::ProcessCompletions,
+        orchestrator::{BlockedPeers, OrchestratorSwitch, setup_addition_process_orchestrator},
+        repository::InMemoryAdditionProcessRepository,
+        retention::CompletedProcessesSweeper,
+        standby::StandbyRole,
+        transcript,
+    },
+    metrics::Metrics,
+    peer_communication::{
+        in_memory_peer_client::{InMemoryPeerBroker, InMemoryPeerClient},
+        setup_peer_communication_with_client,
+    },
+    routes::{
+        addition::{CreateProcessHttpBody, GetProcessResponse},
+        app_router,
+    },
+};
+
+/// Nodes running in the current process, identified from 1 to their number.
+pub struct SimulatedNetwork {
+    broker: Arc<InMemoryPeerBroker>,
+    peer_ids: Vec<u8>,
+}
+
+impl SimulatedNetwork {
+    /// Starts the nodes and their background tasks, requests between nodes go through an [`InMemoryPeerBroker`].
+    /// # Arguments
+    /// * `config` - The base configuration of the nodes, the peer ID and the peers of each node are derived from the number of nodes,
+    /// * `nodes_count` - The number of nodes.
+    pub fn start(config: &Config, nodes_count: u8) -> Self {
+        let broker = Arc::new(InMemoryPeerBroker::new());
+        let peer_ids = (1..=nodes_count).collect::<Vec<_>>();
+        for peer_id in &peer_ids {
+            let node_config = Config {
+                server_peer_id: *peer_id,
+                peers: peer_ids
+                    .iter()
+                    .filter(|id| *id != peer_id)
+                    .map(|id| Peer::new(*id, format!("memory://{id}")))
+                    .collect(),
+                simulate_peers: None,
+                ..config.clone()
+            };
+            let router = start_node(&node_config, broker.clone());
+            broker.register(*peer_id, router);
+        }
+        Self { broker, peer_ids }
+    }
+
+    pub fn peer_ids(&self) -> &[u8] {
+        &self.peer_ids
+    }
+
+    /// Creates a process on every node, each node draws a random input.
+    pub async fn create_process(&self, process_id: Uuid) -> Result<(), anyhow::Error> {
+        let body = serde_json::to_vec(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })?;
+        for peer_id in &self.peer_ids {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/additions")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.clone()))?;
+            let response = self.broker.handle(*peer_id, request).await?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "node {peer_id} failed to create process {process_id}: HTTP {}",
+                    response.status()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits until the process is completed on every node.
+    /// # Arguments
+    /// * `process_id` - The ID of the process,
+    /// * `timeout` - The maximum duration to wait for.
+    /// # Returns
+    /// * The completed process of each node, in the order of the peer IDs.
+    pub async fn wait_for_completion(
+        &self,
+        process_id: Uuid,
+        timeout: Duration,
+    ) -> Result<Vec<GetProcessResponse>, anyhow::Error> {
+        tokio::time::timeout(timeout, async {
+            let mut completed_processes = Vec::with_capacity(self.peer_ids.len());
+            for peer_id in &self.peer_ids {
+                loop {
+                    let process = self.get_process(*peer_id, process_id).await?;
+                    if process.sum.is_some() {
+                        completed_processes.push(process);
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+            Ok(completed_processes)
+        })
+        .await
+        .map_err(|_| anyhow!("process {process_id} did not complete within {timeout:?}"))?
+    }
+
+    async fn get_process(
+        &self,
+        peer_id: u8,
+        process_id: Uuid,
+    ) -> Result<GetProcessResponse, anyhow::Error> {
+        let request = Request::builder()
+            .uri(format!("/additions/{process_id}"))
+            .body(Body::empty())?;
+        let response = self.broker.handle(peer_id, request).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "node {peer_id} failed to get process {process_id}: HTTP {}",
+                response.status()
+            ));
+        }
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+/// Starts the background tasks of a node and returns its router.
+fn start_node(config: &Config, broker: Arc<InMemoryPeerBroker>) -> Router {
+    let (addition_process_repository, transcripts) = transcript::record_transcripts(
+        Arc::new(InMemoryAdditionProcessRepository::new()),
+        config.record_transcripts,
+    );
+    let metrics = Arc::new(Metrics::new());
+    let completions = Arc::new(ProcessCompletions::new(config.completion_event_buffer));
+    let orchestrator_switch = Arc::new(OrchestratorSwitch::default());
+    let blocked_peers = Arc::new(BlockedPeers::new(&config.blocked_peer_ids));
+
+    let (peer_client, peer_messages_sender, peer_messages_relayer, peer_messages_relayer_pinger) =
+        setup_peer_communication_with_client(
+            config,
+            Arc::new(InMemoryPeerClient::new(config.server_peer_id, broker)),
+        );
+    let mut peer_messages_relayer =
+        peer_messages_relayer.with_heartbeats(metrics.heartbeats.clone());
+    tokio::spawn(async move {
+        peer_messages_relayer.run().await;
+    });
+    let relayer_interval = config.relayer_interval;
+    tokio::spawn(async move {
+        if let Err(e) = peer_messages_relayer_pinger.run(relayer_interval).await {
+            tracing::error!(
+                "Peer messages relayer interval pinger encountered an error: {}",
+                e
+            );
+        }
+    });
+
+    let (addition_process_orchestrator, addition_process_notifier) =
+        setup_addition_process_orchestrator(
+            addition_process_repository.clone(),
+            peer_client.clone(),
+            config.server_peer_id,
+            metrics.clone(),
+            completions.clone(),
+            orchestrator_switch.clone(),
+            config.orchestrator_max_failures,
+        );
+    let mut addition_process_orchestrator = addition_process_orchestrator
+        .with_silent_peers(&config.silent_peer_ids)
+        .with_blocked_peers(blocked_peers.clone())
+        .with_peer_points(config.peer_points.clone())
+        .with_excluded_shares_sum_peers(&config.excluded_shares_sum_peer_ids)
+        .with_confirm_quorum(config.confirm_quorum)
+        .with_recovery(config.recovery_settings());
+    tokio::spawn(async move {
+        addition_process_orchestrator.run().await;
+    });
+    let addition_process_notifier = Arc::new(addition_process_notifier);
+    if let Some(retention) = config.completed_retention {
+        let sweeper =
+            CompletedProcessesSweeper::new(addition_process_repository.clone(), retention);
+        tokio::spawn(async move {
+            sweeper
+                .run(retention.clamp(Duration::from_secs(1), Duration::from_secs(60)))
+                .await;
+        });
+    }
+    tokio::spawn({
+        let addition_process_notifier = addition_process_notifier.clone();
+        let orchestrator_interval = config.orchestrator_interval;
+        async move {
+            addition_process_notifier
+                .run_interval_ping(orchestrator_interval)
+                .await;
+        }
+    });
+
+    app_router(
+        config,
+        addition_process_repository,
+        Arc::new(peer_messages_sender),
+        peer_client,
+        addition_process_notifier,
+        metrics,
+        completions,
+        orchestrator_switch,
+        blocked_peers,
+        transcripts,
+        Arc::new(StandbyRole::default()),
+    )
+}
@@ -0,0 +1,156 @@
+//! Serialization of the share and sum values exchanged with the peers and returned to the clients.
+//!
+//! Values are serialized as JSON numbers, or as decimal strings within a [`scope`] or [`sync_scope`] enabling it so that values above
+//! `2^53` survive clients parsing JSON numbers as doubles. Both forms are accepted when deserializing, nodes with different settings
+//! therefore understand each other.
+//!
+//! Serde does not carry a context down to the serialization of a field, the setting is therefore carried by a task-local value
+//! set around the serialization, e.g. by the router for its responses and by the peer client for its requests.
+
+use std::fmt;
+
+use serde::{
+    Deserialize, Deserializer, Serializer,
+    de::{self, Visitor},
+};
+
+tokio::task_local! {
+    static VALUES_AS_STRINGS: bool;
+}
+
+/// Runs the given future serializing values as decimal strings instead of JSON numbers if enabled.
+pub async fn scope<F: Future>(values_as_strings: bool, future: F) -> F::Output {
+    VALUES_AS_STRINGS.scope(values_as_strings, future).await
+}
+
+/// Runs the given function serializing values as decimal strings instead of JSON numbers if enabled.
+pub fn sync_scope<R>(values_as_strings: bool, f: impl FnOnce() -> R) -> R {
+    VALUES_AS_STRINGS.sync_scope(values_as_strings, f)
+}
+
+/// Whether values are serialized as decimal strings in the current scope, values are serialized as JSON numbers outside of any scope.
+pub fn values_as_strings() -> bool {
+    VALUES_AS_STRINGS
+        .try_with(|enabled| *enabled)
+        .unwrap_or(false)
+}
+
+/// Serialization of a value, to be used with `#[serde(with = "crate::value_encoding::value")]`.
+pub mod value {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        if values_as_strings() {
+            serializer.collect_str(value)
+        } else {
+            serializer.serialize_u64(*value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Serialization of an optional value, to be used with `#[serde(with = "crate::value_encoding::optional_value")]`.
+pub mod optional_value {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::value::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Ok(Option::<Value>::deserialize(deserializer)?.map(|value| value.0))
+    }
+
+    #[derive(Deserialize)]
+    struct Value(#[serde(with = "super::value")] u64);
+}
+
+/// Accepts a value as a JSON number or as a decimal string.
+struct ValueVisitor;
+
+impl Visitor<'_> for ValueVisitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an unsigned integer or a decimal string")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+        Ok(value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+        u64::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+        value
+            .parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+    use crate::peer_communication::PeerMessagePayload;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Values {
+        #[serde(with = "value")]
+        value: u64,
+        #[serde(with = "optional_value")]
+        optional_value: Option<u64>,
+    }
+
+    #[test]
+    fn test_values_above_2_pow_53_round_trip_as_strings() {
+        let values = Values {
+            value: (1 << 53) + 1,
+            optional_value: Some(u64::MAX),
+        };
+
+        let serialized = sync_scope(true, || serde_json::to_string(&values)).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"value":"9007199254740993","optional_value":"18446744073709551615"}"#
+        );
+        assert_eq!(serde_json::from_str::<Values>(&serialized).unwrap(), values);
+        assert_eq!(
+            sync_scope(false, || serde_json::to_string(&values)).unwrap(),
+            r#"{"value":9007199254740993,"optional_value":18446744073709551615}"#
+        );
+
+        // Numbers are still accepted, e.g. from a peer not serializing values as strings
+        let values = serde_json::from_str::<Values>(r#"{"value":12,"optional_value":null}"#);
+        assert_eq!(
+            values.unwrap(),
+            Values {
+                value: 12,
+                optional_value: None
+            }
+        );
+        assert!(serde_json::from_str::<Values>(r#"{"value":"-1","optional_value":null}"#).is_err());
+
+        // Values of internally tagged payloads are buffered before being deserialized
+        assert_eq!(
+            serde_json::from_str::<PeerMessagePayload>(
+                r#"{"type":"share","value":"9007199254740993"}"#
+            )
+            .unwrap(),
+            PeerMessagePayload::Share {
+                value: (1 << 53) + 1
+            }
+        );
+    }
+}
@@ -1,10 +1,21 @@
 mod common;
 
+use std::collections::HashMap;
+
 use common::setup_instance;
 use futures::{StreamExt, stream};
 use mpc_exploration::{
     Config, Peer,
-    routes::addition::{CreateProcessHttpBody, GetProcessResponse},
+    domains::additions::{AdditionProcess, PeerPoints, transcript::TranscriptEvent},
+    mpc::{RecoveryStrategy, Share, recover_secret},
+    peer_communication::{PeerMessagePayload, peer_client::AdditionProcessProgress},
+    routes::{
+        addition::{
+            CreateProcessHttpBody, CreatedProcessResponse, GetProcessResponse, PeerContribution,
+            ProcessState, ProcessTranscriptResponse, ReceivedCount, ReconcileProcessResponse,
+        },
+        admin::ProcessesExport,
+    },
 };
 use tracing::Level;
 
@@ -19,7 +30,13 @@ async fn test_addition_single_process() {
     for instance in &instances {
         let create_addition_process_response = client
             .post(format!("{}/additions", &instance.server_url))
-            .json(&CreateProcessHttpBody { process_id })
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
             .send()
             .await
             .unwrap();
@@ -29,6 +46,1182 @@ async fn test_addition_single_process() {
     assert_completed_addition_process(&client, &instances, process_id).await;
 }
 
+#[tokio::test]
+async fn test_addition_of_signed_inputs() {
+    let instances = setup_instances(&[50036, 50037, 50038]).await;
+
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    let signed_inputs = [1_200, -3_400, 56];
+    for (instance, signed_input) in instances.iter().zip(signed_inputs) {
+        let created_process = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: Some(signed_input),
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap()
+            .json::<CreatedProcessResponse>()
+            .await
+            .unwrap();
+        assert_eq!(created_process.signed_input, Some(signed_input));
+    }
+
+    for instance in &instances {
+        wait_for_completed_addition_process(&client, instance, process_id)
+            .await
+            .unwrap();
+        let process = client
+            .get(format!("{}/additions/{}", &instance.server_url, process_id))
+            .send()
+            .await
+            .unwrap()
+            .json::<GetProcessResponse>()
+            .await
+            .unwrap();
+        assert_eq!(process.signed_sum, Some(-2_144));
+    }
+
+    // Signed inputs are bounded so that the sum of the participants does not wrap around the prime
+    for (input, signed_input) in [(None, Some(40_000)), (Some(12), Some(-12))] {
+        let response = client
+            .post(format!("{}/additions", &instances[0].server_url))
+            .json(&CreateProcessHttpBody {
+                process_id: uuid::Uuid::new_v4(),
+                input,
+                signed_input,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_await_addition_process() {
+    let instances = setup_instances(&[50019, 50020, 50021]).await;
+
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    let awaited_process = tokio::spawn({
+        let client = client.clone();
+        let url = format!("{}/additions/await", &instances[0].server_url);
+        async move {
+            client
+                .post(url)
+                .json(&CreateProcessHttpBody {
+                    process_id,
+                    input: None,
+                    signed_input: None,
+                    external_key: None,
+                    priority: None,
+                })
+                .send()
+                .await
+                .unwrap()
+                .json::<GetProcessResponse>()
+                .await
+                .unwrap()
+        }
+    });
+    let mut expected_sum = 0_u128;
+    for instance in &instances[1..] {
+        let created_process = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap()
+            .json::<CreatedProcessResponse>()
+            .await
+            .unwrap();
+        expected_sum += created_process.input as u128;
+    }
+
+    let awaited_process = awaited_process.await.unwrap();
+    expected_sum += awaited_process.input as u128;
+    assert_eq!(awaited_process.process_id, process_id);
+    assert_eq!(
+        awaited_process.sum,
+        Some((expected_sum % 1_000_000_007) as u64)
+    );
+    // With three participants, the modular sum is the integer sum of the inputs
+    assert!(!awaited_process.wrapped);
+    assert_eq!(awaited_process.sum, Some(expected_sum as u64));
+}
+
+#[tokio::test]
+async fn test_create_and_await_addition_process_timeout() {
+    // Peers of the default configuration are not running, the process can not complete
+    let instance = setup_instance(Config {
+        await_completion_timeout: std::time::Duration::from_millis(500),
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/additions/await", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::REQUEST_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_explicit_input_is_required_when_enabled() {
+    // Peers of the default configuration are not running, only the creation is checked
+    let instance = setup_instance(Config {
+        require_explicit_input: true,
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let created_process = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: Some(42),
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(created_process.input, 42);
+}
+
+#[tokio::test]
+async fn test_addition_pushed_progress_advances_process_without_poll() {
+    // The only peer is not running, polling it can not advance the process
+    let instance = setup_instance(Config {
+        peers: vec![Peer::new(2, "http://127.0.0.1:9".to_string())],
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let created_process = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+    let process_id = created_process.process_id;
+    let share_sent_to_peer = client
+        .get(format!("{}/admin/export", &instance.server_url))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap()
+        .processes[0]
+        .input_shares()
+        .shares_to_send[&2];
+
+    // The peer contributes a zero input with a zero polynomial, its shares sum is then the share it received
+    let receive_url = format!("{}/additions/{}/receive", &instance.server_url, process_id);
+    let push = |payload: PeerMessagePayload| {
+        client
+            .post(&receive_url)
+            .header("X-PEER-ID", "2")
+            .json(&payload)
+            .send()
+    };
+    let response = push(PeerMessagePayload::SharesSum {
+        value: share_sent_to_peer,
+    })
+    .await
+    .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    let response = push(PeerMessagePayload::Share { value: 0 }).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = push(PeerMessagePayload::SharesSum {
+        value: share_sent_to_peer,
+    })
+    .await
+    .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let process = client
+        .get(format!("{}/additions/{}", &instance.server_url, process_id))
+        .send()
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(process.sum, Some(created_process.input));
+    assert!(!process.wrapped);
+}
+
+#[tokio::test]
+async fn test_process_state_follows_the_protocol_rounds() {
+    // The only peer is not running, the process only advances with the pushes of the test
+    let instance = setup_instance(Config {
+        peers: vec![Peer::new(2, "http://127.0.0.1:9".to_string())],
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap()
+        .process_id;
+    let get_process = || async {
+        client
+            .get(format!("{}/additions/{}", &instance.server_url, process_id))
+            .send()
+            .await
+            .unwrap()
+            .json::<GetProcessResponse>()
+            .await
+            .unwrap()
+    };
+    let push = |payload: PeerMessagePayload| {
+        client
+            .post(format!(
+                "{}/additions/{}/receive",
+                &instance.server_url, process_id
+            ))
+            .header("X-PEER-ID", "2")
+            .json(&payload)
+            .send()
+    };
+
+    let process = get_process().await;
+    assert_eq!(process.state, ProcessState::AwaitingPeerShares);
+    assert_eq!(
+        process.shares,
+        ReceivedCount {
+            received: 0,
+            expected: 1
+        }
+    );
+
+    // The peer contributes a zero input with a zero polynomial
+    push(PeerMessagePayload::Share { value: 0 }).await.unwrap();
+    let process = get_process().await;
+    assert_eq!(process.state, ProcessState::AwaitingPeerSharesSum);
+    assert_eq!(process.shares.received, 1);
+    assert_eq!(
+        process.shares_sums,
+        ReceivedCount {
+            received: 0,
+            expected: 1
+        }
+    );
+
+    let share_sent_to_peer = client
+        .get(format!("{}/admin/export", &instance.server_url))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap()
+        .processes[0]
+        .input_shares()
+        .shares_to_send[&2];
+    push(PeerMessagePayload::SharesSum {
+        value: share_sent_to_peer,
+    })
+    .await
+    .unwrap();
+    let process = get_process().await;
+    assert_eq!(process.state, ProcessState::Completed);
+    assert_eq!(process.shares_sums.received, 1);
+    assert!(process.sum.is_some());
+}
+
+#[tokio::test]
+async fn test_slow_peer_finishes_from_a_completed_process() {
+    // The only peer is not running, it is played by the test as a peer slower than the server
+    let retention = std::time::Duration::from_secs(1);
+    let instance = setup_instance(Config {
+        peers: vec![Peer::new(2, "http://127.0.0.1:9".to_string())],
+        completed_retention: Some(retention),
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let created_process = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+    let process_id = created_process.process_id;
+    let share_sent_to_peer = client
+        .get(format!("{}/admin/export", &instance.server_url))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap()
+        .processes[0]
+        .input_shares()
+        .shares_to_send[&2];
+
+    // The peer contributes a zero input with a zero polynomial, its shares sum is then the share it received
+    for payload in [
+        PeerMessagePayload::Share { value: 0 },
+        PeerMessagePayload::SharesSum {
+            value: share_sent_to_peer,
+        },
+    ] {
+        client
+            .post(format!(
+                "{}/additions/{}/receive",
+                &instance.server_url, process_id
+            ))
+            .header("X-PEER-ID", "2")
+            .json(&payload)
+            .send()
+            .await
+            .unwrap();
+    }
+    let process = client
+        .get(format!("{}/additions/{}", &instance.server_url, process_id))
+        .send()
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(process.state, ProcessState::Completed);
+
+    // The slow peer polls the completed process and finishes from the shares sum it is given
+    let progress_url = format!(
+        "{}/additions/{}/progress?shares_sum={share_sent_to_peer}",
+        &instance.server_url, process_id
+    );
+    let response = client
+        .get(&progress_url)
+        .header("X-PEER-ID", "2")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let progress = response.json::<AdditionProcessProgress>().await.unwrap();
+    assert!(progress.completed);
+    let final_sum = recover_secret(
+        &[
+            Share {
+                point: 1,
+                value: progress.shares_sum.unwrap(),
+            },
+            Share {
+                point: 2,
+                value: share_sent_to_peer,
+            },
+        ],
+        1_000_000_007,
+    )
+    .unwrap();
+    assert_eq!(Some(final_sum), process.sum);
+
+    // The completed process only answers until its retention elapses, completed processes are swept every retention
+    tokio::time::sleep(3 * retention).await;
+    let response = client
+        .get(&progress_url)
+        .header("X-PEER-ID", "2")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_redelivered_pushed_share_is_ignored() {
+    // The peers are not running, the process keeps awaiting the share of peer 3
+    let instance = setup_instance(Config {
+        peers: vec![
+            Peer::new(2, "http://127.0.0.1:9".to_string()),
+            Peer::new(3, "http://127.0.0.1:9".to_string()),
+        ],
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap()
+        .process_id;
+
+    // The outbox delivers at least once, the same push may be received twice
+    let push = || {
+        client
+            .post(format!(
+                "{}/additions/{}/receive",
+                &instance.server_url, process_id
+            ))
+            .header("X-PEER-ID", "2")
+            .json(&PeerMessagePayload::Share { value: 7 })
+            .send()
+    };
+    let response = push().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = push().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let export = client
+        .get(format!("{}/admin/export", &instance.server_url))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    assert_eq!(export.processes.len(), 1);
+    assert_eq!(
+        export.processes[0].received_shares(),
+        Some(&HashMap::from([(2, 7)]))
+    );
+}
+
+#[tokio::test]
+async fn test_process_is_retrieved_by_external_key() {
+    let instance = setup_instance(common::default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let created_process = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: Some(12),
+            signed_input: None,
+            external_key: Some("job-42".to_string()),
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+
+    let response = client
+        .get(format!("{}/additions/by-key/job-42", &instance.server_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let process = response.json::<GetProcessResponse>().await.unwrap();
+    assert_eq!(process.process_id, created_process.process_id);
+    assert_eq!(process.input, 12);
+
+    let response = client
+        .get(format!("{}/additions/by-key/job-43", &instance.server_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_paused_orchestrator_freezes_process_until_resumed() {
+    let instances = setup_instances(&[50026, 50027]).await;
+    let client = reqwest::Client::new();
+    let paused_instance_url = &instances[0].server_url;
+
+    let response = client
+        .post(format!("{paused_instance_url}/admin/orchestrator/pause"))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    let metrics = client
+        .get(format!("{paused_instance_url}/metrics"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains("orchestrator_paused 1\n"));
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    // The process is pinged several times without advancing
+    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+    let export = client
+        .get(format!("{paused_instance_url}/admin/export"))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    assert!(matches!(
+        &export.processes[0],
+        AdditionProcess::AwaitingPeerShares(p) if p.received_shares.is_empty()
+    ));
+
+    let response = client
+        .post(format!("{paused_instance_url}/admin/orchestrator/resume"))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
+#[tokio::test]
+async fn test_silent_peer_stalls_process_until_timeout() {
+    // The first instance never hears from the second one
+    let instances = setup_instances_with(&[50031, 50032], |config| {
+        if config.server_peer_id == 1 {
+            config.silent_peer_ids = vec![2];
+            config.await_completion_timeout = std::time::Duration::from_secs(2);
+        }
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let process_id = uuid::Uuid::new_v4();
+
+    let response = client
+        .post(format!("{}/additions", &instances[1].server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let response = client
+        .post(format!("{}/additions/await", &instances[0].server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::REQUEST_TIMEOUT);
+
+    let export = client
+        .get(format!("{}/admin/export", &instances[0].server_url))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    assert!(matches!(
+        &export.processes[0],
+        AdditionProcess::AwaitingPeerShares(p) if p.received_shares.is_empty()
+    ));
+}
+
+#[tokio::test]
+async fn test_solo_mode_completes_processes_on_creation() {
+    let instance = setup_instance(Config {
+        peers: vec![],
+        solo_mode: true,
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    let created_process = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: Some(42),
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+    assert!(created_process.notified_peers.is_empty());
+
+    let process = client
+        .get(format!("{}/additions/{}", &instance.server_url, process_id))
+        .send()
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(process.state, ProcessState::Completed);
+    assert_eq!(process.sum, Some(42));
+
+    // Awaiting a process does not wait for an orchestrator iteration
+    let process = client
+        .post(format!("{}/additions/await", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: Some(7),
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(process.sum, Some(7));
+}
+
+#[tokio::test]
+async fn test_process_peers_report_missing_contributions() {
+    // Peers of the default configuration are not running, only the share pushed by peer 2 is received
+    let instance = setup_instance(common::default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    let response = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let response = client
+        .post(format!(
+            "{}/additions/{}/receive",
+            &instance.server_url, process_id
+        ))
+        .header("X-PEER-ID", "2")
+        .json(&PeerMessagePayload::Share { value: 12 })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let url = format!("{}/additions/{}/peers", &instance.server_url, process_id);
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let peers = client
+        .get(&url)
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<PeerContribution>>()
+        .await
+        .unwrap();
+    assert_eq!(peers.len(), 2);
+    assert_eq!(peers[0].peer_id, 2);
+    assert!(peers[0].share_received);
+    assert!(!peers[0].sum_share_received);
+    assert_eq!(peers[1].peer_id, 3);
+    assert!(!peers[1].share_received);
+    assert!(!peers[1].sum_share_received);
+}
+
+#[tokio::test]
+async fn test_reconcile_reports_mismatching_final_sum() {
+    let instances = setup_instances(&[50028, 50029, 50030]).await;
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+    assert_completed_addition_process(&client, &instances, process_id).await;
+
+    let reconcile = || async {
+        client
+            .get(format!(
+                "{}/additions/{}/reconcile",
+                &instances[0].server_url, process_id
+            ))
+            .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap()
+            .json::<ReconcileProcessResponse>()
+            .await
+            .unwrap()
+    };
+    let reconciliation = reconcile().await;
+    assert!(reconciliation.all_agree);
+    assert!(reconciliation.mismatching_peers.is_empty());
+
+    // The third node is corrupted by replacing its process with one having another final sum
+    let corrupted_url = &instances[2].server_url;
+    let mut export = client
+        .get(format!("{corrupted_url}/admin/export"))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    match &mut export.processes[0] {
+        AdditionProcess::Completed(p) => p.final_sum = (p.final_sum + 1) % 1_000_000_007,
+        _ => panic!("expected a completed process"),
+    }
+    client
+        .delete(format!("{corrupted_url}/additions/{process_id}"))
+        .send()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!("{corrupted_url}/admin/import"))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .json(&export)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let reconciliation = reconcile().await;
+    assert!(!reconciliation.all_agree);
+    assert_eq!(reconciliation.mismatching_peers, vec![3]);
+    assert_eq!(
+        reconciliation
+            .peers
+            .iter()
+            .map(|peer| peer.peer_id)
+            .collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+}
+
+#[tokio::test]
+async fn test_addition_with_tampering_detection() {
+    let instances = setup_instances_with(&[50022, 50023, 50024, 50025], |config| {
+        config.verification_threshold = Some(2);
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let create_addition_process_response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(create_addition_process_response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
+#[tokio::test]
+async fn test_addition_completion_duration_metrics() {
+    let instances = setup_instances(&[50007, 50008, 50009]).await;
+
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let create_addition_process_response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(create_addition_process_response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+
+    for instance in &instances {
+        // The observation is recorded right after the process is completed
+        let mut safe_counter = 0;
+        loop {
+            let metrics = client
+                .get(format!("{}/metrics", &instance.server_url))
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            if metrics.contains("addition_process_completion_duration_seconds_count 1\n") {
+                break;
+            }
+            safe_counter += 1;
+            assert!(
+                safe_counter < 20,
+                "completion duration observation not recorded, metrics:\n{metrics}"
+            );
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_addition_progress_discloses_shares_sum_only_to_peers_seen_in_shares_sum_round() {
+    // The only peer is not running, it is played by the test as a peer lagging in the shares round
+    let instance = setup_instance(Config {
+        peers: vec![Peer::new(2, "http://127.0.0.1:9".to_string())],
+        ..common::default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = client
+        .post(format!("{}/additions", &instance.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap()
+        .process_id;
+    // The share of the peer moves the server to the shares sum round
+    let response = client
+        .post(format!(
+            "{}/additions/{}/receive",
+            &instance.server_url, process_id
+        ))
+        .header("X-PEER-ID", "2")
+        .json(&PeerMessagePayload::Share { value: 0 })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let progress_url = format!("{}/additions/{}/progress", &instance.server_url, process_id);
+    let fetch_shares_sum = async |query: &str| {
+        client
+            .get(format!("{progress_url}{query}"))
+            .header("X-PEER-ID", "2")
+            .send()
+            .await
+            .unwrap()
+            .json::<AdditionProcessProgress>()
+            .await
+            .unwrap()
+            .shares_sum
+    };
+    // A peer claiming the shares sum round without presenting its shares sum is still considered in the shares round
+    for query in ["", "?round=shares", "?round=shares_sum"] {
+        assert_eq!(fetch_shares_sum(query).await, None);
+    }
+    assert!(fetch_shares_sum("?shares_sum=7").await.is_some());
+    // The peer is recorded in the shares sum round, the shares sum keeps being disclosed to it
+    assert!(fetch_shares_sum("").await.is_some());
+}
+
+#[tokio::test]
+async fn test_addition_lost_process_is_surfaced_as_unrecoverable() {
+    let instances = setup_instances(&[50013, 50014, 50015]).await;
+
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    // The third instance does not know the process yet, the process is stuck in the shares round
+    for instance in &instances[..2] {
+        let create_addition_process_response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(create_addition_process_response.status().is_success());
+    }
+    // Let the second instance fetch the share of the first one
+    tokio::time::sleep(tokio::time::Duration::from_millis(2_000)).await;
+
+    // The first instance loses its state and re-creates the process with a new input
+    let delete_response = client
+        .delete(format!(
+            "{}/additions/{}",
+            &instances[0].server_url, process_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert!(delete_response.status().is_success());
+    for instance in [&instances[0], &instances[2]] {
+        let create_addition_process_response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(create_addition_process_response.status().is_success());
+    }
+
+    for instance in &instances {
+        let mut safe_counter = 0;
+        loop {
+            let process = client
+                .get(format!("{}/additions/{}", &instance.server_url, process_id))
+                .send()
+                .await
+                .unwrap()
+                .json::<GetProcessResponse>()
+                .await
+                .unwrap();
+            assert_eq!(process.sum, None, "inconsistent process has been completed");
+            if process.unrecoverable_reason.is_some() {
+                break;
+            }
+            safe_counter += 1;
+            assert!(
+                safe_counter < 50,
+                "process has not been marked as unrecoverable"
+            );
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_addition_with_http2_prior_knowledge() {
+    let instances = setup_instances_with(&[50016, 50017, 50018], |config| {
+        config.peer_http2_prior_knowledge = true;
+    })
+    .await;
+
+    let http2_client = reqwest::Client::builder()
+        .http2_prior_knowledge()
+        .build()
+        .unwrap();
+    let health_response = http2_client
+        .get(format!("{}/health", &instances[0].server_url))
+        .send()
+        .await
+        .unwrap();
+    assert!(health_response.status().is_success());
+    assert_eq!(health_response.version(), reqwest::Version::HTTP_2);
+
+    let client = reqwest::Client::new();
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let create_addition_process_response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(create_addition_process_response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
+#[tokio::test]
+async fn test_addition_with_mapped_peer_points() {
+    let points = PeerPoints::new(HashMap::from([(1, 7), (2, 3), (3, 200)])).unwrap();
+    let instances = setup_instances_with(&[50033, 50034, 50035], |config| {
+        config.peer_points = points.clone();
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
 #[tokio::test]
 async fn test_addition_multiple_process() {
     let instances = setup_instances(&[50004, 50005, 50006]).await;
@@ -44,6 +1237,10 @@ async fn test_addition_multiple_process() {
                 .post(format!("{}/additions", &instance.server_url))
                 .json(&CreateProcessHttpBody {
                     process_id: *process_id,
+                    input: None,
+                    signed_input: None,
+                    external_key: None,
+                    priority: None,
                 })
                 .send()
                 .await
@@ -56,7 +1253,176 @@ async fn test_addition_multiple_process() {
     }
 }
 
+#[tokio::test]
+async fn test_late_node_rejoins_ongoing_process() {
+    let mut configs = instance_configs(&[50039, 50040, 50041], |_| {});
+    let mut late_config = configs.pop().unwrap();
+    late_config.rejoin_on_startup = true;
+    let mut instances = Vec::new();
+    for config in configs {
+        instances.push(setup_instance(config).await.unwrap());
+    }
+
+    let client = reqwest::Client::new();
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    // The late node is never asked to create the process, it discovers it from its peers
+    instances.push(setup_instance(late_config).await.unwrap());
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
+#[tokio::test]
+async fn test_transcript_records_the_protocol_steps_in_order() {
+    let instances = setup_instances_with(&[50042, 50043, 50044], |config| {
+        config.record_transcripts = true;
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+    assert_completed_addition_process(&client, &instances, process_id).await;
+
+    let process = client
+        .get(format!("{}/admin/export", &instances[0].server_url))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap()
+        .processes
+        .remove(0);
+    let AdditionProcess::Completed(process) = process else {
+        panic!("expected a completed process");
+    };
+    let transcript = client
+        .get(format!(
+            "{}/additions/{process_id}/transcript",
+            &instances[0].server_url
+        ))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessTranscriptResponse>()
+        .await
+        .unwrap();
+    let events = transcript
+        .events
+        .into_iter()
+        .map(|entry| entry.event)
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 9);
+    assert_eq!(
+        events[..3],
+        [
+            TranscriptEvent::Created {
+                input: process.input_shares.input,
+                own_share: process.input_shares.own_share,
+            },
+            TranscriptEvent::OutgoingShare {
+                peer_id: 2,
+                value: process.input_shares.shares_to_send[&2],
+            },
+            TranscriptEvent::OutgoingShare {
+                peer_id: 3,
+                value: process.input_shares.shares_to_send[&3],
+            },
+        ]
+    );
+    // Shares are received from the peers in any order, before the shares sum is computed
+    for event in &events[3..5] {
+        let TranscriptEvent::ShareReceived { peer_id, value } = event else {
+            panic!("expected a received share, got {event:?}");
+        };
+        assert_eq!(process.received_shares[peer_id], *value);
+    }
+    assert_eq!(
+        events[5],
+        TranscriptEvent::SharesSumComputed {
+            shares_sum: process.shares_sum,
+        }
+    );
+    for event in &events[6..8] {
+        let TranscriptEvent::SharesSumReceived { peer_id, value } = event else {
+            panic!("expected a received shares sum, got {event:?}");
+        };
+        assert_eq!(process.received_shares_sums[peer_id], *value);
+    }
+    assert_eq!(
+        events[8],
+        TranscriptEvent::Reconstructed {
+            own_shares_sum: process.shares_sum,
+            received_shares_sums: vec![
+                (2, process.received_shares_sums[&2]),
+                (3, process.received_shares_sums[&3]),
+            ],
+            final_sum: process.final_sum,
+        }
+    );
+
+    // Transcripts are not recorded by default
+    let instance = setup_instance(common::default_test_config()).await.unwrap();
+    let response = client
+        .get(format!(
+            "{}/additions/{process_id}/transcript",
+            &instance.server_url
+        ))
+        .header("X-ADMIN-TOKEN", common::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
 async fn setup_instances(ports: &[u16]) -> Vec<common::InstanceState> {
+    setup_instances_with(ports, |_| {}).await
+}
+
+async fn setup_instances_with(
+    ports: &[u16],
+    customize_config: impl Fn(&mut Config),
+) -> Vec<common::InstanceState> {
+    let mut instances = Vec::new();
+    for config in instance_configs(ports, customize_config) {
+        instances.push(setup_instance(config).await.unwrap());
+    }
+    instances
+}
+
+/// Configurations of instances listening on the given ports, each instance has the others as peers.
+fn instance_configs(ports: &[u16], customize_config: impl Fn(&mut Config)) -> Vec<Config> {
     let peers = ports
         .iter()
         .enumerate()
@@ -70,20 +1436,17 @@ async fn setup_instances(ports: &[u16]) -> Vec<common::InstanceState> {
             .filter(|p| p.id != (i + 1) as u8)
             .cloned()
             .collect::<Vec<_>>();
-        let config = Config {
+        let mut config = Config {
             port: *port,
             log_level: Level::WARN,
             server_peer_id: (i + 1) as u8,
             peers: peer_list,
+            ..common::default_test_config()
         };
+        customize_config(&mut config);
         configs.push(config);
     }
-
-    let mut instances = Vec::new();
-    for config in configs {
-        instances.push(setup_instance(config).await.unwrap());
-    }
-    instances
+    configs
 }
 
 async fn assert_completed_addition_process(
@@ -95,7 +1458,7 @@ async fn assert_completed_addition_process(
         .map(|instance| async move {
             wait_for_completed_addition_process(client, instance, process_id).await
         })
-        .buffer_unordered(3);
+        .buffer_unordered(instances.len());
     let wait_for_completion_results: Vec<Result<CompletedAdditionProcess, anyhow::Error>> =
         wait_for_completion_bodies.collect().await;
     let wait_for_completion_results: Vec<CompletedAdditionProcess> = wait_for_completion_results
@@ -151,3 +1514,132 @@ async fn wait_for_completed_addition_process(
         }
     }
 }
+
+#[tokio::test]
+async fn test_addition_completed_once_confirmed_by_quorum() {
+    let instances = setup_instances_with(&[50045, 50046, 50047], |config| {
+        config.confirm_quorum = Some(2);
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
+#[tokio::test]
+async fn test_addition_between_nodes_serializing_values_differently() {
+    let mut configs = instance_configs(&[50048, 50049], |_| {});
+    configs[0].values_as_strings = true;
+    let mut instances = Vec::new();
+    for config in configs {
+        instances.push(setup_instance(config).await.unwrap());
+    }
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+    let raw_process = |instance: &common::InstanceState| {
+        let request = client.get(format!("{}/additions/{}", &instance.server_url, process_id));
+        async move {
+            request
+                .send()
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+    assert!(raw_process(&instances[0]).await["sum"].is_string());
+    assert!(raw_process(&instances[1]).await["sum"].is_number());
+}
+
+#[tokio::test]
+async fn test_addition_with_montgomery_recovery_strategy() {
+    let instances = setup_instances_with(&[50050, 50051, 50052, 50053], |config| {
+        config.recovery_strategy = RecoveryStrategy::Montgomery;
+        config.verification_threshold = Some(2);
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
+
+#[tokio::test]
+async fn test_addition_with_self_check() {
+    let instances = setup_instances_with(&[50054, 50055, 50056], |config| {
+        config.self_check = true;
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    for instance in &instances {
+        let response = client
+            .post(format!("{}/additions", &instance.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    assert_completed_addition_process(&client, &instances, process_id).await;
+}
@@ -0,0 +1,750 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use axum::http::{StatusCode, header};
+use mpc_exploration::{
+    Config, Peer,
+    domains::additions::{
+        AdditionProcess, AwaitingPeerSharesSumProcess, CompletedProcess, TamperedProcess,
+        UnrecoverableProcess,
+    },
+    metrics::{OrchestratorMetricsSnapshot, ProcessFailureAttempts},
+    mpc,
+    routes::{
+        addition::{
+            CreateProcessHttpBody, CreatedProcessResponse, GetProcessResponse, ResendShareResponse,
+        },
+        admin::{ImportProcessesResponse, ProcessesExport, StatisticsResponse},
+    },
+};
+
+mod common;
+use common::{ADMIN_TOKEN, default_test_config, setup_instance};
+
+#[tokio::test]
+async fn test_admin_endpoint_requires_admin_token() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/additions/{}/force-complete",
+        &instance_state.server_url,
+        uuid::Uuid::new_v4()
+    );
+
+    let response = client.post(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(&url)
+        .header("X-ADMIN-TOKEN", "wrong-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Authorized, the process is unknown
+    let response = client
+        .post(&url)
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_export_and_import_processes() {
+    let source = setup_instance(default_test_config()).await.unwrap();
+    let destination = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = uuid::Uuid::new_v4();
+    let created_process = client
+        .post(format!("{}/additions", &source.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+
+    let response = client
+        .get(format!("{}/admin/export", &source.server_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let export = client
+        .get(format!("{}/admin/export", &source.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    assert_eq!(export.processes.len(), 1);
+
+    let import = client
+        .post(format!("{}/admin/import", &destination.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .json(&export)
+        .send()
+        .await
+        .unwrap()
+        .json::<ImportProcessesResponse>()
+        .await
+        .unwrap();
+    assert_eq!(import.imported, 1);
+
+    let imported_process = client
+        .get(format!(
+            "{}/additions/{}",
+            &destination.server_url, process_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(imported_process.input, created_process.input);
+    assert_eq!(imported_process.sum, None);
+}
+
+#[tokio::test]
+async fn test_export_and_import_processes_with_compression() {
+    let compressed_config = || Config {
+        peer_compression: true,
+        ..default_test_config()
+    };
+    let source = setup_instance(compressed_config()).await.unwrap();
+    let destination = setup_instance(compressed_config()).await.unwrap();
+    // Automatic decompression is disabled in order to inspect the raw responses
+    let client = reqwest::Client::builder().gzip(false).build().unwrap();
+
+    for _ in 0..50 {
+        client
+            .post(format!("{}/additions", &source.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id: uuid::Uuid::new_v4(),
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // Clients not accepting gzip still receive an uncompressed response
+    let response = client
+        .get(format!("{}/admin/export", &source.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    let uncompressed_export = response.bytes().await.unwrap();
+
+    let response = client
+        .get(format!("{}/admin/export", &source.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .header(header::ACCEPT_ENCODING, "gzip")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get(header::CONTENT_ENCODING).unwrap(),
+        "gzip"
+    );
+    let compressed_export = response.bytes().await.unwrap();
+    assert!(compressed_export.len() < uncompressed_export.len());
+
+    let mut export = Vec::new();
+    flate2::read::GzDecoder::new(compressed_export.as_ref())
+        .read_to_end(&mut export)
+        .unwrap();
+    assert_eq!(export, uncompressed_export);
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&export).unwrap();
+    let import = client
+        .post(format!("{}/admin/import", &destination.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CONTENT_ENCODING, "gzip")
+        .body(encoder.finish().unwrap())
+        .send()
+        .await
+        .unwrap()
+        .json::<ImportProcessesResponse>()
+        .await
+        .unwrap();
+    assert_eq!(import.imported, 50);
+}
+
+#[tokio::test]
+async fn test_stream_processes() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let mut process_ids = Vec::new();
+    for _ in 0..50 {
+        let process_id = uuid::Uuid::new_v4();
+        let response = client
+            .post(format!("{}/additions", &instance_state.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        process_ids.push(process_id);
+    }
+
+    let url = format!("{}/admin/processes/stream", &instance_state.server_url);
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(&url)
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/x-ndjson"
+    );
+
+    let body = response.text().await.unwrap();
+    let mut streamed_ids = body
+        .lines()
+        .map(|line| serde_json::from_str::<GetProcessResponse>(line).unwrap())
+        .map(|process| process.process_id)
+        .collect::<Vec<_>>();
+    streamed_ids.sort();
+    process_ids.sort();
+    assert_eq!(streamed_ids, process_ids);
+}
+
+#[tokio::test]
+async fn test_orchestrator_failures() {
+    // Peers of the default configuration are not running, polls of the process fail
+    let instance_state = setup_instance(Config {
+        orchestrator_interval: std::time::Duration::from_millis(100),
+        orchestrator_max_failures: 3,
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+    let url = format!("{}/admin/orchestrator/failures", &instance_state.server_url);
+
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let process_id = uuid::Uuid::new_v4();
+    client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    // Attempts are incremented by each failed poll until the process is skipped
+    let mut observed_attempts = vec![];
+    let mut safe_counter = 0;
+    while observed_attempts.last() != Some(&3) {
+        let failures = client
+            .get(&url)
+            .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap()
+            .json::<Vec<ProcessFailureAttempts>>()
+            .await
+            .unwrap();
+        if let Some(failure) = failures.iter().find(|f| f.process_id == process_id)
+            && observed_attempts.last() != Some(&failure.attempts)
+        {
+            observed_attempts.push(failure.attempts);
+        }
+        safe_counter += 1;
+        assert!(
+            safe_counter < 100,
+            "process not skipped: {observed_attempts:?}"
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+    }
+    assert!(observed_attempts.len() > 1);
+    assert!(observed_attempts.is_sorted());
+}
+
+#[tokio::test]
+async fn test_orchestrator_activity() {
+    // Peers of the default configuration are not running, polls of the process fail
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    let mut safe_counter = 0;
+    let activity = loop {
+        let activity = client
+            .get(format!("{}/admin/orchestrator", &instance_state.server_url))
+            .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap()
+            .json::<OrchestratorMetricsSnapshot>()
+            .await
+            .unwrap();
+        if activity.poll_failures > 0 {
+            break activity;
+        }
+        safe_counter += 1;
+        assert!(safe_counter < 50, "no failed poll recorded");
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    };
+    assert_eq!(activity.last_polled_processes, 1);
+    assert_eq!(activity.poll_successes, 0);
+    let elapsed = chrono::Utc::now() - activity.last_run_at.unwrap();
+    assert!(elapsed < chrono::TimeDelta::seconds(5));
+
+    let metrics = client
+        .get(format!("{}/metrics", &instance_state.server_url))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains("orchestrator_last_polled_processes 1\n"));
+    assert!(metrics.contains("orchestrator_poll_successes_total 0\n"));
+}
+
+#[tokio::test]
+async fn test_force_complete_with_threshold_despite_a_stuck_peer() {
+    let mut config = default_test_config();
+    config
+        .peers
+        .push(Peer::new(4, "http://localhost:3003".to_string()));
+    config.verification_threshold = Some(2);
+    let instance_state = setup_instance(config).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let export = client
+        .get(format!("{}/admin/export", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    let input_shares = export.processes[0].input_shares().clone();
+    assert_eq!(input_shares.verification_threshold, Some(2));
+
+    // Peer 4 is stuck, the shares sums of the other participants are enough with a threshold of 2
+    let sum = 1234;
+    let shares_sums = mpc::split_secret(sum, &[1, 2, 3, 4], Some(1), 1_000_000_007).unwrap();
+    let process_id = uuid::Uuid::new_v4();
+    let process = AdditionProcess::AwaitingPeerSharesSum(AwaitingPeerSharesSumProcess {
+        id: process_id,
+        created_at: chrono::Utc::now(),
+        external_key: None,
+        priority: 0,
+        input_shares,
+        received_shares: HashMap::new(),
+        shares_sum: shares_sums[&1],
+        received_shares_sums: HashMap::from([(2, shares_sums[&2]), (3, shares_sums[&3])]),
+    });
+    let response = client
+        .post(format!("{}/admin/import", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .json(&ProcessesExport {
+            processes: vec![process],
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = client
+        .post(format!(
+            "{}/additions/{}/force-complete",
+            &instance_state.server_url, process_id
+        ))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let completed_process = response.json::<GetProcessResponse>().await.unwrap();
+    assert_eq!(completed_process.sum, Some(sum));
+}
+
+#[tokio::test]
+async fn test_outbox_flush() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let url = format!("{}/admin/outbox/flush", &instance_state.server_url);
+
+    let response = client.post(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(&url)
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_statistics() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    // Peers of the default configuration are not running, the messages to them stay in the outbox
+    let response = client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // Processes in the other states are imported
+    let export = client
+        .get(format!("{}/admin/export", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    let input_shares = export.processes[0].input_shares().clone();
+    let created_at = chrono::Utc::now() - chrono::TimeDelta::seconds(10);
+    let completed = |completion_seconds| {
+        AdditionProcess::Completed(CompletedProcess {
+            id: uuid::Uuid::new_v4(),
+            created_at,
+            external_key: None,
+            priority: 0,
+            completed_at: created_at + chrono::TimeDelta::seconds(completion_seconds),
+            input_shares: input_shares.clone(),
+            received_shares: HashMap::new(),
+            shares_sum: 0,
+            received_shares_sums: HashMap::new(),
+            final_sum: 0,
+        })
+    };
+    let processes = vec![
+        completed(2),
+        completed(4),
+        AdditionProcess::AwaitingPeerSharesSum(AwaitingPeerSharesSumProcess {
+            id: uuid::Uuid::new_v4(),
+            created_at,
+            external_key: None,
+            priority: 0,
+            input_shares: input_shares.clone(),
+            received_shares: HashMap::new(),
+            shares_sum: 0,
+            received_shares_sums: HashMap::new(),
+        }),
+        AdditionProcess::Unrecoverable(UnrecoverableProcess {
+            id: uuid::Uuid::new_v4(),
+            created_at,
+            external_key: None,
+            priority: 0,
+            input_shares: input_shares.clone(),
+            reason: "share of peer 2 changed".to_string(),
+        }),
+        AdditionProcess::Tampered(TamperedProcess {
+            id: uuid::Uuid::new_v4(),
+            created_at,
+            external_key: None,
+            priority: 0,
+            input_shares,
+            received_shares: HashMap::new(),
+            shares_sum: 0,
+            received_shares_sums: HashMap::new(),
+            reason: "recoveries disagree".to_string(),
+        }),
+    ];
+    let response = client
+        .post(format!("{}/admin/import", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .json(&ProcessesExport { processes })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("{}/admin/stats", &instance_state.server_url);
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let statistics = client
+        .get(&url)
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<StatisticsResponse>()
+        .await
+        .unwrap();
+    assert_eq!(statistics.awaiting_peer_shares, 1);
+    assert_eq!(statistics.awaiting_peer_shares_sum, 1);
+    assert_eq!(statistics.completed, 2);
+    assert_eq!(statistics.unrecoverable, 1);
+    assert_eq!(statistics.tampered, 1);
+    assert_eq!(statistics.average_completion_seconds, Some(3.0));
+    // A progress notification and a share push per peer, retried until abandoned
+    assert_eq!(statistics.outbox_depth, 4);
+}
+
+#[tokio::test]
+async fn test_resend_share_enqueues_only_the_targeted_peer() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let process_id = uuid::Uuid::new_v4();
+    client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id,
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+    let outbox_depth = || async {
+        client
+            .get(format!("{}/admin/stats", &instance_state.server_url))
+            .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap()
+            .json::<StatisticsResponse>()
+            .await
+            .unwrap()
+            .outbox_depth
+    };
+    // Peers are unreachable, the messages of the creation stay in the outbox
+    let initial_outbox_depth = outbox_depth().await;
+
+    let url = |peer_id: u8| {
+        format!(
+            "{}/additions/{process_id}/resend-share/{peer_id}",
+            &instance_state.server_url
+        )
+    };
+    let response = client.post(url(3)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(url(3))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert_eq!(
+        response
+            .json::<ResendShareResponse>()
+            .await
+            .unwrap()
+            .enqueued_peers,
+        vec![3]
+    );
+    assert_eq!(outbox_depth().await, initial_outbox_depth + 1);
+
+    // The server itself and unknown peers have no share to resend
+    for peer_id in [1, 4] {
+        let response = client
+            .post(url(peer_id))
+            .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    assert_eq!(outbox_depth().await, initial_outbox_depth + 1);
+}
+
+#[tokio::test]
+async fn test_standby_mirrors_the_primary_until_promoted() {
+    let primary = setup_instance(default_test_config()).await.unwrap();
+    let standby = setup_instance(Config {
+        standby_primary_url: Some(primary.server_url.clone()),
+        standby_sync_interval: std::time::Duration::from_millis(100),
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    // A completed process of the primary, imported as exported from another node
+    let created_process = client
+        .post(format!("{}/additions", &primary.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+    let export = client
+        .get(format!("{}/admin/export", &primary.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    let completed_process = CompletedProcess {
+        id: uuid::Uuid::new_v4(),
+        created_at: chrono::Utc::now(),
+        external_key: None,
+        priority: 0,
+        completed_at: chrono::Utc::now(),
+        input_shares: export.processes[0].input_shares().clone(),
+        received_shares: HashMap::new(),
+        shares_sum: 0,
+        received_shares_sums: HashMap::new(),
+        final_sum: 42,
+    };
+    let response = client
+        .post(format!("{}/admin/import", &primary.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .json(&ProcessesExport {
+            processes: vec![AdditionProcess::Completed(completed_process.clone())],
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let get_process = |process_id: uuid::Uuid| {
+        client
+            .get(format!("{}/additions/{process_id}", &standby.server_url))
+            .send()
+    };
+    let mut safe_counter = 0;
+    while get_process(completed_process.id).await.unwrap().status() != StatusCode::OK {
+        safe_counter += 1;
+        assert!(safe_counter < 50, "process not mirrored by the standby");
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+    let mirrored_process = get_process(created_process.process_id)
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(mirrored_process.input, created_process.input);
+
+    // The mirror is read-only until the standby is promoted
+    let create_on_standby = || {
+        client
+            .post(format!("{}/additions", &standby.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id: uuid::Uuid::new_v4(),
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+    };
+    assert_eq!(
+        create_on_standby().await.unwrap().status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    let promote = || {
+        client
+            .post(format!("{}/admin/promote", &standby.server_url))
+            .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+            .send()
+    };
+    assert_eq!(promote().await.unwrap().status(), StatusCode::NO_CONTENT);
+    assert_eq!(promote().await.unwrap().status(), StatusCode::CONFLICT);
+
+    let promoted_process = get_process(completed_process.id)
+        .await
+        .unwrap()
+        .json::<GetProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(promoted_process.sum, Some(42));
+    assert_eq!(create_on_standby().await.unwrap().status(), StatusCode::OK);
+}
@@ -8,9 +8,19 @@ use axum::{
 use mpc_exploration::{
     Config, Peer,
     domains::additions::{
-        orchestrator::setup_addition_process_orchestrator,
+        PeerPoints,
+        completion::ProcessCompletions,
+        notifier::Notifier,
+        orchestrator::{BlockedPeers, OrchestratorSwitch, setup_addition_process_orchestrator},
+        rejoin::ProcessesRejoiner,
         repository::InMemoryAdditionProcessRepository,
+        retention::CompletedProcessesSweeper,
+        standby::{StandbyMirror, StandbyRole},
+        transcript,
     },
+    listener::{BindRetryConfig, bind_listener_with_retries},
+    metrics::Metrics,
+    mpc::RecoveryStrategy,
     peer_communication::setup_peer_communication,
     routes::app_router,
 };
@@ -18,6 +28,9 @@ use tower_http::trace::TraceLayer;
 use tracing::{Level, Span, error, info, info_span, level_filters::LevelFilter};
 use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
+#[allow(dead_code)]
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
 #[allow(dead_code)]
 pub struct InstanceState {
     pub server_url: String,
@@ -33,9 +46,49 @@ pub fn default_test_config() -> Config {
             Peer::new(2, "http://localhost:3001".to_string()),
             Peer::new(3, "http://localhost:3002".to_string()),
         ],
+        admin_token: Some(ADMIN_TOKEN.to_string()),
+        peer_http2_prior_knowledge: false,
+        peer_compression: false,
+        dry_run: false,
+        completed_retention: None,
+        await_completion_timeout: Duration::from_secs(5),
+        completion_event_buffer: 128,
+        verification_threshold: None,
+        excluded_shares_sum_peer_ids: vec![],
+        confirm_quorum: None,
+        require_explicit_input: false,
+        orchestrator_max_failures: 5,
+        orchestrator_interval: Duration::from_secs(1),
+        relayer_interval: Duration::from_secs(1),
+        outbox_retry_jitter: 0.5,
+        outbox_item_ttl: None,
+        heartbeat_timeout: Duration::from_secs(60),
+        silent_peer_ids: vec![],
+        blocked_peer_ids: vec![],
+        coordinator_peer_id: None,
+        rejoin_on_startup: false,
+        strict_peer_payloads: false,
+        peer_process_rate_limit: 20,
+        inbound_concurrency: 256,
+        peer_points: PeerPoints::default(),
+        shutdown_grace: Duration::from_secs(30),
+        simulate_peers: None,
+        solo_mode: false,
+        values_as_strings: false,
+        recovery_strategy: RecoveryStrategy::default(),
+        self_check: false,
+        record_transcripts: false,
+        standby_primary_url: None,
+        standby_sync_interval: Duration::from_secs(1),
+        // Fixed test ports may be briefly held by outgoing connections using them as ephemeral ports
+        bind_retry: BindRetryConfig {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+        },
     }
 }
 
+#[allow(dead_code)]
 pub async fn setup_instance(config: Config) -> Result<InstanceState, anyhow::Error> {
     let _ = tracing_subscriber::registry()
         .with(
@@ -43,19 +96,31 @@ pub async fn setup_instance(config: Config) -> Result<InstanceState, anyhow::Err
         )
         .try_init();
 
-    let addition_process_repository = Arc::new(InMemoryAdditionProcessRepository::new());
+    let (addition_process_repository, transcripts) = transcript::record_transcripts(
+        Arc::new(InMemoryAdditionProcessRepository::new()),
+        config.record_transcripts,
+    );
+    let metrics = Arc::new(Metrics::new());
+    let completions = Arc::new(ProcessCompletions::new(config.completion_event_buffer));
+    let orchestrator_switch = Arc::new(OrchestratorSwitch::default());
+    // A standby only mirrors the processes of its primary, its orchestrator resumes on promotion
+    let standby = Arc::new(StandbyRole::new(config.standby_primary_url.is_some()));
+    if standby.is_standby() {
+        orchestrator_switch.pause();
+        metrics.orchestrator.record_paused(true);
+    }
+    let blocked_peers = Arc::new(BlockedPeers::new(&config.blocked_peer_ids));
 
-    let (
-        peer_client,
-        peer_messages_sender,
-        mut peer_messages_relayer,
-        peer_messages_relayer_pinger,
-    ) = setup_peer_communication(config.server_peer_id, &config.peers);
+    let (peer_client, peer_messages_sender, peer_messages_relayer, peer_messages_relayer_pinger) =
+        setup_peer_communication(&config)?;
+    let mut peer_messages_relayer =
+        peer_messages_relayer.with_heartbeats(metrics.heartbeats.clone());
     tokio::spawn(async move {
         peer_messages_relayer.run().await;
     });
+    let relayer_interval = config.relayer_interval;
     tokio::spawn(async move {
-        if let Err(e) = peer_messages_relayer_pinger.run().await {
+        if let Err(e) = peer_messages_relayer_pinger.run(relayer_interval).await {
             error!(
                 "Peer messages relayer interval pinger encountered an error: {}",
                 e
@@ -63,31 +128,89 @@ pub async fn setup_instance(config: Config) -> Result<InstanceState, anyhow::Err
         }
     });
 
-    let (mut addition_process_orchestrator, addition_process_notifier) =
+    let (addition_process_orchestrator, addition_process_notifier) =
         setup_addition_process_orchestrator(
             addition_process_repository.clone(),
-            peer_client,
+            peer_client.clone(),
             config.server_peer_id,
-            &config.peers,
+            metrics.clone(),
+            completions.clone(),
+            orchestrator_switch.clone(),
+            config.orchestrator_max_failures,
         );
+    let mut addition_process_orchestrator = addition_process_orchestrator
+        .with_silent_peers(&config.silent_peer_ids)
+        .with_blocked_peers(blocked_peers.clone())
+        .with_peer_points(config.peer_points.clone())
+        .with_excluded_shares_sum_peers(&config.excluded_shares_sum_peer_ids)
+        .with_confirm_quorum(config.confirm_quorum)
+        .with_recovery(config.recovery_settings());
     let addition_process_notifier = Arc::new(addition_process_notifier);
     tokio::spawn(async move {
         addition_process_orchestrator.run().await;
     });
+    if let (Some(primary_url), Some(admin_token)) =
+        (&config.standby_primary_url, &config.admin_token)
+    {
+        let mirror = StandbyMirror::new(
+            addition_process_repository.clone(),
+            primary_url,
+            admin_token.clone(),
+            standby.clone(),
+        );
+        let standby_sync_interval = config.standby_sync_interval;
+        tokio::spawn(async move {
+            mirror.run(standby_sync_interval).await;
+        });
+    }
+    if let Some(retention) = config.completed_retention {
+        let sweeper =
+            CompletedProcessesSweeper::new(addition_process_repository.clone(), retention);
+        tokio::spawn(async move {
+            sweeper
+                .run(retention.clamp(Duration::from_secs(1), Duration::from_secs(60)))
+                .await;
+        });
+    }
     tokio::spawn({
         let addition_process_notifier = addition_process_notifier.clone();
+        let orchestrator_interval = config.orchestrator_interval;
         async move {
             addition_process_notifier
-                .run_interval_ping(tokio::time::Duration::from_secs(1))
+                .run_interval_ping(orchestrator_interval)
                 .await;
         }
     });
 
+    if config.rejoin_on_startup {
+        let rejoiner = ProcessesRejoiner::new(
+            addition_process_repository.clone(),
+            peer_client.clone(),
+            config.server_peer_id,
+            &config.peers.iter().map(|p| p.id).collect::<Vec<_>>(),
+            config.verification_threshold,
+            config.peer_points.clone(),
+        );
+        let addition_process_notifier = addition_process_notifier.clone();
+        tokio::spawn(async move {
+            if !rejoiner.run().await.is_empty() {
+                addition_process_notifier.ping();
+            }
+        });
+    }
+
     let app = app_router(
         &config,
         addition_process_repository,
         Arc::new(peer_messages_sender),
+        peer_client,
         addition_process_notifier,
+        metrics,
+        completions,
+        orchestrator_switch,
+        blocked_peers,
+        transcripts,
+        standby,
     )
     .layer(
         TraceLayer::new_for_http()
@@ -101,6 +224,7 @@ pub async fn setup_instance(config: Config) -> Result<InstanceState, anyhow::Err
                     "http_request",
                     method = ?request.method(),
                     matched_path,
+                    peer_id = tracing::field::Empty,
                 )
             })
             .on_response(
@@ -118,9 +242,11 @@ pub async fn setup_instance(config: Config) -> Result<InstanceState, anyhow::Err
         bind_listener_to_free_port().await?
     } else {
         let addr = SocketAddr::from(([127, 0, 0, 1], config.port));
-        tokio::net::TcpListener::bind(&addr).await.map_err(|err| {
-            anyhow::anyhow!("Failed to bind the TCP listener to address {addr}: {err}")
-        })?
+        bind_listener_with_retries(addr, config.bind_retry)
+            .await
+            .map_err(|err| {
+                anyhow::anyhow!("Failed to bind the TCP listener to address {addr}: {err}")
+            })?
     };
 
     let addr = listener.local_addr().unwrap();
@@ -3,6 +3,7 @@ use mpc_exploration::routes::GetHealthcheckResponse;
 
 mod common;
 use common::{default_test_config, setup_instance};
+use mpc_exploration::Config;
 
 #[tokio::test]
 async fn test_healthcheck() {
@@ -14,3 +15,24 @@ async fn test_healthcheck() {
     assert_eq!(response.status(), StatusCode::OK);
     assert!(response.json::<GetHealthcheckResponse>().await.unwrap().ok);
 }
+
+#[tokio::test]
+async fn test_healthcheck_reports_stale_background_tasks() {
+    // The orchestrator is only pinged once in the test, its heartbeat then becomes stale
+    let instance_state = setup_instance(Config {
+        orchestrator_interval: std::time::Duration::from_secs(3600),
+        heartbeat_timeout: std::time::Duration::from_millis(500),
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    let response = reqwest::get(format!("{}/health", &instance_state.server_url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let health = response.json::<GetHealthcheckResponse>().await.unwrap();
+    assert!(!health.ok);
+    assert_eq!(health.stale_tasks, vec!["orchestrator".to_string()]);
+}
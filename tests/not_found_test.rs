@@ -1,4 +1,5 @@
 use axum::http::StatusCode;
+use mpc_exploration::routes::addition::CreateProcessHttpBody;
 mod common;
 use common::{default_test_config, setup_instance};
 
@@ -12,3 +13,49 @@ async fn test_not_found() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
     assert_eq!(response.text().await.unwrap(), "Not found");
 }
+
+#[tokio::test]
+async fn test_unknown_process_is_not_found() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/additions/{}",
+        &instance_state.server_url,
+        uuid::Uuid::new_v4()
+    );
+
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = client.delete(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_existing_process_is_a_conflict() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let body = CreateProcessHttpBody {
+        process_id: uuid::Uuid::new_v4(),
+        input: None,
+        signed_input: None,
+        external_key: None,
+        priority: None,
+    };
+
+    let response = client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
@@ -0,0 +1,778 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use axum::http::StatusCode;
+use mpc_exploration::{
+    Config, Peer,
+    domains::additions::AdditionProcess,
+    peer_communication::{
+        PeerMessagePayload,
+        peer_client::{
+            AdditionProcessProgress, AdditionProcessProgressBatchItem,
+            AdditionProcessProgressQuery, HttpPeerClient, HttpPeerClientOptions, PeerClient,
+        },
+    },
+    routes::{
+        GetPeersHealthResponse, GetPeersResponse,
+        addition::{CreateProcessHttpBody, CreatedProcessResponse},
+        admin::{BlockedPeersResponse, ProcessesExport},
+    },
+};
+
+mod common;
+use common::{ADMIN_TOKEN, default_test_config, setup_instance};
+
+#[tokio::test]
+async fn test_get_peers() {
+    let config = default_test_config();
+    let expected_peers = config
+        .peers
+        .iter()
+        .map(|peer| (peer.id, peer.url.clone()))
+        .collect::<Vec<_>>();
+    let instance_state = setup_instance(config).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/peers", &instance_state.server_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(format!("{}/peers", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.json::<GetPeersResponse>().await.unwrap();
+    assert_eq!(body.server_peer_id, 1);
+    assert_eq!(
+        body.peers
+            .into_iter()
+            .map(|peer| (peer.id, peer.url))
+            .collect::<Vec<_>>(),
+        expected_peers
+    );
+}
+
+#[tokio::test]
+async fn test_remove_peer() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let create_process = || async {
+        client
+            .post(format!("{}/additions", &instance_state.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id: uuid::Uuid::new_v4(),
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap()
+            .json::<CreatedProcessResponse>()
+            .await
+            .unwrap()
+            .process_id
+    };
+
+    let in_flight_process_id = create_process().await;
+
+    let remove_peer_url = format!("{}/admin/peers/3", &instance_state.server_url);
+    let response = client.delete(&remove_peer_url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = client
+        .delete(&remove_peer_url)
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = client
+        .delete(&remove_peer_url)
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    // The last peer can not be removed
+    let response = client
+        .delete(format!("{}/admin/peers/2", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let peers = client
+        .get(format!("{}/peers", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<GetPeersResponse>()
+        .await
+        .unwrap();
+    assert_eq!(
+        peers.peers.iter().map(|peer| peer.id).collect::<Vec<_>>(),
+        vec![2]
+    );
+
+    let new_process_id = create_process().await;
+
+    let export = client
+        .get(format!("{}/admin/export", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    let participants = |process_id: uuid::Uuid| -> HashSet<u8> {
+        export
+            .processes
+            .iter()
+            .find(|process| process.id() == process_id)
+            .map(AdditionProcess::input_shares)
+            .unwrap()
+            .peer_ids()
+    };
+    assert_eq!(participants(in_flight_process_id), HashSet::from([2, 3]));
+    assert_eq!(participants(new_process_id), HashSet::from([2]));
+}
+
+#[tokio::test]
+async fn test_create_process_reports_pending_peers() {
+    // A misconfigured peer sharing the server's own peer ID, recording the requests it receives
+    let self_requests = Arc::new(AtomicUsize::new(0));
+    let app = axum::Router::new().fallback({
+        let self_requests = self_requests.clone();
+        move || async move {
+            self_requests.fetch_add(1, Ordering::SeqCst);
+            StatusCode::OK
+        }
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let self_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let config = default_test_config();
+    let mut peers = config.peers.clone();
+    peers.push(Peer::new(config.server_peer_id, self_url));
+    let relayer_interval = config.relayer_interval;
+    let instance_state = setup_instance(Config { peers, ..config }).await.unwrap();
+
+    let created_process = reqwest::Client::new()
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap();
+    assert_eq!(created_process.notified_peers, vec![2, 3]);
+    assert_eq!(created_process.pending_peers, vec![1]);
+
+    // No message to the server's own peer ID is enqueued, the relayer never delivers one
+    tokio::time::sleep(relayer_interval * 2).await;
+    assert_eq!(self_requests.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_shares_sum_is_only_pushed_to_peers_seen_in_shares_sum_round() {
+    // Peers played by the test, recording the progress pushed to them
+    let pushed_payloads = Arc::new(Mutex::new(Vec::<(u8, PeerMessagePayload)>::new()));
+    let mut peers = vec![];
+    for peer_id in [2, 3] {
+        let pushed_payloads = pushed_payloads.clone();
+        let app = axum::Router::new().route(
+            "/additions/{id}/receive",
+            axum::routing::post(
+                move |axum::Json(payload): axum::Json<PeerMessagePayload>| async move {
+                    pushed_payloads.lock().unwrap().push((peer_id, payload));
+                    StatusCode::OK
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        peers.push(Peer::new(
+            peer_id,
+            format!("http://{}", listener.local_addr().unwrap()),
+        ));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    }
+    let config = Config {
+        peers,
+        ..default_test_config()
+    };
+    let relayer_interval = config.relayer_interval;
+    let instance_state = setup_instance(config).await.unwrap();
+    let client = reqwest::Client::new();
+    let process_id = client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap()
+        .process_id;
+
+    // Peer 3 has computed its shares sum, it polls the server while the server still collects shares
+    let progress = client
+        .get(format!(
+            "{}/additions/{process_id}/progress?shares_sum=7",
+            &instance_state.server_url
+        ))
+        .header("X-PEER-ID", "3")
+        .send()
+        .await
+        .unwrap()
+        .json::<AdditionProcessProgress>()
+        .await
+        .unwrap();
+    assert_eq!(progress.shares_sum, None);
+    // Peer 2 still awaits shares, it only pushes its own share
+    for peer_id in ["2", "3"] {
+        let response = client
+            .post(format!(
+                "{}/additions/{process_id}/receive",
+                &instance_state.server_url
+            ))
+            .header("X-PEER-ID", peer_id)
+            .json(&PeerMessagePayload::Share { value: 0 })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    tokio::time::sleep(relayer_interval * 2).await;
+    let shares_sum_receivers = pushed_payloads
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, payload)| matches!(payload, PeerMessagePayload::SharesSum { .. }))
+        .map(|(peer_id, _)| *peer_id)
+        .collect::<Vec<_>>();
+    assert_eq!(shares_sum_receivers, vec![3]);
+}
+
+#[tokio::test]
+async fn test_peer_client_targets_registered_routes() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let process_id = reqwest::Client::new()
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap()
+        .process_id;
+
+    // The client of peer 2, with the instance as peer 1
+    let peer_client = HttpPeerClient::new(
+        2,
+        &[Peer::new(1, instance_state.server_url.clone())],
+        HttpPeerClientOptions::default(),
+    )
+    .unwrap();
+    peer_client
+        .notify_process_progress(1, process_id)
+        .await
+        .unwrap();
+    peer_client
+        .fetch_process_progress(
+            1,
+            process_id,
+            AdditionProcessProgressQuery {
+                shares_sum: None,
+                sent_share: None,
+            },
+        )
+        .await
+        .unwrap();
+    peer_client
+        .push_process_progress(1, process_id, PeerMessagePayload::Share { value: 0 })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_progress_batch_returns_progress_of_each_process() {
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+    let client = reqwest::Client::new();
+    let mut process_ids = vec![];
+    for _ in 0..3 {
+        let process_id = client
+            .post(format!("{}/additions", &instance_state.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id: uuid::Uuid::new_v4(),
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap()
+            .json::<CreatedProcessResponse>()
+            .await
+            .unwrap()
+            .process_id;
+        process_ids.push(process_id);
+    }
+    let export = client
+        .get(format!("{}/admin/export", &instance_state.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+
+    // The client of peer 2, with the instance as peer 1
+    let peer_client = HttpPeerClient::new(
+        2,
+        &[Peer::new(1, instance_state.server_url.clone())],
+        HttpPeerClientOptions::default(),
+    )
+    .unwrap();
+    let unknown_process_id = uuid::Uuid::new_v4();
+    let items = process_ids
+        .iter()
+        .chain([&unknown_process_id])
+        .map(|process_id| AdditionProcessProgressBatchItem {
+            process_id: *process_id,
+            query: AdditionProcessProgressQuery {
+                shares_sum: None,
+                sent_share: None,
+            },
+        })
+        .collect();
+    let mut progresses = peer_client
+        .fetch_process_progress_batch(1, items)
+        .await
+        .unwrap();
+
+    assert_eq!(progresses.len(), process_ids.len() + 1);
+    assert!(progresses.remove(&unknown_process_id).unwrap().is_err());
+    for process in &export.processes {
+        let progress = progresses.remove(&process.id()).unwrap().unwrap();
+        assert_eq!(progress.share, process.input_shares().shares_to_send[&2]);
+        assert_eq!(progress.shares_sum, None);
+    }
+}
+
+#[tokio::test]
+async fn test_flooding_peer_is_rate_limited_per_process() {
+    let instance_state = setup_instance(Config {
+        peer_process_rate_limit: 5,
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let mut process_ids = vec![];
+    for _ in 0..2 {
+        let process_id = uuid::Uuid::new_v4();
+        client
+            .post(format!("{}/additions", &instance_state.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id,
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+            .send()
+            .await
+            .unwrap();
+        process_ids.push(process_id);
+    }
+    let fetch_progress = |process_id: uuid::Uuid, peer_id: &'static str| {
+        client
+            .get(format!(
+                "{}/additions/{}/progress",
+                &instance_state.server_url, process_id
+            ))
+            .header("X-PEER-ID", peer_id)
+            .send()
+    };
+
+    let mut statuses = vec![];
+    for _ in 0..20 {
+        statuses.push(fetch_progress(process_ids[0], "2").await.unwrap().status());
+    }
+    assert!(statuses.contains(&StatusCode::OK));
+    assert!(statuses.contains(&StatusCode::TOO_MANY_REQUESTS));
+
+    // Other peers and other processes are not throttled
+    let response = fetch_progress(process_ids[0], "3").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = fetch_progress(process_ids[1], "2").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_undecodable_peer_progress_is_surfaced_and_not_retried_away() {
+    // A peer answering progress requests with an incompatible format
+    let app = axum::Router::new().route(
+        "/additions/progress/batch",
+        axum::routing::post(|| async { "[{\"unexpected\": true}]" }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let peer_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let instance_state = setup_instance(Config {
+        peers: vec![Peer::new(2, peer_url)],
+        orchestrator_max_failures: 1,
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+    client
+        .post(format!("{}/additions", &instance_state.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    // The process keeps being polled although it failed more than the maximum failures
+    let mut safe_counter = 0;
+    loop {
+        let health = client
+            .get(format!("{}/health/peers", &instance_state.server_url))
+            .send()
+            .await
+            .unwrap()
+            .json::<GetPeersHealthResponse>()
+            .await
+            .unwrap();
+        assert_eq!(health.peers.len(), 1);
+        if let Some(decode_failures) = &health.peers[0].decode_failures
+            && decode_failures.count >= 3
+        {
+            break;
+        }
+        safe_counter += 1;
+        assert!(safe_counter < 50, "undecodable progress not reported");
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_blocked_peer_requests_are_rejected() {
+    let instance_state = setup_instance(Config {
+        blocked_peer_ids: vec![3],
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+    let notify = |peer_id: u8| {
+        client
+            .post(format!(
+                "{}/additions/progress-notification",
+                &instance_state.server_url
+            ))
+            .header("X-PEER-ID", peer_id.to_string())
+            .send()
+    };
+    let blocked_peers = || async {
+        client
+            .get(format!(
+                "{}/admin/peers/blocked",
+                &instance_state.server_url
+            ))
+            .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap()
+            .json::<BlockedPeersResponse>()
+            .await
+            .unwrap()
+            .peer_ids
+    };
+    let block_url =
+        |peer_id: u8| format!("{}/admin/peers/{peer_id}/block", &instance_state.server_url);
+
+    // Blocked from the configuration
+    assert_eq!(blocked_peers().await, vec![3]);
+    assert_eq!(notify(3).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(notify(2).await.unwrap().status(), StatusCode::OK);
+
+    let response = client.post(block_url(2)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = client
+        .post(block_url(2))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(blocked_peers().await, vec![2, 3]);
+    assert_eq!(notify(2).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .delete(block_url(3))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(blocked_peers().await, vec![2]);
+    assert_eq!(notify(3).await.unwrap().status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_only_coordinator_and_admins_create_processes() {
+    let instance_state = setup_instance(Config {
+        coordinator_peer_id: Some(2),
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+    let create = || {
+        client
+            .post(format!("{}/additions", &instance_state.server_url))
+            .json(&CreateProcessHttpBody {
+                process_id: uuid::Uuid::new_v4(),
+                input: None,
+                signed_input: None,
+                external_key: None,
+                priority: None,
+            })
+    };
+
+    assert_eq!(
+        create().send().await.unwrap().status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let response = create().header("X-PEER-ID", "3").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = create()
+        .header("X-ADMIN-TOKEN", "invalid-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = create().header("X-PEER-ID", "2").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = create()
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_invalid_peer_payloads_are_rejected() {
+    let lenient_instance = setup_instance(default_test_config()).await.unwrap();
+    let strict_instance = setup_instance(Config {
+        strict_peer_payloads: true,
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+    let push = |server_url: &str, payload: serde_json::Value| {
+        client
+            .post(format!(
+                "{server_url}/additions/{}/receive",
+                uuid::Uuid::new_v4()
+            ))
+            .header("X-PEER-ID", "2")
+            .json(&payload)
+            .send()
+    };
+
+    for instance in [&lenient_instance, &strict_instance] {
+        let response = push(
+            &instance.server_url,
+            serde_json::json!({ "type": "new_process", "value": 1 }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(
+            response
+                .text()
+                .await
+                .unwrap()
+                .contains("unknown variant `new_process`")
+        );
+    }
+
+    let extra_field_payload = serde_json::json!({ "type": "share", "value": 1, "extra": true });
+    // Unknown fields are ignored by default, the process is then looked up
+    let response = push(&lenient_instance.server_url, extra_field_payload.clone())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = push(&strict_instance.server_url, extra_field_payload)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.text().await.unwrap(),
+        "invalid peer payload: unknown field `extra`"
+    );
+
+    // Nested unknown fields of a batch are reported with their path
+    let response = client
+        .post(format!(
+            "{}/additions/progress/batch",
+            &strict_instance.server_url
+        ))
+        .header("X-PEER-ID", "2")
+        .json(&serde_json::json!([
+            { "process_id": uuid::Uuid::new_v4() },
+            { "process_id": uuid::Uuid::new_v4(), "extra": 1 },
+        ]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.text().await.unwrap(),
+        "invalid peer payload: unknown field `[1].extra`"
+    );
+}
+
+#[tokio::test]
+async fn test_share_of_a_peer_outside_the_participants_is_rejected() {
+    let source = setup_instance(default_test_config()).await.unwrap();
+    // Peer 4 is added to the configuration after the creation of the process
+    let destination = setup_instance(Config {
+        peers: vec![
+            Peer::new(2, "http://localhost:3001".to_string()),
+            Peer::new(3, "http://localhost:3002".to_string()),
+            Peer::new(4, "http://localhost:3003".to_string()),
+        ],
+        ..default_test_config()
+    })
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+
+    let process_id = client
+        .post(format!("{}/additions", &source.server_url))
+        .json(&CreateProcessHttpBody {
+            process_id: uuid::Uuid::new_v4(),
+            input: None,
+            signed_input: None,
+            external_key: None,
+            priority: None,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<CreatedProcessResponse>()
+        .await
+        .unwrap()
+        .process_id;
+    let export = client
+        .get(format!("{}/admin/export", &source.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!("{}/admin/import", &destination.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .json(&export)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let push = |peer_id: &'static str| {
+        client
+            .post(format!(
+                "{}/additions/{process_id}/receive",
+                &destination.server_url
+            ))
+            .header("X-PEER-ID", peer_id)
+            .json(&PeerMessagePayload::Share { value: 1 })
+            .send()
+    };
+    let response = push("4").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.text().await.unwrap(),
+        "peer 4 does not participate in the process"
+    );
+
+    // The share of a participant is counted
+    let response = push("2").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let process = client
+        .get(format!("{}/admin/export", &destination.server_url))
+        .header("X-ADMIN-TOKEN", ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json::<ProcessesExport>()
+        .await
+        .unwrap()
+        .processes
+        .into_iter()
+        .find(|p| p.id() == process_id)
+        .unwrap();
+    match process {
+        AdditionProcess::AwaitingPeerShares(p) => {
+            assert_eq!(p.received_shares.keys().copied().collect::<Vec<_>>(), [2]);
+        }
+        _ => panic!("expected the process to await the share of peer 3"),
+    }
+}
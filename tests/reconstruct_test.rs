@@ -0,0 +1,44 @@
+use std::process::Command;
+
+use mpc_exploration::mpc::{Share, split_secret};
+
+const PRIME: u64 = 1_000_000_007;
+
+/// Writes the shares to a temporary file and runs the `reconstruct` binary on it.
+fn run_reconstruct(shares: &[Share]) -> std::process::Output {
+    let path = std::env::temp_dir().join(format!("shares-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, serde_json::to_string(shares).unwrap()).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_reconstruct"))
+        .arg(format!("file={}", path.display()))
+        .arg(format!("prime={PRIME}"))
+        .output()
+        .unwrap();
+    std::fs::remove_file(path).unwrap();
+    output
+}
+
+#[test]
+fn test_reconstruct_prints_the_secret() {
+    let shares = split_secret(424_242, &[1, 2, 3, 4], None, PRIME)
+        .unwrap()
+        .into_iter()
+        .map(|(point, value)| Share { point, value })
+        .collect::<Vec<_>>();
+
+    let output = run_reconstruct(&shares);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "424242");
+}
+
+#[test]
+fn test_reconstruct_reports_duplicate_points() {
+    let shares = vec![Share { point: 1, value: 5 }, Share { point: 1, value: 7 }];
+
+    let output = run_reconstruct(&shares);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("duplicate point 1")
+    );
+}
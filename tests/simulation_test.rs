@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use mpc_exploration::simulation::SimulatedNetwork;
+
+mod common;
+use common::default_test_config;
+
+#[tokio::test]
+async fn test_simulated_peers_complete_an_addition() {
+    let network = SimulatedNetwork::start(&default_test_config(), 3);
+
+    let process_id = uuid::Uuid::new_v4();
+    network.create_process(process_id).await.unwrap();
+    let completed_processes = network
+        .wait_for_completion(process_id, Duration::from_secs(10))
+        .await
+        .unwrap();
+
+    assert_eq!(completed_processes.len(), 3);
+    let expected_sum = completed_processes
+        .iter()
+        .map(|process| process.input)
+        .sum::<u64>()
+        % 1_000_000_007;
+    for process in &completed_processes {
+        assert_eq!(process.sum, Some(expected_sum));
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::{Arc, Mutex};
+
+use mpc_exploration::peer_communication::paths;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
+
+mod common;
+use common::{default_test_config, setup_instance};
+
+/// Logs written by the subscriber, shared with the test.
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_peer_requests_are_logged_with_the_peer_id() {
+    // Installed before the instance, whose own subscriber is then ignored
+    let logs = CapturedLogs::default();
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer({
+                    let logs = logs.clone();
+                    move || logs.clone()
+                })
+                .with_filter(LevelFilter::INFO),
+        )
+        .init();
+    let instance_state = setup_instance(default_test_config()).await.unwrap();
+
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}{}",
+            &instance_state.server_url,
+            paths::addition_path(paths::PROGRESS_NOTIFICATION)
+        ))
+        .header("X-PEER-ID", "2")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    let response_log = logs
+        .lines()
+        .find(|line| line.contains("response: 200"))
+        .expect("the response is logged");
+    assert!(response_log.contains("peer_id=2"), "{response_log}");
+}